@@ -0,0 +1,767 @@
+#![forbid(unsafe_code)]
+//! Command line tool for the Collection program.
+
+mod output;
+
+use {
+    crate::output::{CliCollection, CliCollections, CliMember, CliSignature, OutputFormat},
+    clap::{crate_description, crate_name, crate_version, App, AppSettings, Arg, ArgMatches, SubCommand},
+    collection::{
+        id,
+        instruction::{
+            close_collection_account, create_collection_account, include_token, light_up_stars, remove_token,
+            update_collection_account_with_symbol, withdraw, CreateCollectionAccountArgs,
+            UpdateCollectionAccountArgs, CLEAR_BANNER, CLEAR_HEADER_IMAGE, CLEAR_SHORT_DESCRIPTION, CLEAR_TAGS,
+        },
+        utils::{get_index_account, get_position_account, get_treasury_account},
+    },
+    collection_client::CollectionClient,
+    solana_clap_utils::{
+        input_parsers::{keypair_of, lamports_of_sol, pubkey_of, value_of, values_of},
+        input_validators::{
+            is_amount, is_keypair, is_parsable, is_pubkey, is_url_or_moniker, is_valid_signer,
+            normalize_to_url_if_moniker,
+        },
+        keypair::signer_from_path,
+    },
+    solana_client::rpc_client::RpcClient,
+    solana_sdk::{
+        commitment_config::CommitmentConfig,
+        instruction::Instruction,
+        pubkey::Pubkey,
+        signature::{Keypair, Signature, Signer},
+        transaction::Transaction,
+    },
+    spl_associated_token_account::get_associated_token_address,
+    std::{error::Error, process::exit},
+};
+
+type CommandResult = Result<String, Box<dyn Error>>;
+
+/// Optional fields `update --clear` resets, with their `CLEAR_*` flag.
+const CLEARABLE_FIELDS: [(&str, u8); 4] = [
+    ("header-image", CLEAR_HEADER_IMAGE),
+    ("short-description", CLEAR_SHORT_DESCRIPTION),
+    ("banner", CLEAR_BANNER),
+    ("tags", CLEAR_TAGS),
+];
+
+struct Config {
+    client: CollectionClient<RpcClient>,
+    output_format: OutputFormat,
+}
+
+impl Config {
+    /// Signs and sends `instructions`, `signer` pays the fee.
+    fn send(
+        &self,
+        instructions: &[Instruction],
+        signer: &dyn Signer,
+        extra_signers: &[&Keypair],
+    ) -> Result<Signature, Box<dyn Error>> {
+        let rpc_client = self.client.source();
+        let mut signers = vec![signer];
+        signers.extend(extra_signers.iter().map(|keypair| *keypair as &dyn Signer));
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&signer.pubkey()),
+            &signers,
+            rpc_client.get_latest_blockhash()?,
+        );
+        Ok(rpc_client.send_and_confirm_transaction(&transaction)?)
+    }
+
+    fn signature(&self, signature: Signature, address: Option<Pubkey>) -> String {
+        self.output_format.formatted_string(&CliSignature::new(signature, address))
+    }
+}
+
+fn command_create(
+    config: &Config,
+    signer: &dyn Signer,
+    collection_keypair: Keypair,
+    args: CreateCollectionAccountArgs,
+) -> CommandResult {
+    let collection = collection_keypair.pubkey();
+    let instruction = create_collection_account(id(), collection, signer.pubkey(), args);
+    let signature = config.send(&[instruction], signer, &[&collection_keypair])?;
+    Ok(config.signature(signature, Some(collection)))
+}
+
+fn command_update(
+    config: &Config,
+    signer: &dyn Signer,
+    collection: Pubkey,
+    args: UpdateCollectionAccountArgs,
+) -> CommandResult {
+    // a new symbol moves the reservation of the current one
+    let collection_data = config.client.get_collection(&collection)?;
+    let instruction =
+        update_collection_account_with_symbol(id(), collection, signer.pubkey(), &collection_data.symbol, args);
+    let signature = config.send(&[instruction], signer, &[])?;
+    Ok(config.signature(signature, None))
+}
+
+fn command_include(
+    config: &Config,
+    signer: &dyn Signer,
+    collection: Pubkey,
+    mint: Pubkey,
+    token_account: Option<Pubkey>,
+) -> CommandResult {
+    let authority = signer.pubkey();
+    let index = config.client.get_next_index(&collection)?;
+    let token_account = token_account.unwrap_or_else(|| get_associated_token_address(&authority, &mint));
+    let (index_account, _) = get_index_account(&collection, &mint);
+    let (position_account, _) = get_position_account(&collection, index);
+    let instruction = include_token(
+        id(),
+        collection,
+        authority,
+        mint,
+        token_account,
+        index_account,
+        authority,
+        position_account,
+    );
+    let signature = config.send(&[instruction], signer, &[])?;
+    Ok(config.signature(signature, Some(index_account)))
+}
+
+fn command_remove(config: &Config, signer: &dyn Signer, collection: Pubkey, mint: Pubkey) -> CommandResult {
+    let authority = signer.pubkey();
+    let member = config
+        .client
+        .get_member(&collection, &mint)?
+        .ok_or_else(|| format!("Token {} is not included in collection {}", mint, collection))?;
+    // the last token of the collection moves into the position being freed
+    let last_index = config.client.get_next_index(&collection)?.saturating_sub(1);
+    let last_mint = config
+        .client
+        .get_position(&collection, last_index)?
+        .ok_or_else(|| format!("Position {} of collection {} not found", last_index, collection))?
+        .mint;
+    let instruction = remove_token(
+        id(),
+        collection,
+        authority,
+        get_index_account(&collection, &mint).0,
+        authority,
+        get_position_account(&collection, member.index).0,
+        get_position_account(&collection, last_index).0,
+        get_index_account(&collection, &last_mint).0,
+    );
+    let signature = config.send(&[instruction], signer, &[])?;
+    Ok(config.signature(signature, None))
+}
+
+fn command_star(config: &Config, signer: &dyn Signer, collection: Pubkey, amount: u64) -> CommandResult {
+    let (treasury_account, _) = get_treasury_account();
+    let instruction = light_up_stars(id(), collection, signer.pubkey(), treasury_account, amount);
+    let signature = config.send(&[instruction], signer, &[])?;
+    Ok(config.signature(signature, None))
+}
+
+fn command_close(
+    config: &Config,
+    signer: &dyn Signer,
+    collection: Pubkey,
+    recipient: Option<Pubkey>,
+) -> CommandResult {
+    let authority = signer.pubkey();
+    let recipient = recipient.unwrap_or(authority);
+    let collection_data = config.client.get_collection(&collection)?;
+    // the symbol reserved by the collection is released along with it
+    let instruction = close_collection_account(id(), collection, recipient, authority, &collection_data.symbol);
+    let signature = config.send(&[instruction], signer, &[])?;
+    Ok(config.signature(signature, None))
+}
+
+fn command_withdraw(
+    config: &Config,
+    signer: &dyn Signer,
+    recipient: Option<Pubkey>,
+    amount: Option<u64>,
+) -> CommandResult {
+    let (treasury_account, _) = get_treasury_account();
+    let recipient = recipient.unwrap_or_else(|| signer.pubkey());
+    let instruction = withdraw(id(), signer.pubkey(), treasury_account, recipient, amount);
+    let signature = config.send(&[instruction], signer, &[])?;
+    Ok(config.signature(signature, None))
+}
+
+fn command_show(config: &Config, collection: Pubkey) -> CommandResult {
+    let collection_data = config.client.get_collection(&collection)?;
+    let members = config
+        .client
+        .get_members(&collection)?
+        .into_iter()
+        .map(|(address, data)| CliMember {
+            address: address.to_string(),
+            mint: data.mint.to_string(),
+            index: data.index,
+        })
+        .collect();
+    let mut cli_collection = CliCollection::new(&collection, collection_data);
+    cli_collection.members = Some(members);
+    Ok(config.output_format.formatted_string(&cli_collection))
+}
+
+fn command_list(config: &Config, authority: Option<Pubkey>) -> CommandResult {
+    let collections = match authority {
+        Some(authority) => config.client.get_collections_by_authority(&authority)?,
+        None => config.client.get_collections()?,
+    };
+    let collections = CliCollections {
+        collections: collections
+            .into_iter()
+            .map(|(address, data)| CliCollection::new(&address, data))
+            .collect(),
+    };
+    Ok(config.output_format.formatted_string(&collections))
+}
+
+fn collection_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("collection")
+        .value_name("COLLECTION_ADDRESS")
+        .validator(is_pubkey)
+        .takes_value(true)
+        .required(true)
+        .index(1)
+        .help("Address of the collection")
+}
+
+fn mint_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("mint")
+        .value_name("MINT_ADDRESS")
+        .validator(is_pubkey)
+        .takes_value(true)
+        .required(true)
+        .index(2)
+        .help("Mint of the token")
+}
+
+fn recipient_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("recipient")
+        .long("recipient")
+        .value_name("RECIPIENT_ADDRESS")
+        .validator(is_pubkey)
+        .takes_value(true)
+        .help("Account receiving the lamports [default: the keypair]")
+}
+
+/// Metadata fields shared by `create` and `update`, required by `create`.
+fn metadata_args<'a, 'b>(app: App<'a, 'b>, required: bool) -> App<'a, 'b> {
+    let text_arg = |name: &'a str, help: &'a str| {
+        Arg::with_name(name)
+            .long(name)
+            .value_name("TEXT")
+            .takes_value(true)
+            .help(help)
+    };
+    app.arg(text_arg("title", "Title of the collection").required(required))
+        .arg(text_arg("symbol", "Symbol of the collection").required(required))
+        .arg(text_arg("description", "Description of the collection").required(required))
+        .arg(text_arg("icon-image", "Url of the icon image").required(required))
+        .arg(text_arg("header-image", "Url of the header image"))
+        .arg(text_arg("short-description", "Short description of the collection"))
+        .arg(text_arg("banner", "Url of the banner image"))
+        .arg(
+            text_arg("tag", "Tag of the collection, may be repeated")
+                .multiple(true)
+                .number_of_values(1),
+        )
+        .arg(
+            Arg::with_name("max-supply")
+                .long("max-supply")
+                .value_name("AMOUNT")
+                .validator(is_parsable::<u64>)
+                .takes_value(true)
+                .help("Maximum number of tokens in the collection"),
+        )
+}
+
+fn app<'a, 'b>() -> App<'a, 'b> {
+    App::new(crate_name!())
+        .about(crate_description!())
+        .version(crate_version!())
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .arg({
+            let arg = Arg::with_name("config_file")
+                .short("C")
+                .long("config")
+                .value_name("PATH")
+                .takes_value(true)
+                .global(true)
+                .help("Configuration file to use");
+            if let Some(ref config_file) = *solana_cli_config::CONFIG_FILE {
+                arg.default_value(config_file)
+            } else {
+                arg
+            }
+        })
+        .arg(
+            Arg::with_name("json_rpc_url")
+                .short("u")
+                .long("url")
+                .value_name("URL_OR_MONIKER")
+                .takes_value(true)
+                .global(true)
+                .validator(is_url_or_moniker)
+                .help("JSON RPC URL for the cluster [default: value from configuration file], \
+                    or a moniker: mainnet-beta, testnet, devnet or localhost"),
+        )
+        .arg(
+            Arg::with_name("keypair")
+                .short("k")
+                .long("keypair")
+                .value_name("KEYPAIR")
+                .takes_value(true)
+                .global(true)
+                .validator(is_valid_signer)
+                .help("Authority and fee payer [default: value from configuration file]"),
+        )
+        .arg(
+            Arg::with_name("output_format")
+                .long("output")
+                .value_name("FORMAT")
+                .takes_value(true)
+                .global(true)
+                .possible_values(&["json", "json-compact"])
+                .help("Return information in specified output format"),
+        )
+        .subcommand(metadata_args(
+            SubCommand::with_name("create")
+                .about("Create a new collection")
+                .arg(
+                    Arg::with_name("collection_keypair")
+                        .long("collection-keypair")
+                        .value_name("KEYPAIR")
+                        .validator(is_keypair)
+                        .takes_value(true)
+                        .help("Keypair of the new collection [default: randomly generated]"),
+                ),
+            true,
+        ))
+        .subcommand(metadata_args(
+            SubCommand::with_name("update")
+                .about("Update the metadata of a collection")
+                .arg(collection_arg())
+                .arg(
+                    Arg::with_name("clear")
+                        .long("clear")
+                        .value_name("FIELD")
+                        .possible_values(&CLEARABLE_FIELDS.iter().map(|(field, _)| *field).collect::<Vec<_>>())
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1)
+                        .help("Optional field to remove, may be repeated"),
+                ),
+            false,
+        ))
+        .subcommand(
+            SubCommand::with_name("include")
+                .about("Include a token in a collection")
+                .arg(collection_arg())
+                .arg(mint_arg())
+                .arg(
+                    Arg::with_name("token_account")
+                        .long("token-account")
+                        .value_name("TOKEN_ACCOUNT_ADDRESS")
+                        .validator(is_pubkey)
+                        .takes_value(true)
+                        .help("Token account holding the token [default: associated token account of the keypair]"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("remove")
+                .about("Remove a token from a collection")
+                .arg(collection_arg())
+                .arg(mint_arg()),
+        )
+        .subcommand(
+            SubCommand::with_name("star")
+                .about("Light up stars of a collection")
+                .arg(collection_arg())
+                .arg(
+                    Arg::with_name("amount")
+                        .value_name("AMOUNT")
+                        .validator(is_parsable::<u64>)
+                        .takes_value(true)
+                        .required(true)
+                        .index(2)
+                        .help("Number of stars"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("close")
+                .about("Close an empty collection")
+                .arg(collection_arg())
+                .arg(recipient_arg()),
+        )
+        .subcommand(
+            SubCommand::with_name("withdraw")
+                .about("Withdraw from the treasury, the keypair must be the treasury manager")
+                .arg(recipient_arg())
+                .arg(
+                    Arg::with_name("amount")
+                        .long("amount")
+                        .value_name("SOL")
+                        .validator(is_amount)
+                        .takes_value(true)
+                        .help("Amount to withdraw [default: everything above the rent exempt minimum]"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("show")
+                .about("Show a collection and its members")
+                .arg(collection_arg()),
+        )
+        .subcommand(
+            SubCommand::with_name("list")
+                .about("List the collections of an authority")
+                .arg(
+                    Arg::with_name("authority")
+                        .long("authority")
+                        .value_name("AUTHORITY_ADDRESS")
+                        .validator(is_pubkey)
+                        .takes_value(true)
+                        .conflicts_with("all")
+                        .help("Authority of the collections [default: the keypair]"),
+                )
+                .arg(
+                    Arg::with_name("all")
+                        .long("all")
+                        .takes_value(false)
+                        .help("List the collections of every authority"),
+                ),
+        )
+}
+
+fn update_args(matches: &ArgMatches) -> UpdateCollectionAccountArgs {
+    UpdateCollectionAccountArgs {
+        title: value_of(matches, "title"),
+        symbol: value_of(matches, "symbol"),
+        description: value_of(matches, "description"),
+        icon_image: value_of(matches, "icon-image"),
+        header_image: value_of(matches, "header-image"),
+        short_description: value_of(matches, "short-description"),
+        banner: value_of(matches, "banner"),
+        tags: values_of(matches, "tag"),
+        max_supply: value_of(matches, "max-supply"),
+        clear: values_of::<String>(matches, "clear")
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|field| CLEARABLE_FIELDS.iter().find(|(name, _)| name == field))
+            .fold(0, |clear, (_, flag)| clear | flag),
+    }
+}
+
+fn create_args(matches: &ArgMatches) -> CreateCollectionAccountArgs {
+    let args = update_args(matches);
+    CreateCollectionAccountArgs {
+        title: args.title.unwrap(),
+        symbol: args.symbol.unwrap(),
+        description: args.description.unwrap(),
+        icon_image: args.icon_image.unwrap(),
+        header_image: args.header_image,
+        short_description: args.short_description,
+        banner: args.banner,
+        tags: args.tags,
+        max_supply: args.max_supply,
+    }
+}
+
+fn process_command(matches: &ArgMatches) -> CommandResult {
+    let cli_config = match matches.value_of("config_file") {
+        Some(config_file) => solana_cli_config::Config::load(config_file).unwrap_or_default(),
+        None => solana_cli_config::Config::default(),
+    };
+    let json_rpc_url =
+        normalize_to_url_if_moniker(matches.value_of("json_rpc_url").unwrap_or(&cli_config.json_rpc_url));
+    let commitment = cli_config.commitment.parse().unwrap_or_else(|_| CommitmentConfig::confirmed());
+    let config = Config {
+        client: CollectionClient::new(RpcClient::new_with_commitment(json_rpc_url, commitment)),
+        output_format: OutputFormat::from_matches(matches.value_of("output_format")),
+    };
+
+    let (command, command_matches) = matches.subcommand();
+    let command_matches = command_matches.unwrap();
+    // read only commands work without a keypair
+    let load_signer = || {
+        let keypair_path = matches.value_of("keypair").unwrap_or(&cli_config.keypair_path);
+        signer_from_path(command_matches, keypair_path, "keypair", &mut None)
+    };
+
+    match command {
+        "create" => {
+            let collection_keypair = keypair_of(command_matches, "collection_keypair").unwrap_or_else(Keypair::new);
+            command_create(&config, load_signer()?.as_ref(), collection_keypair, create_args(command_matches))
+        }
+        "update" => {
+            let collection = pubkey_of(command_matches, "collection").unwrap();
+            command_update(&config, load_signer()?.as_ref(), collection, update_args(command_matches))
+        }
+        "include" => {
+            let collection = pubkey_of(command_matches, "collection").unwrap();
+            let mint = pubkey_of(command_matches, "mint").unwrap();
+            let token_account = pubkey_of(command_matches, "token_account");
+            command_include(&config, load_signer()?.as_ref(), collection, mint, token_account)
+        }
+        "remove" => {
+            let collection = pubkey_of(command_matches, "collection").unwrap();
+            let mint = pubkey_of(command_matches, "mint").unwrap();
+            command_remove(&config, load_signer()?.as_ref(), collection, mint)
+        }
+        "star" => {
+            let collection = pubkey_of(command_matches, "collection").unwrap();
+            let amount = value_of(command_matches, "amount").unwrap();
+            command_star(&config, load_signer()?.as_ref(), collection, amount)
+        }
+        "close" => {
+            let collection = pubkey_of(command_matches, "collection").unwrap();
+            let recipient = pubkey_of(command_matches, "recipient");
+            command_close(&config, load_signer()?.as_ref(), collection, recipient)
+        }
+        "withdraw" => {
+            let recipient = pubkey_of(command_matches, "recipient");
+            let amount = lamports_of_sol(command_matches, "amount");
+            command_withdraw(&config, load_signer()?.as_ref(), recipient, amount)
+        }
+        "show" => {
+            let collection = pubkey_of(command_matches, "collection").unwrap();
+            command_show(&config, collection)
+        }
+        "list" => {
+            let authority = if command_matches.is_present("all") {
+                None
+            } else {
+                match pubkey_of(command_matches, "authority") {
+                    Some(authority) => Some(authority),
+                    None => Some(load_signer()?.pubkey()),
+                }
+            };
+            command_list(&config, authority)
+        }
+        _ => unreachable!(),
+    }
+}
+
+fn main() {
+    let matches = app().get_matches();
+    match process_command(&matches) {
+        Ok(output) => println!("{}", output),
+        Err(error) => {
+            eprintln!("error: {}", error);
+            exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        borsh::BorshSerialize,
+        clap::ErrorKind,
+        collection::state::{AccountType, CollectionAccountData, CollectionIndexAccountData, COLLECTION_ACCOUNT_VERSION},
+        serde_json::Value,
+        solana_account_decoder::{UiAccount, UiAccountEncoding},
+        solana_client::{
+            rpc_client::Mocks,
+            rpc_request::RpcRequest,
+            rpc_response::{Response, RpcKeyedAccount, RpcResponseContext},
+        },
+        solana_sdk::account::Account,
+    };
+
+    fn parse(args: &[&str]) -> Result<ArgMatches<'static>, clap::Error> {
+        app().get_matches_from_safe([crate_name!()].iter().chain(args))
+    }
+
+    fn collection_data(authority: Pubkey) -> CollectionAccountData {
+        CollectionAccountData {
+            account_type: AccountType::VersionedCollectionAccount,
+            version: COLLECTION_ACCOUNT_VERSION,
+            authority,
+            title: "test collection".to_string(),
+            symbol: "tc".to_string(),
+            description: "test collection description".to_string(),
+            icon_image: "https://www.google.com".to_string(),
+            supply: 1,
+            stars: 42,
+            header_image: None,
+            short_description: None,
+            banner: None,
+            tags: Some(vec!["art".to_string()]),
+            pending_authority: None,
+            max_supply: Some(10),
+            metadata_locked: false,
+            membership_locked: false,
+        }
+    }
+
+    fn program_account(data: Vec<u8>) -> Account {
+        Account {
+            lamports: 1_000_000,
+            data,
+            owner: id(),
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    fn response<T: serde::Serialize>(value: T) -> Value {
+        serde_json::to_value(Response {
+            context: RpcResponseContext { slot: 1, api_version: None },
+            value,
+        })
+        .unwrap()
+    }
+
+    fn mock_config(mocks: Mocks, output_format: OutputFormat) -> Config {
+        Config {
+            client: CollectionClient::new(RpcClient::new_mock_with_mocks("succeeds".to_string(), mocks)),
+            output_format,
+        }
+    }
+
+    #[test]
+    fn test_parse_create() {
+        let matches = parse(&[
+            "create",
+            "--title", "test collection",
+            "--symbol", "tc",
+            "--description", "test collection description",
+            "--icon-image", "https://www.google.com",
+            "--tag", "art",
+            "--tag", "music",
+            "--max-supply", "10",
+            "--output", "json-compact",
+        ])
+        .unwrap();
+        assert_eq!(OutputFormat::from_matches(matches.value_of("output_format")), OutputFormat::JsonCompact);
+        let (command, command_matches) = matches.subcommand();
+        assert_eq!(command, "create");
+        assert_eq!(
+            create_args(command_matches.unwrap()),
+            CreateCollectionAccountArgs {
+                title: "test collection".to_string(),
+                symbol: "tc".to_string(),
+                description: "test collection description".to_string(),
+                icon_image: "https://www.google.com".to_string(),
+                header_image: None,
+                short_description: None,
+                banner: None,
+                tags: Some(vec!["art".to_string(), "music".to_string()]),
+                max_supply: Some(10),
+            }
+        );
+
+        // the metadata is required on create only
+        let err = parse(&["create", "--title", "test collection"]).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::MissingRequiredArgument);
+        let collection = Pubkey::new_unique().to_string();
+        let matches = parse(&["update", &collection, "--banner", "https://www.solana.com"]).unwrap();
+        let (_, command_matches) = matches.subcommand();
+        assert_eq!(
+            update_args(command_matches.unwrap()),
+            UpdateCollectionAccountArgs {
+                banner: Some("https://www.solana.com".to_string()),
+                ..UpdateCollectionAccountArgs::default()
+            }
+        );
+        let matches = parse(&["update", &collection, "--clear", "banner", "--clear", "tags"]).unwrap();
+        let (_, command_matches) = matches.subcommand();
+        assert_eq!(update_args(command_matches.unwrap()).clear, CLEAR_BANNER | CLEAR_TAGS);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let collection = Pubkey::new_unique().to_string();
+        assert_eq!(parse(&["show", "not-an-address"]).unwrap_err().kind, ErrorKind::ValueValidation);
+        assert_eq!(parse(&["star", &collection, "many"]).unwrap_err().kind, ErrorKind::ValueValidation);
+        assert_eq!(
+            parse(&["update", &collection, "--max-supply", "ten"]).unwrap_err().kind,
+            ErrorKind::ValueValidation
+        );
+        assert_eq!(parse(&["show", &collection, "--output", "yaml"]).unwrap_err().kind, ErrorKind::InvalidValue);
+        assert_eq!(parse(&["update", &collection, "--clear", "title"]).unwrap_err().kind, ErrorKind::InvalidValue);
+        assert_eq!(
+            parse(&["list", "--all", "--authority", &collection]).unwrap_err().kind,
+            ErrorKind::ArgumentConflict
+        );
+        assert_eq!(parse(&[]).unwrap_err().kind, ErrorKind::MissingArgumentOrSubcommand);
+
+        let matches = parse(&["--output", "json", "list", "--all"]).unwrap();
+        assert_eq!(OutputFormat::from_matches(matches.value_of("output_format")), OutputFormat::Json);
+        assert!(matches.subcommand_matches("list").unwrap().is_present("all"));
+    }
+
+    #[test]
+    fn test_command_show() {
+        let collection = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let (index_account, _) = get_index_account(&collection, &mint);
+        let collection_account = program_account(collection_data(authority).try_to_vec().unwrap());
+        let index_account_data = program_account(CollectionIndexAccountData::new(collection, mint, 0).try_to_vec().unwrap());
+        let mut mocks = Mocks::default();
+        mocks.insert(
+            RpcRequest::GetAccountInfo,
+            response(Some(UiAccount::encode(&collection, &collection_account, UiAccountEncoding::Base64, None, None))),
+        );
+        mocks.insert(
+            RpcRequest::GetProgramAccounts,
+            serde_json::to_value(vec![RpcKeyedAccount {
+                pubkey: index_account.to_string(),
+                account: UiAccount::encode(&index_account, &index_account_data, UiAccountEncoding::Base64, None, None),
+            }])
+            .unwrap(),
+        );
+
+        let output = command_show(&mock_config(mocks, OutputFormat::Json), collection).unwrap();
+        let output: Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(output["address"], collection.to_string());
+        assert_eq!(output["authority"], authority.to_string());
+        assert_eq!(output["title"], "test collection");
+        assert_eq!(output["supply"], 1);
+        assert_eq!(output["maxSupply"], 10);
+        assert_eq!(output["members"][0]["mint"], mint.to_string());
+        assert_eq!(output["members"][0]["index"], 0);
+
+        // a missing collection is reported
+        let err = command_show(&mock_config(Mocks::default(), OutputFormat::Json), collection).unwrap_err();
+        assert!(err.to_string().contains(&collection.to_string()));
+    }
+
+    #[test]
+    fn test_command_create() {
+        let signer = Keypair::new();
+        let collection_keypair = Keypair::new();
+        let collection = collection_keypair.pubkey();
+        let args = create_args(
+            parse(&[
+                "create",
+                "--title", "test collection",
+                "--symbol", "tc",
+                "--description", "test collection description",
+                "--icon-image", "https://www.google.com",
+            ])
+            .unwrap()
+            .subcommand_matches("create")
+            .unwrap(),
+        );
+
+        let output = command_create(
+            &mock_config(Mocks::default(), OutputFormat::JsonCompact),
+            &signer,
+            collection_keypair,
+            args,
+        )
+        .unwrap();
+        let output: Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(output["address"], collection.to_string());
+        assert!(output["signature"].as_str().unwrap().parse::<Signature>().is_ok());
+    }
+}
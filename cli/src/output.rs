@@ -0,0 +1,278 @@
+//! Display and json output of the commands.
+
+use {
+    collection::state::CollectionAccountData,
+    serde::Serialize,
+    solana_sdk::{pubkey::Pubkey, signature::Signature},
+    std::fmt,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OutputFormat {
+    Display,
+    Json,
+    JsonCompact,
+}
+
+impl OutputFormat {
+    pub fn from_matches(value: Option<&str>) -> OutputFormat {
+        match value {
+            Some("json") => OutputFormat::Json,
+            Some("json-compact") => OutputFormat::JsonCompact,
+            _ => OutputFormat::Display,
+        }
+    }
+
+    pub fn formatted_string<T: Serialize + fmt::Display>(&self, item: &T) -> String {
+        match self {
+            OutputFormat::Display => item.to_string(),
+            OutputFormat::Json => serde_json::to_string_pretty(item).unwrap(),
+            OutputFormat::JsonCompact => serde_json::to_string(item).unwrap(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliSignature {
+    /// Account created by the transaction.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+    pub signature: String,
+}
+
+impl CliSignature {
+    pub fn new(signature: Signature, address: Option<Pubkey>) -> CliSignature {
+        CliSignature {
+            address: address.map(|address| address.to_string()),
+            signature: signature.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for CliSignature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(address) = &self.address {
+            writeln!(f, "Address: {}", address)?;
+        }
+        write!(f, "Signature: {}", self.signature)
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliMember {
+    pub address: String,
+    pub mint: String,
+    pub index: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliCollection {
+    pub address: String,
+    pub version: u8,
+    pub authority: String,
+    pub pending_authority: Option<String>,
+    pub title: String,
+    pub symbol: String,
+    pub description: String,
+    pub icon_image: String,
+    pub header_image: Option<String>,
+    pub short_description: Option<String>,
+    pub banner: Option<String>,
+    pub tags: Vec<String>,
+    pub supply: u64,
+    pub max_supply: Option<u64>,
+    pub stars: u64,
+    pub metadata_locked: bool,
+    pub membership_locked: bool,
+    /// Only filled in by `show`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub members: Option<Vec<CliMember>>,
+}
+
+impl CliCollection {
+    pub fn new(address: &Pubkey, data: CollectionAccountData) -> CliCollection {
+        CliCollection {
+            address: address.to_string(),
+            version: data.version,
+            authority: data.authority.to_string(),
+            pending_authority: data.pending_authority.map(|authority| authority.to_string()),
+            title: data.title,
+            symbol: data.symbol,
+            description: data.description,
+            icon_image: data.icon_image,
+            header_image: data.header_image,
+            short_description: data.short_description,
+            banner: data.banner,
+            tags: data.tags.unwrap_or_default(),
+            supply: data.supply,
+            max_supply: data.max_supply,
+            stars: data.stars,
+            metadata_locked: data.metadata_locked,
+            membership_locked: data.membership_locked,
+            members: None,
+        }
+    }
+}
+
+fn writeln_optional(f: &mut fmt::Formatter, name: &str, value: &Option<String>) -> fmt::Result {
+    if let Some(value) = value {
+        writeln!(f, "{}: {}", name, value)?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for CliCollection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Address: {}", self.address)?;
+        writeln!(f, "Version: {}", self.version)?;
+        writeln!(f, "Authority: {}", self.authority)?;
+        writeln_optional(f, "Pending authority", &self.pending_authority)?;
+        writeln!(f, "Title: {}", self.title)?;
+        writeln!(f, "Symbol: {}", self.symbol)?;
+        writeln!(f, "Description: {}", self.description)?;
+        writeln!(f, "Icon image: {}", self.icon_image)?;
+        writeln_optional(f, "Header image", &self.header_image)?;
+        writeln_optional(f, "Short description", &self.short_description)?;
+        writeln_optional(f, "Banner", &self.banner)?;
+        if !self.tags.is_empty() {
+            writeln!(f, "Tags: {}", self.tags.join(", "))?;
+        }
+        match self.max_supply {
+            Some(max_supply) => writeln!(f, "Supply: {} of {}", self.supply, max_supply)?,
+            None => writeln!(f, "Supply: {}", self.supply)?,
+        }
+        writeln!(f, "Stars: {}", self.stars)?;
+        writeln!(f, "Metadata locked: {}", self.metadata_locked)?;
+        write!(f, "Membership locked: {}", self.membership_locked)?;
+        if let Some(members) = &self.members {
+            writeln!(f)?;
+            write!(f, "Members:")?;
+            for member in members {
+                write!(f, "\n  {:<6} {}", member.index, member.mint)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliCollections {
+    pub collections: Vec<CliCollection>,
+}
+
+impl fmt::Display for CliCollections {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:<44}  {:<10}  {:>8}  {:>8}  Title", "Address", "Symbol", "Supply", "Stars")?;
+        for collection in &self.collections {
+            write!(
+                f,
+                "\n{:<44}  {:<10}  {:>8}  {:>8}  {}",
+                collection.address, collection.symbol, collection.supply, collection.stars, collection.title
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        collection::state::{AccountType, COLLECTION_ACCOUNT_VERSION},
+        serde_json::{json, Value},
+    };
+
+    fn cli_collection(address: &Pubkey, authority: &Pubkey) -> CliCollection {
+        CliCollection::new(
+            address,
+            CollectionAccountData {
+                account_type: AccountType::VersionedCollectionAccount,
+                version: COLLECTION_ACCOUNT_VERSION,
+                authority: *authority,
+                title: "test collection".to_string(),
+                symbol: "tc".to_string(),
+                description: "test collection description".to_string(),
+                icon_image: "https://www.google.com".to_string(),
+                supply: 2,
+                stars: 42,
+                header_image: Some("https://www.solana.com".to_string()),
+                short_description: None,
+                banner: None,
+                tags: None,
+                pending_authority: None,
+                max_supply: None,
+                metadata_locked: true,
+                membership_locked: false,
+            },
+        )
+    }
+
+    #[test]
+    fn test_collection_json() {
+        let address = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let collection = cli_collection(&address, &authority);
+        let expected = json!({
+            "address": address.to_string(),
+            "version": COLLECTION_ACCOUNT_VERSION,
+            "authority": authority.to_string(),
+            "pendingAuthority": null,
+            "title": "test collection",
+            "symbol": "tc",
+            "description": "test collection description",
+            "iconImage": "https://www.google.com",
+            "headerImage": "https://www.solana.com",
+            "shortDescription": null,
+            "banner": null,
+            "tags": [],
+            "supply": 2,
+            "maxSupply": null,
+            "stars": 42,
+            "metadataLocked": true,
+            "membershipLocked": false,
+        });
+
+        let pretty = OutputFormat::Json.formatted_string(&collection);
+        assert!(pretty.contains('\n'));
+        assert_eq!(serde_json::from_str::<Value>(&pretty).unwrap(), expected);
+        let compact = OutputFormat::JsonCompact.formatted_string(&collection);
+        assert!(!compact.contains('\n'));
+        assert_eq!(serde_json::from_str::<Value>(&compact).unwrap(), expected);
+
+        // members are only listed once they are filled in
+        let mut collection = collection;
+        collection.members = Some(vec![CliMember { address: address.to_string(), mint: authority.to_string(), index: 0 }]);
+        let output: Value = serde_json::from_str(&OutputFormat::JsonCompact.formatted_string(&collection)).unwrap();
+        assert_eq!(output["members"], json!([{ "address": address.to_string(), "mint": authority.to_string(), "index": 0 }]));
+        let display = OutputFormat::Display.formatted_string(&collection);
+        assert!(display.contains("Supply: 2\n"));
+        assert!(display.ends_with(&format!("Members:\n  0      {}", authority)));
+    }
+
+    #[test]
+    fn test_collections_json() {
+        let authority = Pubkey::new_unique();
+        let addresses = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let collections = CliCollections {
+            collections: addresses.iter().map(|address| cli_collection(address, &authority)).collect(),
+        };
+
+        for format in [OutputFormat::Json, OutputFormat::JsonCompact].iter() {
+            let output: Value = serde_json::from_str(&format.formatted_string(&collections)).unwrap();
+            let output = output["collections"].as_array().unwrap();
+            assert_eq!(output.len(), 2);
+            for (collection, address) in output.iter().zip(addresses.iter()) {
+                assert_eq!(collection["address"], address.to_string());
+                assert_eq!(collection["authority"], authority.to_string());
+                assert!(collection.get("members").is_none());
+            }
+        }
+        let empty = CliCollections { collections: vec![] };
+        assert_eq!(OutputFormat::JsonCompact.formatted_string(&empty), r#"{"collections":[]}"#);
+        assert_eq!(OutputFormat::Display.formatted_string(&collections).lines().count(), 3);
+    }
+}
@@ -0,0 +1,33 @@
+//! Error types
+
+use {
+    collection::state::AccountType,
+    solana_sdk::pubkey::Pubkey,
+    thiserror::Error,
+};
+
+/// Errors that may be returned by the collection client.
+#[derive(Error, Debug)]
+pub enum ClientError {
+    /// Request to the RPC node failed
+    #[error(transparent)]
+    Rpc(#[from] Box<solana_client::client_error::ClientError>),
+    /// Account does not exist
+    #[error("Account {0} not found")]
+    AccountNotFound(Pubkey),
+    /// Account is not owned by the collection program
+    #[error("Account {0} is not owned by the collection program")]
+    InvalidAccountOwner(Pubkey),
+    /// Account holds another type of collection account
+    #[error("Account {0} is not a {1:?}")]
+    InvalidAccountType(Pubkey, AccountType),
+    /// Account data could not be deserialized
+    #[error("Account {0} has invalid data")]
+    InvalidAccountData(Pubkey),
+}
+
+impl From<solana_client::client_error::ClientError> for ClientError {
+    fn from(error: solana_client::client_error::ClientError) -> Self {
+        ClientError::Rpc(Box::new(error))
+    }
+}
@@ -0,0 +1,42 @@
+//! `getProgramAccounts` filters matching the collection program accounts.
+
+use {
+    collection::state::{AccountType, CollectionIndexAccountData, COLLECTION_ACCOUNT_VERSION},
+    solana_client::rpc_filter::{Memcmp, RpcFilterType},
+    solana_sdk::pubkey::Pubkey,
+};
+
+fn memcmp(offset: usize, bytes: Vec<u8>) -> RpcFilterType {
+    RpcFilterType::Memcmp(Memcmp::new_base58_encoded(offset, &bytes))
+}
+
+fn prefixed(prefix: &[u8], address: &Pubkey) -> Vec<u8> {
+    [prefix, address.as_ref()].concat()
+}
+
+/// All collection accounts. Versioned and unversioned accounts are stored under
+/// different account types, so each layout needs its own request.
+pub fn collections() -> Vec<Vec<RpcFilterType>> {
+    vec![
+        vec![memcmp(0, vec![AccountType::VersionedCollectionAccount as u8])],
+        vec![memcmp(0, vec![AccountType::CollectionAccount as u8])],
+    ]
+}
+
+/// Collection accounts of `authority`. The authority follows the version byte
+/// of versioned accounts and the account type of unversioned ones.
+pub fn collections_by_authority(authority: &Pubkey) -> Vec<Vec<RpcFilterType>> {
+    let versioned = [AccountType::VersionedCollectionAccount as u8, COLLECTION_ACCOUNT_VERSION];
+    vec![
+        vec![memcmp(0, prefixed(&versioned, authority))],
+        vec![memcmp(0, prefixed(&[AccountType::CollectionAccount as u8], authority))],
+    ]
+}
+
+/// Index accounts of the tokens included in `collection`.
+pub fn members(collection: &Pubkey) -> Vec<RpcFilterType> {
+    vec![
+        RpcFilterType::DataSize(CollectionIndexAccountData::LEN as u64),
+        memcmp(0, prefixed(&[AccountType::CollectionIndexAccount as u8], collection)),
+    ]
+}
@@ -0,0 +1,208 @@
+#![forbid(unsafe_code)]
+//! RPC client for the Collection program, fetches and decodes the program accounts.
+
+pub mod error;
+pub mod filters;
+
+pub use collection;
+
+use {
+    crate::error::ClientError,
+    collection::{
+        state::{collection_account_type, AccountType, CollectionAccountData, CollectionIndexAccountData, CollectionPositionAccountData, ConfigAccountData, SymbolAccountData},
+        utils::{get_config_account, get_index_account, get_position_account, get_symbol_account, get_treasury_account},
+    },
+    solana_account_decoder::UiAccountEncoding,
+    solana_client::{
+        rpc_client::RpcClient,
+        rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+        rpc_filter::RpcFilterType,
+    },
+    solana_sdk::{account::Account, program_error::ProgramError, pubkey::Pubkey},
+};
+
+pub type ClientResult<T> = Result<T, ClientError>;
+
+/// Accounts the client reads from, implemented for `RpcClient`.
+pub trait AccountSource {
+    fn get_account(&self, address: &Pubkey) -> ClientResult<Option<Account>>;
+
+    /// Accounts owned by `program_id` matching every filter.
+    fn get_program_accounts(
+        &self,
+        program_id: &Pubkey,
+        filters: Vec<RpcFilterType>,
+    ) -> ClientResult<Vec<(Pubkey, Account)>>;
+}
+
+impl AccountSource for RpcClient {
+    fn get_account(&self, address: &Pubkey) -> ClientResult<Option<Account>> {
+        Ok(self.get_account_with_commitment(address, self.commitment())?.value)
+    }
+
+    fn get_program_accounts(
+        &self,
+        program_id: &Pubkey,
+        filters: Vec<RpcFilterType>,
+    ) -> ClientResult<Vec<(Pubkey, Account)>> {
+        let config = RpcProgramAccountsConfig {
+            filters: Some(filters),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                commitment: Some(self.commitment()),
+                ..RpcAccountInfoConfig::default()
+            },
+            ..RpcProgramAccountsConfig::default()
+        };
+        Ok(self.get_program_accounts_with_config(program_id, config)?)
+    }
+}
+
+fn decode_account<T>(
+    address: &Pubkey,
+    account: &Account,
+    account_type: AccountType,
+    decode: fn(&[u8]) -> Result<T, ProgramError>,
+) -> ClientResult<T> {
+    if account.owner != collection::id() {
+        return Err(ClientError::InvalidAccountOwner(*address));
+    }
+    if account.data.first() != Some(&(account_type as u8)) {
+        return Err(ClientError::InvalidAccountType(*address, account_type));
+    }
+    decode(&account.data).map_err(|_| ClientError::InvalidAccountData(*address))
+}
+
+/// Decodes a collection account of any layout version.
+pub fn decode_collection_account(address: &Pubkey, account: &Account) -> ClientResult<CollectionAccountData> {
+    let account_type = collection_account_type(&account.data);
+    decode_account(address, account, account_type, CollectionAccountData::try_from_slice_unchecked)
+}
+
+pub fn decode_index_account(address: &Pubkey, account: &Account) -> ClientResult<CollectionIndexAccountData> {
+    decode_account(address, account, AccountType::CollectionIndexAccount, CollectionIndexAccountData::try_from_slice_unchecked)
+}
+
+pub fn decode_position_account(address: &Pubkey, account: &Account) -> ClientResult<CollectionPositionAccountData> {
+    decode_account(address, account, AccountType::CollectionPositionAccount, CollectionPositionAccountData::try_from_slice_unchecked)
+}
+
+pub fn decode_symbol_account(address: &Pubkey, account: &Account) -> ClientResult<SymbolAccountData> {
+    decode_account(address, account, AccountType::SymbolAccount, SymbolAccountData::try_from_slice_unchecked)
+}
+
+pub fn decode_config_account(address: &Pubkey, account: &Account) -> ClientResult<ConfigAccountData> {
+    decode_account(address, account, AccountType::ConfigAccount, ConfigAccountData::try_from_slice_unchecked)
+}
+
+/// Typed reads of the collection program state.
+pub struct CollectionClient<S> {
+    source: S,
+}
+
+impl<S: AccountSource> CollectionClient<S> {
+    pub fn new(source: S) -> Self {
+        CollectionClient { source }
+    }
+
+    pub fn source(&self) -> &S {
+        &self.source
+    }
+
+    fn get_existing_account(&self, address: &Pubkey) -> ClientResult<Account> {
+        self.source
+            .get_account(address)?
+            .ok_or(ClientError::AccountNotFound(*address))
+    }
+
+    pub fn get_collection(&self, address: &Pubkey) -> ClientResult<CollectionAccountData> {
+        decode_collection_account(address, &self.get_existing_account(address)?)
+    }
+
+    pub fn get_index(&self, address: &Pubkey) -> ClientResult<CollectionIndexAccountData> {
+        decode_index_account(address, &self.get_existing_account(address)?)
+    }
+
+    /// Index account of `mint` in `collection`, `None` when the token is not included.
+    pub fn get_member(&self, collection: &Pubkey, mint: &Pubkey) -> ClientResult<Option<CollectionIndexAccountData>> {
+        let (address, _) = get_index_account(collection, mint);
+        match self.source.get_account(&address)? {
+            Some(account) => decode_index_account(&address, &account).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Position account of `index` in `collection`, `None` past the last position.
+    pub fn get_position(&self, collection: &Pubkey, index: u64) -> ClientResult<Option<CollectionPositionAccountData>> {
+        let (address, _) = get_position_account(collection, index);
+        match self.source.get_account(&address)? {
+            Some(account) => decode_position_account(&address, &account).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Index the next token included in `collection` gets, legacy collections
+    /// restart at zero.
+    pub fn get_next_index(&self, collection: &Pubkey) -> ClientResult<u64> {
+        Ok(self.get_collection(collection)?.effective_supply())
+    }
+
+    /// Registration of `symbol`, `None` when no collection reserved it or the
+    /// symbol can not be reserved.
+    pub fn get_symbol(&self, symbol: &str) -> ClientResult<Option<SymbolAccountData>> {
+        let (address, _) = match get_symbol_account(symbol) {
+            Some(symbol_account) => symbol_account,
+            None => return Ok(None),
+        };
+        match self.source.get_account(&address)? {
+            Some(account) => decode_symbol_account(&address, &account).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Program config, the defaults while it is not initialized.
+    pub fn get_config(&self) -> ClientResult<ConfigAccountData> {
+        let (address, _) = get_config_account();
+        match self.source.get_account(&address)? {
+            Some(account) => decode_config_account(&address, &account),
+            None => Ok(ConfigAccountData::default()),
+        }
+    }
+
+    pub fn get_collections(&self) -> ClientResult<Vec<(Pubkey, CollectionAccountData)>> {
+        self.get_collections_matching(filters::collections())
+    }
+
+    pub fn get_collections_by_authority(&self, authority: &Pubkey) -> ClientResult<Vec<(Pubkey, CollectionAccountData)>> {
+        self.get_collections_matching(filters::collections_by_authority(authority))
+    }
+
+    /// Collections matching any of the filter sets, one request per layout.
+    fn get_collections_matching(&self, requests: Vec<Vec<RpcFilterType>>) -> ClientResult<Vec<(Pubkey, CollectionAccountData)>> {
+        let mut collections = Vec::new();
+        for filters in requests {
+            for (address, account) in self.source.get_program_accounts(&collection::id(), filters)? {
+                collections.push((address, decode_collection_account(&address, &account)?));
+            }
+        }
+        Ok(collections)
+    }
+
+    /// Index accounts of the tokens included in `collection`, ordered by index.
+    pub fn get_members(&self, collection: &Pubkey) -> ClientResult<Vec<(Pubkey, CollectionIndexAccountData)>> {
+        let mut members = self
+            .source
+            .get_program_accounts(&collection::id(), filters::members(collection))?
+            .iter()
+            .map(|(address, account)| Ok((*address, decode_index_account(address, account)?)))
+            .collect::<ClientResult<Vec<_>>>()?;
+        members.sort_by_key(|(_, data)| data.index);
+        Ok(members)
+    }
+
+    /// Lamports held by the treasury, including its rent exempt minimum.
+    pub fn get_treasury_balance(&self) -> ClientResult<u64> {
+        let (address, _) = get_treasury_account();
+        Ok(self.source.get_account(&address)?.map_or(0, |account| account.lamports))
+    }
+}
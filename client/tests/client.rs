@@ -0,0 +1,259 @@
+use solana_program_test::*;
+use collection::processor::process_instruction;
+use collection::instruction::{light_up_stars_hundred, CreateCollectionAccountArgs};
+use collection::state::{try_from_slice_unchecked, AccountType, CollectionAccountData, CollectionIndexAccountData, COLLECTION_ACCOUNT_VERSION};
+use collection::utils::{get_index_account, get_position_account, get_symbol_account, get_treasury_account};
+use collection_client::{error::ClientError, AccountSource, ClientResult, CollectionClient};
+use solana_account_decoder::{UiAccount, UiAccountEncoding};
+use solana_client::{
+    rpc_client::{Mocks, RpcClient},
+    rpc_filter::RpcFilterType,
+    rpc_request::RpcRequest,
+    rpc_response::{Response, RpcKeyedAccount, RpcResponseContext},
+};
+use borsh::BorshSerialize;
+use solana_sdk::{
+    account::{Account, AccountSharedData},
+    native_token::sol_to_lamports,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+use collection_test_utils::{create_collection, create_nft, default_collection_args, include_nft};
+
+/// Accounts read from the banks, which can not search program accounts, so
+/// the test names every account it wants the client to see.
+struct BanksAccountSource {
+    accounts: Vec<(Pubkey, Account)>,
+}
+
+impl BanksAccountSource {
+    async fn load(context: &mut ProgramTestContext, addresses: &[Pubkey]) -> BanksAccountSource {
+        let mut accounts = Vec::new();
+        for address in addresses {
+            if let Some(account) = context.banks_client.get_account(*address).await.unwrap() {
+                accounts.push((*address, account));
+            }
+        }
+        BanksAccountSource { accounts }
+    }
+}
+
+impl AccountSource for BanksAccountSource {
+    fn get_account(&self, address: &Pubkey) -> ClientResult<Option<Account>> {
+        Ok(self
+            .accounts
+            .iter()
+            .find(|(known, _)| known == address)
+            .map(|(_, account)| account.clone()))
+    }
+
+    fn get_program_accounts(
+        &self,
+        program_id: &Pubkey,
+        filters: Vec<RpcFilterType>,
+    ) -> ClientResult<Vec<(Pubkey, Account)>> {
+        Ok(self
+            .accounts
+            .iter()
+            .filter(|(_, account)| {
+                let shared = AccountSharedData::from(account.clone());
+                account.owner == *program_id && filters.iter().all(|filter| filter.allows(&shared))
+            })
+            .cloned()
+            .collect())
+    }
+}
+
+#[tokio::test]
+async fn test_client() {
+    let program_id = collection::id();
+    let program_test = ProgramTest::new("collection", program_id, processor!(process_instruction));
+    let mut context = program_test.start_with_context().await;
+    let payer_pubkey = context.payer.pubkey();
+
+    let collection_keypair = Keypair::new();
+    let collection_account = collection_keypair.pubkey();
+    create_collection(&mut context, &collection_keypair, default_collection_args()).await.unwrap();
+
+    let (first_mint, first_token_account) = create_nft(&mut context).await.unwrap();
+    let (second_mint, second_token_account) = create_nft(&mut context).await.unwrap();
+    include_nft(&mut context, &collection_account, &first_mint.pubkey(), &first_token_account, 0).await.unwrap();
+    include_nft(&mut context, &collection_account, &second_mint.pubkey(), &second_token_account, 1).await.unwrap();
+
+    let (treasury_account, _) = get_treasury_account();
+    let mut transaction = Transaction::new_with_payer(
+        &[light_up_stars_hundred(program_id, collection_account, payer_pubkey, treasury_account)],
+        Some(&payer_pubkey),
+    );
+    transaction.sign(&[&context.payer], context.last_blockhash);
+    context.banks_client.process_transaction(transaction).await.unwrap();
+
+    // a collection of the payer written before the version byte existed
+    let legacy_collection = Pubkey::new_unique();
+    let legacy_data = (
+        AccountType::CollectionAccount,
+        payer_pubkey,
+        "legacy collection".to_string(),
+        "lc".to_string(),
+        "legacy collection description".to_string(),
+        "https://www.google.com".to_string(),
+        3u64,
+        0u64,
+        None::<String>,
+        None::<String>,
+        None::<String>,
+        None::<Vec<String>>,
+    ).try_to_vec().unwrap();
+    let legacy_account = Account {
+        lamports: solana_sdk::rent::Rent::default().minimum_balance(legacy_data.len()),
+        data: legacy_data,
+        owner: program_id,
+        executable: false,
+        rent_epoch: 0,
+    };
+    context.set_account(&legacy_collection, &legacy_account.into());
+
+    // a collection handed over to someone else
+    let other_authority = Pubkey::new_unique();
+    let other_keypair = Keypair::new();
+    let other_collection = other_keypair.pubkey();
+    let args = CreateCollectionAccountArgs {
+        symbol: "oc".to_string(),
+        ..default_collection_args()
+    };
+    create_collection(&mut context, &other_keypair, args).await.unwrap();
+    let mut other_account = context.banks_client.get_account(other_collection).await.unwrap().unwrap();
+    let mut other_data: CollectionAccountData = try_from_slice_unchecked(&other_account.data).unwrap();
+    other_data.authority = other_authority;
+    other_account.data = other_data.try_to_vec().unwrap();
+    context.set_account(&other_collection, &other_account.into());
+
+    let (first_index_account, _) = get_index_account(&collection_account, &first_mint.pubkey());
+    let (second_index_account, _) = get_index_account(&collection_account, &second_mint.pubkey());
+    let addresses = [
+        collection_account,
+        legacy_collection,
+        other_collection,
+        second_index_account,
+        first_index_account,
+        get_position_account(&collection_account, 0).0,
+        get_position_account(&collection_account, 1).0,
+        first_mint.pubkey(),
+        first_token_account,
+        treasury_account,
+        payer_pubkey,
+        get_symbol_account(&default_collection_args().symbol).unwrap().0,
+    ];
+    let client = CollectionClient::new(BanksAccountSource::load(&mut context, &addresses).await);
+
+    let collection_data = client.get_collection(&collection_account).unwrap();
+    assert_eq!(collection_data.version, COLLECTION_ACCOUNT_VERSION);
+    assert_eq!(collection_data.authority, payer_pubkey);
+    assert_eq!(collection_data.supply, 2);
+    assert_eq!(collection_data.stars, 100);
+
+    let legacy_data = client.get_collection(&legacy_collection).unwrap();
+    assert_eq!(legacy_data.version, 0);
+    assert_eq!(legacy_data.title, "legacy collection");
+    assert_eq!(legacy_data.supply, 3);
+    assert_eq!(client.get_next_index(&legacy_collection).unwrap(), 0);
+    assert_eq!(client.get_next_index(&collection_account).unwrap(), 2);
+
+    assert_eq!(client.get_collections().unwrap().len(), 3);
+
+    let mut collections: Vec<Pubkey> = client
+        .get_collections_by_authority(&payer_pubkey)
+        .unwrap()
+        .into_iter()
+        .map(|(address, _)| address)
+        .collect();
+    collections.sort();
+    let mut expected = vec![collection_account, legacy_collection];
+    expected.sort();
+    assert_eq!(collections, expected);
+
+    let collections = client.get_collections_by_authority(&other_authority).unwrap();
+    assert_eq!(collections.len(), 1);
+    assert_eq!(collections[0].0, other_collection);
+    assert!(client.get_collections_by_authority(&Pubkey::new_unique()).unwrap().is_empty());
+
+    // members come back in the order they were included
+    let members = client.get_members(&collection_account).unwrap();
+    assert_eq!(
+        members.iter().map(|(address, _)| *address).collect::<Vec<_>>(),
+        vec![first_index_account, second_index_account]
+    );
+    assert_eq!(members[0].1.mint, first_mint.pubkey());
+    assert_eq!(members[1].1.mint, second_mint.pubkey());
+    assert_eq!(members[1].1.index, 1);
+    assert!(client.get_members(&other_collection).unwrap().is_empty());
+
+    let member = client.get_member(&collection_account, &first_mint.pubkey()).unwrap().unwrap();
+    assert_eq!(member.index, 0);
+    assert!(client.get_member(&other_collection, &first_mint.pubkey()).unwrap().is_none());
+    assert_eq!(client.get_index(&second_index_account).unwrap().mint, second_mint.pubkey());
+    assert_eq!(client.get_position(&collection_account, 1).unwrap().unwrap().mint, second_mint.pubkey());
+    assert!(client.get_position(&collection_account, 2).unwrap().is_none());
+
+    assert_eq!(client.get_treasury_balance().unwrap(), sol_to_lamports(0.01));
+    assert!(!client.get_config().unwrap().is_initialized());
+    assert_eq!(client.get_symbol(&default_collection_args().symbol).unwrap().unwrap().collection, collection_account);
+
+    assert!(matches!(
+        client.get_collection(&first_index_account),
+        Err(ClientError::InvalidAccountType(address, AccountType::CollectionAccount)) if address == first_index_account
+    ));
+    assert!(matches!(
+        client.get_collection(&first_mint.pubkey()),
+        Err(ClientError::InvalidAccountOwner(_))
+    ));
+    assert!(matches!(
+        client.get_collection(&Pubkey::new_unique()),
+        Err(ClientError::AccountNotFound(_))
+    ));
+}
+
+#[test]
+fn test_rpc_account_source() {
+    let collection = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    let (index_account, _) = get_index_account(&collection, &mint);
+    let index_data = Account {
+        lamports: 1_000_000,
+        data: CollectionIndexAccountData::new(collection, mint, 4).try_to_vec().unwrap(),
+        owner: collection::id(),
+        executable: false,
+        rent_epoch: 0,
+    };
+    let ui_account = UiAccount::encode(&index_account, &index_data, UiAccountEncoding::Base64, None, None);
+    // the mock sender answers the first request of a kind with its mock and falls
+    // back to its defaults, an empty account and a foreign program account, after
+    let mut mocks = Mocks::default();
+    mocks.insert(
+        RpcRequest::GetAccountInfo,
+        serde_json::to_value(Response {
+            context: RpcResponseContext { slot: 1, api_version: None },
+            value: Some(ui_account.clone()),
+        })
+        .unwrap(),
+    );
+    mocks.insert(
+        RpcRequest::GetProgramAccounts,
+        serde_json::to_value(vec![RpcKeyedAccount { pubkey: index_account.to_string(), account: ui_account }]).unwrap(),
+    );
+    let client = CollectionClient::new(RpcClient::new_mock_with_mocks("succeeds".to_string(), mocks));
+
+    let member = client.get_member(&collection, &mint).unwrap().unwrap();
+    assert_eq!(member.mint, mint);
+    assert_eq!(member.index, 4);
+    let members = client.get_members(&collection).unwrap();
+    assert_eq!(members.len(), 1);
+    assert_eq!(members[0].0, index_account);
+    assert_eq!(members[0].1.index, 4);
+
+    assert!(client.get_member(&collection, &mint).unwrap().is_none());
+    assert!(!client.get_config().unwrap().is_initialized());
+    assert!(matches!(client.get_collection(&collection), Err(ClientError::AccountNotFound(address)) if address == collection));
+    assert!(matches!(client.get_members(&collection), Err(ClientError::InvalidAccountOwner(_))));
+}
@@ -0,0 +1,99 @@
+// Generates src/idl.ts from the program IDL, regenerate the IDL itself with
+// `UPDATE_IDL=1 cargo test --test idl` in program/ first.
+const fs = require('fs');
+const path = require('path');
+
+const idlPath = path.resolve(__dirname, '../../../program/idl/collection.json');
+const outPath = path.resolve(__dirname, '../src/idl.ts');
+
+const idl = JSON.parse(fs.readFileSync(idlPath, 'utf8'));
+
+function quote(value) {
+  return `'${value.replace(/\\/g, '\\\\').replace(/'/g, "\\'")}'`;
+}
+
+// borsh-js field type of an IDL type, types of the IDL are left as `{defined}`
+// and resolved by `idlFields` in models.ts
+function fieldType(type) {
+  if (typeof type === 'string') {
+    switch (type) {
+      case 'publicKey':
+        return '[32]';
+      case 'bool':
+        return quote('u8');
+      default:
+        return quote(type);
+    }
+  }
+  if (type.option) {
+    return `{kind: 'option', type: ${fieldType(type.option)}}`;
+  }
+  if (type.vec) {
+    return `[${fieldType(type.vec)}]`;
+  }
+  if (type.defined) {
+    return `{defined: ${quote(type.defined)}}`;
+  }
+  throw new Error(`unknown type ${JSON.stringify(type)}`);
+}
+
+function enumBody(members) {
+  return members.map(([name, value]) => `  ${name} = ${value},`).join('\n');
+}
+
+const out = [];
+out.push(`// Generated by scripts/idlgen.js from program/idl/collection.json, do not edit.`);
+out.push('');
+out.push(`export const IDL_VERSION = ${quote(idl.version)};`);
+out.push('');
+out.push('export enum CollectionInstructionType {');
+out.push(enumBody(idl.instructions.map(instruction => [instruction.name, instruction.discriminant])));
+out.push('}');
+out.push('');
+
+for (const type of idl.types.filter(type => type.type.kind === 'enum')) {
+  out.push(`export enum ${type.name}Kind {`);
+  out.push(enumBody(type.type.variants.map((variant, index) => [variant.name, index])));
+  out.push('}');
+  out.push('');
+}
+
+out.push('/** First byte of the `Program data:` logged for each event. */');
+out.push('export enum CollectionEventType {');
+out.push(enumBody(idl.events.map(event => [event.name, event.discriminant])));
+out.push('}');
+out.push('');
+
+out.push('export enum CollectionErrorCode {');
+out.push(enumBody(idl.errors.map(error => [error.name, error.code])));
+out.push('}');
+out.push('');
+out.push('export const COLLECTION_ERROR_MESSAGES: Record<CollectionErrorCode, string> = {');
+for (const error of idl.errors) {
+  const key = `  [CollectionErrorCode.${error.name}]:`;
+  const line = `${key} ${quote(error.msg)},`;
+  out.push(line.length > 120 ? `${key}\n    ${quote(error.msg)},` : line);
+}
+out.push('};');
+out.push('');
+out.push('/** Names of the enums of the IDL, encoded as a single u8. */');
+out.push(
+  `export const IDL_ENUMS: string[] = [${idl.types
+    .filter(type => type.type.kind === 'enum')
+    .map(type => quote(type.name))
+    .join(', ')}];`,
+);
+out.push('');
+out.push('/** borsh-js fields of the structs, accounts and events of the IDL. */');
+out.push('export const IDL_STRUCTS: Record<string, [string, any][]> = {');
+for (const type of [...idl.types, ...idl.accounts, ...idl.events].filter(type => type.type.kind === 'struct')) {
+  out.push(`  ${type.name}: [`);
+  for (const field of type.type.fields) {
+    out.push(`    [${quote(field.name)}, ${fieldType(field.type)}],`);
+  }
+  out.push('  ],');
+}
+out.push('};');
+out.push('');
+
+fs.writeFileSync(outPath, out.join('\n'));
@@ -9,6 +9,7 @@ import {
   sendAndConfirmRawTransaction,
   LAMPORTS_PER_SOL,
   AccountInfo,
+  MAX_SEED_LENGTH,
 } from '@solana/web3.js';
 import bs58 from 'bs58';
 import {serialize, deserializeUnchecked} from 'borsh';
@@ -17,16 +18,18 @@ import {COLLECTION_PROGRAM_ID} from './ids';
 import {
   CREATE_COLLECTION_ARGS_SCHEMA,
   CreateCollectionArgs,
-  COLLECTION_ACCOUNT_DATA_SCHEMA,
+  COLLECTION_ACCOUNT_VERSION,
   CollectionAccountData,
+  decodeCollectionAccountData,
+  effectiveSupply,
   ACCOUNT_TYPE_SCHEMA,
   AccountType,
   COLLECTION_INDEX_ACCOUNT_DATA_SCHEMA,
   CollectionIndexAccountData,
   CLOSE_ACCOUNT_INSTRUCTION_ARGS_SCHEMA,
   CloseAccountInstructionArgs,
-  CollectionInstructionType,
 } from './models';
+import {AccountTypeKind, CollectionInstructionType} from './idl';
 
 export type SignTransaction = (transaction: Transaction) => Promise<Transaction>;
 
@@ -34,7 +37,7 @@ export function parseCollectionAccountData(collection: {
   pubkey: PublicKey;
   account: AccountInfo<Buffer>;
 }): CollectionAccountData & {pubkey: PublicKey} {
-  const data = deserializeUnchecked(COLLECTION_ACCOUNT_DATA_SCHEMA, CollectionAccountData, collection.account.data);
+  const data = decodeCollectionAccountData(collection.account.data);
   return {
     ...data,
     pubkey: collection.pubkey,
@@ -50,6 +53,49 @@ export async function getTreasuryProgram() {
   return treasuryPubkey;
 }
 
+export async function getConfigAccount() {
+  const [configPubkey] = await PublicKey.findProgramAddress(
+    [Buffer.from('collection'), Buffer.from('config'), COLLECTION_PROGRAM_ID.toBytes()],
+    COLLECTION_PROGRAM_ID,
+  );
+
+  return configPubkey;
+}
+
+export async function getStarReceiptAccount(collection: PublicKey, supporter: PublicKey) {
+  const [receiptPubkey] = await PublicKey.findProgramAddress(
+    [
+      Buffer.from('collection'),
+      COLLECTION_PROGRAM_ID.toBytes(),
+      Buffer.from('receipt'),
+      collection.toBytes(),
+      supporter.toBytes(),
+    ],
+    COLLECTION_PROGRAM_ID,
+  );
+
+  return receiptPubkey;
+}
+
+// symbols that are empty or longer than a seed once normalized can not be reserved
+export async function getSymbolAccount(symbol: string): Promise<PublicKey | null> {
+  const normalizedSymbol = Buffer.from(symbol.trim().toUpperCase());
+  if (normalizedSymbol.length === 0 || normalizedSymbol.length > MAX_SEED_LENGTH) {
+    return null;
+  }
+  const [symbolPubkey] = await PublicKey.findProgramAddress(
+    [
+      Buffer.from('collection'),
+      COLLECTION_PROGRAM_ID.toBytes(),
+      Buffer.from('symbol'),
+      normalizedSymbol,
+    ],
+    COLLECTION_PROGRAM_ID,
+  );
+
+  return symbolPubkey;
+}
+
 export async function getTreasuryBalance(connection: Connection) {
   const treasury = await getTreasuryProgram();
   const balance = await connection.getBalance(treasury);
@@ -68,6 +114,8 @@ export async function createCollection(
   const collectionDataU8 = serialize(CREATE_COLLECTION_ARGS_SCHEMA, args);
 
   const account = new Keypair();
+  const configAccount = await getConfigAccount();
+  const symbolAccount = await getSymbolAccount(args.symbol);
   const instruction = new TransactionInstruction({
     data: Buffer.from(collectionDataU8),
     keys: [
@@ -75,6 +123,8 @@ export async function createCollection(
       {isSigner: true, isWritable: false, pubkey: authority},
       {isSigner: false, isWritable: false, pubkey: SYSVAR_RENT_PUBKEY},
       {isSigner: false, isWritable: false, pubkey: SystemProgram.programId},
+      {isSigner: false, isWritable: false, pubkey: configAccount},
+      ...(symbolAccount ? [{isSigner: false, isWritable: true, pubkey: symbolAccount}] : []),
     ],
     programId: COLLECTION_PROGRAM_ID,
   });
@@ -92,41 +142,41 @@ export async function createCollection(
 }
 
 export async function getAccountCollections(connection: Connection, account: PublicKey) {
-  const accountType = Buffer.from([1]);
-  const buf = Buffer.concat([accountType, account.toBytes()], 33);
-  const bytes = bs58.encode(buf);
-
-  const collections = await connection.getProgramAccounts(COLLECTION_PROGRAM_ID, {
-    encoding: 'base64',
-    commitment: 'recent',
-    filters: [
-      {
-        memcmp: {
-          offset: 0,
-          bytes: bytes,
-        },
-      },
-    ],
-  });
-
-  return collections.map(parseCollectionAccountData);
+  // versioned accounts keep the authority after the version byte, older ones right after the account type
+  const prefixes = [
+    Buffer.from([AccountTypeKind.VersionedCollectionAccount, COLLECTION_ACCOUNT_VERSION]),
+    Buffer.from([AccountTypeKind.CollectionAccount]),
+  ];
+  return getCollectionsWithPrefixes(connection, prefixes, account);
 }
 
 export async function getCollections(connection: Connection) {
-  const collections = await connection.getProgramAccounts(COLLECTION_PROGRAM_ID, {
-    encoding: 'base64',
-    commitment: 'recent',
-    filters: [
-      {
-        memcmp: {
-          offset: 0,
-          bytes: '2',
-        },
-      },
-    ],
-  });
+  const prefixes = [
+    Buffer.from([AccountTypeKind.VersionedCollectionAccount]),
+    Buffer.from([AccountTypeKind.CollectionAccount]),
+  ];
+  return getCollectionsWithPrefixes(connection, prefixes);
+}
 
-  return collections.map(parseCollectionAccountData);
+async function getCollectionsWithPrefixes(connection: Connection, prefixes: Buffer[], authority?: PublicKey) {
+  const results = await Promise.all(
+    prefixes.map((prefix) =>
+      connection.getProgramAccounts(COLLECTION_PROGRAM_ID, {
+        encoding: 'base64',
+        commitment: 'recent',
+        filters: [
+          {
+            memcmp: {
+              offset: 0,
+              bytes: bs58.encode(authority ? Buffer.concat([prefix, authority.toBytes()]) : prefix),
+            },
+          },
+        ],
+      }),
+    ),
+  );
+
+  return results.flat().map(parseCollectionAccountData);
 }
 
 export async function addNFTToCollection(
@@ -139,7 +189,16 @@ export async function addNFTToCollection(
 ) {
   const dataArr = serialize(ACCOUNT_TYPE_SCHEMA, new AccountType({type: 1}));
   const [indexAccount] = await PublicKey.findProgramAddress(
-    [Buffer.from('collection'), COLLECTION_PROGRAM_ID.toBytes(), mint.toBytes()],
+    [Buffer.from('collection'), COLLECTION_PROGRAM_ID.toBytes(), collection.toBytes(), mint.toBytes()],
+    COLLECTION_PROGRAM_ID,
+  );
+  const collectionAccount = await connection.getAccountInfo(collection);
+  if (!collectionAccount) {
+    throw new Error(`collection ${collection.toBase58()} not found`);
+  }
+  const supply = effectiveSupply(decodeCollectionAccountData(collectionAccount.data));
+  const [positionAccount] = await PublicKey.findProgramAddress(
+    [Buffer.from('collection'), COLLECTION_PROGRAM_ID.toBytes(), collection.toBytes(), supply.toArrayLike(Buffer, 'le', 8)],
     COLLECTION_PROGRAM_ID,
   );
 
@@ -150,10 +209,12 @@ export async function addNFTToCollection(
       {isSigner: true, isWritable: false, pubkey: authority}, // 1. `[signer]` Authority of collection account
       {isSigner: false, isWritable: false, pubkey: mint}, // 2. `[]` Mint of token asset (supply must be 1)
       {isSigner: false, isWritable: false, pubkey: tokenAccountOfMint}, // 3. `[]` Token account of mint (amount must be 1)
-      {isSigner: false, isWritable: true, pubkey: indexAccount}, // 4. `[writable]`  Collection index account (pda of ['collection', program id, mint id])
+      {isSigner: false, isWritable: true, pubkey: indexAccount}, // 4. `[writable]`  Collection index account (pda of ['collection', program id, collection id, mint id])
       {isSigner: true, isWritable: false, pubkey: authority}, // 5. `[signer]` Funding account (must be a system account)
       {isSigner: false, isWritable: false, pubkey: SYSVAR_RENT_PUBKEY}, // 6. `[]` Rent info
       {isSigner: false, isWritable: false, pubkey: SystemProgram.programId}, // 7. `[]` System program id account
+      {isSigner: false, isWritable: true, pubkey: positionAccount}, // 8. `[writable]` Collection position account (pda of ['collection', program id, collection id, supply])
+      {isSigner: false, isWritable: false, pubkey: await getConfigAccount()}, // 9. `[]` Config account (pda of ['collection', 'config', program id])
     ],
     programId: COLLECTION_PROGRAM_ID,
   });
@@ -202,12 +263,21 @@ export async function closeAccount(
 ) {
   const dataArr = serialize(CLOSE_ACCOUNT_INSTRUCTION_ARGS_SCHEMA, new CloseAccountInstructionArgs());
 
+  // the symbol reserved by the collection is released along with it
+  const collectionAccount = await connection.getAccountInfo(collection);
+  if (!collectionAccount) {
+    throw new Error(`collection ${collection.toBase58()} not found`);
+  }
+  const {symbol} = decodeCollectionAccountData(collectionAccount.data);
+  const symbolAccount = await getSymbolAccount(symbol);
+
   const instruction = new TransactionInstruction({
     data: Buffer.from(dataArr),
     keys: [
       {isSigner: false, isWritable: true, pubkey: collection},
       {isSigner: false, isWritable: true, pubkey: authority},
       {isSigner: true, isWritable: false, pubkey: authority},
+      ...(symbolAccount ? [{isSigner: false, isWritable: true, pubkey: symbolAccount}] : []),
     ],
     programId: COLLECTION_PROGRAM_ID,
   });
@@ -254,15 +324,20 @@ export async function starMultiple(
   signTransaction: SignTransaction,
 ) {
   const treasuryAccount = await getTreasuryProgram();
+  const configAccount = await getConfigAccount();
+  const receiptAccount = await getStarReceiptAccount(collection, feePayer);
   const dataArr = serialize(ACCOUNT_TYPE_SCHEMA, new AccountType({type: instructionType}));
 
   const instruction = new TransactionInstruction({
     data: Buffer.from(dataArr),
     keys: [
       {isSigner: false, isWritable: true, pubkey: collection},
-      {isSigner: true, isWritable: false, pubkey: feePayer},
+      {isSigner: true, isWritable: true, pubkey: feePayer},
       {isSigner: false, isWritable: true, pubkey: treasuryAccount},
       {isSigner: false, isWritable: false, pubkey: SystemProgram.programId},
+      {isSigner: false, isWritable: false, pubkey: configAccount},
+      {isSigner: false, isWritable: true, pubkey: receiptAccount},
+      {isSigner: false, isWritable: false, pubkey: SYSVAR_RENT_PUBKEY},
     ],
     programId: COLLECTION_PROGRAM_ID,
   });
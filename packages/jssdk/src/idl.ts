@@ -0,0 +1,282 @@
+// Generated by scripts/idlgen.js from program/idl/collection.json, do not edit.
+
+export const IDL_VERSION = '0.1.1';
+
+export enum CollectionInstructionType {
+  CreateCollectionAccount = 0,
+  IncludeToken = 1,
+  LightUpStarsOnce = 2,
+  LightUpStarsHundred = 3,
+  LightUpStarsThousand = 4,
+  CloseAccount = 5,
+  Withdraw = 6,
+  UpdateCollectionAccount = 7,
+  ProposeAuthority = 8,
+  AcceptAuthority = 9,
+  CancelAuthorityTransfer = 10,
+  RemoveToken = 11,
+  MigrateIndexAccount = 12,
+  InitializeConfig = 13,
+  UpdateConfig = 14,
+  LightUpStars = 15,
+  SetPaused = 16,
+  CreateCollectionPda = 17,
+  IncludeTokenByCreator = 18,
+  SubmitToken = 19,
+  ApproveSubmission = 20,
+  RejectSubmission = 21,
+  AddCurator = 22,
+  RemoveCurator = 23,
+  LockCollection = 24,
+  MigrateCollectionAccount = 25,
+}
+
+export enum AccountTypeKind {
+  Uninitialized = 0,
+  CollectionAccount = 1,
+  CollectionIndexAccount = 2,
+  CollectionPositionAccount = 3,
+  ConfigAccount = 4,
+  StarReceiptAccount = 5,
+  SymbolAccount = 6,
+  SubmissionAccount = 7,
+  CuratorAccount = 8,
+  VersionedCollectionAccount = 9,
+}
+
+/** First byte of the `Program data:` logged for each event. */
+export enum CollectionEventType {
+  CollectionCreated = 0,
+  TokenIncluded = 1,
+  TokenRemoved = 2,
+  StarsLit = 3,
+  Withdrawn = 4,
+  AccountClosed = 5,
+  CollectionMigrated = 6,
+}
+
+export enum CollectionErrorCode {
+  AlreadyInitialized = 0,
+  Uninitialized = 1,
+  InvalidNFT = 2,
+  NotMintAuthority = 3,
+  NotCollectionAuthority = 4,
+  InvalidProgramId = 5,
+  InvalidInstructionArguments = 6,
+  CollectionIndexAccountMismatch = 7,
+  InvalidTreasuryAccount = 8,
+  InvalidAccountType = 9,
+  InsufficientFunds = 10,
+  NotTreasuryManager = 11,
+  NotPendingAuthority = 12,
+  NoPendingAuthority = 13,
+  CollectionPositionAccountMismatch = 14,
+  InvalidConfigAccount = 15,
+  NotConfigAdmin = 16,
+  NotUpgradeAuthority = 17,
+  NumericalOverflow = 18,
+  StarReceiptAccountMismatch = 19,
+  ProgramPaused = 20,
+  InvalidAccountOwner = 21,
+  InvalidCollectionAccount = 22,
+  SymbolAlreadyTaken = 23,
+  SymbolAccountMismatch = 24,
+  InvalidMetadataAccount = 25,
+  CreatorNotVerified = 26,
+  SubmissionAccountMismatch = 27,
+  CuratorAccountMismatch = 28,
+  CollectionFull = 29,
+  CollectionImmutable = 30,
+  CollectionNotEmpty = 31,
+  SubmitterNotHolder = 32,
+}
+
+export const COLLECTION_ERROR_MESSAGES: Record<CollectionErrorCode, string> = {
+  [CollectionErrorCode.AlreadyInitialized]: 'Already initialized',
+  [CollectionErrorCode.Uninitialized]: 'Uninitialized',
+  [CollectionErrorCode.InvalidNFT]: 'Invalid nft',
+  [CollectionErrorCode.NotMintAuthority]: 'You must be the mint authority and signer on this transaction',
+  [CollectionErrorCode.NotCollectionAuthority]: 'You must be the collection authority and signer on this transaction',
+  [CollectionErrorCode.InvalidProgramId]: 'Invalid program id',
+  [CollectionErrorCode.InvalidInstructionArguments]: 'Invalid instruction arguments',
+  [CollectionErrorCode.CollectionIndexAccountMismatch]: 'Collection index account mismatch',
+  [CollectionErrorCode.InvalidTreasuryAccount]: 'Invalid treasury account',
+  [CollectionErrorCode.InvalidAccountType]: 'Invalid account type',
+  [CollectionErrorCode.InsufficientFunds]: 'Insufficient funds',
+  [CollectionErrorCode.NotTreasuryManager]: 'You must be the treasury manager and signer on this transaction',
+  [CollectionErrorCode.NotPendingAuthority]: 'You must be the pending authority and signer on this transaction',
+  [CollectionErrorCode.NoPendingAuthority]: 'No authority transfer is pending',
+  [CollectionErrorCode.CollectionPositionAccountMismatch]: 'Collection position account mismatch',
+  [CollectionErrorCode.InvalidConfigAccount]: 'Invalid config account',
+  [CollectionErrorCode.NotConfigAdmin]: 'You must be the config admin and signer on this transaction',
+  [CollectionErrorCode.NotUpgradeAuthority]: 'You must be the program upgrade authority and signer on this transaction',
+  [CollectionErrorCode.NumericalOverflow]: 'Numerical overflow',
+  [CollectionErrorCode.StarReceiptAccountMismatch]: 'Star receipt account does not match the collection and supporter',
+  [CollectionErrorCode.ProgramPaused]: 'This instruction is paused by the config admin',
+  [CollectionErrorCode.InvalidAccountOwner]: 'Account is not owned by the collection program',
+  [CollectionErrorCode.InvalidCollectionAccount]: 'Collection account does not match the authority and slug',
+  [CollectionErrorCode.SymbolAlreadyTaken]: 'Symbol is already taken by another collection',
+  [CollectionErrorCode.SymbolAccountMismatch]: 'Symbol account does not match the collection symbol',
+  [CollectionErrorCode.InvalidMetadataAccount]: 'Metadata account is not the Metaplex metadata of the mint',
+  [CollectionErrorCode.CreatorNotVerified]: 'Collection authority is not a verified creator of the token',
+  [CollectionErrorCode.SubmissionAccountMismatch]: 'Submission account does not match the collection and mint',
+  [CollectionErrorCode.CuratorAccountMismatch]: 'Curator account does not match the collection and member',
+  [CollectionErrorCode.CollectionFull]: 'Collection reached its maximum supply',
+  [CollectionErrorCode.CollectionImmutable]: 'Collection is locked against this change',
+  [CollectionErrorCode.CollectionNotEmpty]: 'Collection still has members',
+  [CollectionErrorCode.SubmitterNotHolder]: 'Submitter does not hold the token',
+};
+
+/** Names of the enums of the IDL, encoded as a single u8. */
+export const IDL_ENUMS: string[] = ['AccountType'];
+
+/** borsh-js fields of the structs, accounts and events of the IDL. */
+export const IDL_STRUCTS: Record<string, [string, any][]> = {
+  CreateCollectionAccountArgs: [
+    ['title', 'string'],
+    ['symbol', 'string'],
+    ['description', 'string'],
+    ['icon_image', 'string'],
+    ['header_image', {kind: 'option', type: 'string'}],
+    ['short_description', {kind: 'option', type: 'string'}],
+    ['banner', {kind: 'option', type: 'string'}],
+    ['tags', {kind: 'option', type: ['string']}],
+    ['max_supply', {kind: 'option', type: 'u64'}],
+  ],
+  UpdateCollectionAccountArgs: [
+    ['title', {kind: 'option', type: 'string'}],
+    ['symbol', {kind: 'option', type: 'string'}],
+    ['description', {kind: 'option', type: 'string'}],
+    ['icon_image', {kind: 'option', type: 'string'}],
+    ['header_image', {kind: 'option', type: 'string'}],
+    ['short_description', {kind: 'option', type: 'string'}],
+    ['banner', {kind: 'option', type: 'string'}],
+    ['tags', {kind: 'option', type: ['string']}],
+    ['max_supply', {kind: 'option', type: 'u64'}],
+    ['clear', 'u8'],
+  ],
+  FieldLimits: [
+    ['max_title_length', 'u32'],
+    ['max_symbol_length', 'u32'],
+    ['max_uri_length', 'u32'],
+    ['max_description_length', 'u32'],
+    ['max_short_description_length', 'u32'],
+    ['max_tag_length', 'u32'],
+    ['max_tags_array_length', 'u32'],
+  ],
+  ConfigArgs: [
+    ['admin', [32]],
+    ['treasury_manager', [32]],
+    ['stars_hundred_price', 'u64'],
+    ['stars_thousand_price', 'u64'],
+    ['field_limits', {defined: 'FieldLimits'}],
+    ['price_per_star', 'u64'],
+  ],
+  CollectionAccountData: [
+    ['account_type', {defined: 'AccountType'}],
+    ['version', 'u8'],
+    ['authority', [32]],
+    ['title', 'string'],
+    ['symbol', 'string'],
+    ['description', 'string'],
+    ['icon_image', 'string'],
+    ['supply', 'u64'],
+    ['stars', 'u64'],
+    ['header_image', {kind: 'option', type: 'string'}],
+    ['short_description', {kind: 'option', type: 'string'}],
+    ['banner', {kind: 'option', type: 'string'}],
+    ['tags', {kind: 'option', type: ['string']}],
+    ['pending_authority', {kind: 'option', type: [32]}],
+    ['max_supply', {kind: 'option', type: 'u64'}],
+    ['metadata_locked', 'u8'],
+    ['membership_locked', 'u8'],
+  ],
+  CollectionIndexAccountData: [
+    ['account_type', {defined: 'AccountType'}],
+    ['collection', [32]],
+    ['mint', [32]],
+    ['index', 'u64'],
+  ],
+  CollectionPositionAccountData: [
+    ['account_type', {defined: 'AccountType'}],
+    ['collection', [32]],
+    ['mint', [32]],
+    ['index', 'u64'],
+  ],
+  ConfigAccountData: [
+    ['account_type', {defined: 'AccountType'}],
+    ['admin', [32]],
+    ['treasury_manager', [32]],
+    ['stars_hundred_price', 'u64'],
+    ['stars_thousand_price', 'u64'],
+    ['field_limits', {defined: 'FieldLimits'}],
+    ['price_per_star', 'u64'],
+    ['membership_paused', 'u8'],
+    ['payments_paused', 'u8'],
+  ],
+  StarReceiptAccountData: [
+    ['account_type', {defined: 'AccountType'}],
+    ['collection', [32]],
+    ['supporter', [32]],
+    ['stars', 'u64'],
+    ['lamports', 'u64'],
+    ['last_slot', 'u64'],
+  ],
+  SymbolAccountData: [
+    ['account_type', {defined: 'AccountType'}],
+    ['collection', [32]],
+  ],
+  SubmissionAccountData: [
+    ['account_type', {defined: 'AccountType'}],
+    ['collection', [32]],
+    ['mint', [32]],
+    ['submitter', [32]],
+  ],
+  CuratorAccountData: [
+    ['account_type', {defined: 'AccountType'}],
+    ['collection', [32]],
+    ['curator', [32]],
+    ['permissions', 'u8'],
+  ],
+  CollectionCreated: [
+    ['collection', [32]],
+    ['authority', [32]],
+    ['title', 'string'],
+    ['symbol', 'string'],
+    ['max_supply', {kind: 'option', type: 'u64'}],
+  ],
+  TokenIncluded: [
+    ['collection', [32]],
+    ['mint', [32]],
+    ['index', 'u64'],
+    ['supply', 'u64'],
+  ],
+  TokenRemoved: [
+    ['collection', [32]],
+    ['mint', [32]],
+    ['index', 'u64'],
+    ['supply', 'u64'],
+  ],
+  StarsLit: [
+    ['collection', [32]],
+    ['supporter', {kind: 'option', type: [32]}],
+    ['stars', 'u64'],
+    ['lamports', 'u64'],
+    ['total_stars', 'u64'],
+  ],
+  Withdrawn: [
+    ['treasury_manager', [32]],
+    ['recipient', [32]],
+    ['lamports', 'u64'],
+  ],
+  AccountClosed: [
+    ['account', [32]],
+    ['account_type', {defined: 'AccountType'}],
+    ['recipient', [32]],
+  ],
+  CollectionMigrated: [
+    ['collection', [32]],
+    ['version', 'u8'],
+    ['legacy_supply', 'u64'],
+  ],
+};
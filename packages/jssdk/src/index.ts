@@ -1,3 +1,4 @@
 export * from './ids';
+export * from './idl';
 export * from './models';
 export * from './actions';
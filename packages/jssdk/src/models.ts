@@ -1,15 +1,14 @@
 import {PublicKey} from '@solana/web3.js';
 import BN from 'bn.js';
+import {deserialize, deserializeUnchecked} from 'borsh';
 
-export enum CollectionInstructionType {
-  CreateCollectionAccount = 0,
-  IncludeToken = 1,
-  LightUpStarsOnce = 2,
-  LightUpStarsHundred = 3,
-  LightUpStarsThousand = 4,
-  CloseAccount = 5,
-  Withdraw = 6,
-}
+import {AccountTypeKind, CollectionInstructionType, IDL_ENUMS, IDL_STRUCTS} from './idl';
+
+/**
+ * Layout version written after the `VersionedCollectionAccount` account type,
+ * `CollectionAccount` accounts from before versioning read as version 0.
+ */
+export const COLLECTION_ACCOUNT_VERSION = 1;
 
 export class CreateCollectionArgs {
   type: number;
@@ -19,8 +18,9 @@ export class CreateCollectionArgs {
   icon_image: string;
   header_image?: string;
   short_description?: string;
-  banaer?: string;
+  banner?: string;
   tags?: string[];
+  max_supply?: BN;
 
   constructor(args: {
     type: number;
@@ -30,8 +30,9 @@ export class CreateCollectionArgs {
     icon_image: string;
     header_image?: string;
     short_description?: string;
-    banaer?: string;
+    banner?: string;
     tags?: string[];
+    max_supply?: BN;
   }) {
     this.type = args.type;
     this.title = args.title;
@@ -40,8 +41,9 @@ export class CreateCollectionArgs {
     this.icon_image = args.icon_image;
     this.header_image = args.header_image;
     this.short_description = args.short_description;
-    this.banaer = args.banaer;
+    this.banner = args.banner;
     this.tags = args.tags;
+    this.max_supply = args.max_supply;
   }
 }
 
@@ -58,6 +60,7 @@ export class AccountType {
 
 export class CollectionAccountData {
   account_type: AccountType;
+  version: number;
   title: string;
   symbol: string;
   description: string;
@@ -67,11 +70,16 @@ export class CollectionAccountData {
   authority: Uint8Array;
   header_image?: string;
   short_description?: string;
-  banaer?: string;
+  banner?: string;
   tags?: string[];
+  pending_authority?: Uint8Array;
+  max_supply?: BN;
+  metadata_locked: number;
+  membership_locked: number;
 
   constructor(args: {
     account_type: AccountType;
+    version?: number;
     title: string;
     symbol: string;
     description: string;
@@ -81,10 +89,15 @@ export class CollectionAccountData {
     authority: Uint8Array;
     header_image?: string;
     short_description?: string;
-    banaer?: string;
+    banner?: string;
     tags?: string[];
+    pending_authority?: Uint8Array;
+    max_supply?: BN;
+    metadata_locked?: number;
+    membership_locked?: number;
   }) {
     this.account_type = args.account_type;
+    this.version = args.version ?? 0;
     this.title = args.title;
     this.symbol = args.symbol;
     this.description = args.description;
@@ -94,8 +107,12 @@ export class CollectionAccountData {
     this.authority = args.authority;
     this.header_image = args.header_image;
     this.short_description = args.short_description;
-    this.banaer = args.banaer;
+    this.banner = args.banner;
     this.tags = args.tags;
+    this.pending_authority = args.pending_authority;
+    this.max_supply = args.max_supply;
+    this.metadata_locked = args.metadata_locked ?? 0;
+    this.membership_locked = args.membership_locked ?? 0;
   }
 }
 
@@ -123,6 +140,32 @@ export class CloseAccountInstructionArgs {
   }
 }
 
+/**
+ * borsh-js fields of a struct of the IDL. Structs named in `classes` are read into
+ * those classes, enums of the IDL are read as their u8 index.
+ */
+export function idlFields(name: string, classes: Record<string, any> = {}): [string, any][] {
+  const resolve = (type: any): any => {
+    if (Array.isArray(type)) {
+      return type.map(resolve);
+    }
+    if (type.defined) {
+      if (classes[type.defined]) {
+        return classes[type.defined];
+      }
+      if (IDL_ENUMS.includes(type.defined)) {
+        return 'u8';
+      }
+      throw new Error(`no class for ${type.defined}`);
+    }
+    if (type.kind === 'option') {
+      return {kind: 'option', type: resolve(type.type)};
+    }
+    return type;
+  };
+  return IDL_STRUCTS[name].map(([field, type]) => [field, resolve(type)]);
+}
+
 export const ACCOUNT_TYPE_SCHEMA = new Map([
   [
     AccountType,
@@ -138,17 +181,7 @@ export const CREATE_COLLECTION_ARGS_SCHEMA = new Map([
     CreateCollectionArgs,
     {
       kind: 'struct',
-      fields: [
-        ['type', 'u8'],
-        ['title', 'string'],
-        ['symbol', 'string'],
-        ['description', 'string'],
-        ['icon_image', 'string'],
-        ['header_image', {kind: 'option', type: 'string'}],
-        ['short_description', {kind: 'option', type: 'string'}],
-        ['banaer', {kind: 'option', type: 'string'}],
-        ['tags', {kind: 'option', type: ['string']}],
-      ],
+      fields: [['type', 'u8'], ...idlFields('CreateCollectionAccountArgs')],
     },
   ],
 ]);
@@ -165,35 +198,63 @@ export const COLLECTION_ACCOUNT_DATA_SCHEMA = new Map<any, any>([
     CollectionAccountData,
     {
       kind: 'struct',
-      fields: [
-        ['account_type', AccountType],
-        ['authority', [32]],
-        ['title', 'string'],
-        ['symbol', 'string'],
-        ['description', 'string'],
-        ['icon_image', 'string'],
-        ['supply', 'u64'],
-        ['stars', 'u64'],
-        ['header_image', {kind: 'option', type: 'string'}],
-        ['short_description', {kind: 'option', type: 'string'}],
-        ['banaer', {kind: 'option', type: 'string'}],
-        ['tags', {kind: 'option', type: ['string']}],
-      ],
+      fields: idlFields('CollectionAccountData', {AccountType}),
     },
   ],
 ]);
 
+// unversioned accounts end after the tags and have no version byte
+const LEGACY_COLLECTION_ACCOUNT_FIELDS = idlFields('CollectionAccountData', {AccountType}).filter(
+  ([field]) => field !== 'version',
+);
+LEGACY_COLLECTION_ACCOUNT_FIELDS.splice(LEGACY_COLLECTION_ACCOUNT_FIELDS.findIndex(([field]) => field === 'tags') + 1);
+
+/** Layout of collection accounts written before the version byte. */
+export const LEGACY_COLLECTION_ACCOUNT_DATA_SCHEMA = new Map<any, any>([
+  [
+    AccountType,
+    {
+      kind: 'struct',
+      fields: [['type', 'u8']],
+    },
+  ],
+  [
+    CollectionAccountData,
+    {
+      kind: 'struct',
+      fields: LEGACY_COLLECTION_ACCOUNT_FIELDS,
+    },
+  ],
+]);
+
+/**
+ * Decodes a collection account of any known version, versioned accounts are
+ * stored under their own account type.
+ */
+export function decodeCollectionAccountData(data: Buffer): CollectionAccountData {
+  if (data[0] === AccountTypeKind.VersionedCollectionAccount) {
+    if (data[1] !== COLLECTION_ACCOUNT_VERSION) {
+      throw new Error(`unknown collection account version ${data[1]}`);
+    }
+    return deserializeUnchecked(COLLECTION_ACCOUNT_DATA_SCHEMA, CollectionAccountData, data);
+  }
+  return deserializeUnchecked(LEGACY_COLLECTION_ACCOUNT_DATA_SCHEMA, CollectionAccountData, data);
+}
+
+/**
+ * Supply once the account is in the current layout, the position the next token
+ * goes to. Unversioned accounts restart at zero on their first write.
+ */
+export function effectiveSupply(data: CollectionAccountData): BN {
+  return data.version === COLLECTION_ACCOUNT_VERSION ? data.supply : new BN(0);
+}
+
 export const COLLECTION_INDEX_ACCOUNT_DATA_SCHEMA = new Map([
   [
     CollectionIndexAccountData,
     {
       kind: 'struct',
-      fields: [
-        ['account_type', 'u8'],
-        ['collection', [32]],
-        ['mint', [32]],
-        ['index', 'u64'],
-      ],
+      fields: idlFields('CollectionIndexAccountData'),
     },
   ],
 ]);
@@ -15,8 +15,8 @@ export default ({ data, block }: { data: CollectionAccountData & { pubkey: Publi
   const wallet = useWallet();
 
   function renderImage() {
-    if (block && data.banaer) {
-      return <img src={data.banaer} />;
+    if (block && data.banner) {
+      return <img src={data.banner} />;
     }
     if (!block && data.header_image) {
       return <img src={data.header_image} />;
@@ -40,7 +40,7 @@ export default () => {
     icon_image: '',
     header_image: '',
     short_description: '',
-    banaer: '',
+    banner: '',
     tags: [],
   });
 
@@ -136,7 +136,7 @@ export default () => {
               <Input
                 size="large"
                 placeholder="e.g. 'https://arweave.net/xxx'"
-                onChange={(e) => setData({ ...data, banaer: e.target.value })}
+                onChange={(e) => setData({ ...data, banner: e.target.value })}
               />
             </div>
           </Space>
@@ -60,6 +60,90 @@ pub enum CollectionError {
     /// You must be the treasury manager and signer on this transaction
     #[error("You must be the treasury manager and signer on this transaction")]
     NotTreasuryManager,
+
+    /// You must be the pending authority and signer on this transaction
+    #[error("You must be the pending authority and signer on this transaction")]
+    NotPendingAuthority,
+
+    /// No authority transfer is pending
+    #[error("No authority transfer is pending")]
+    NoPendingAuthority,
+
+    /// Collection position account mismatch
+    #[error("Collection position account mismatch")]
+    CollectionPositionAccountMismatch,
+
+    /// Invalid config account
+    #[error("Invalid config account")]
+    InvalidConfigAccount,
+
+    /// You must be the config admin and signer on this transaction
+    #[error("You must be the config admin and signer on this transaction")]
+    NotConfigAdmin,
+
+    /// You must be the program upgrade authority and signer on this transaction
+    #[error("You must be the program upgrade authority and signer on this transaction")]
+    NotUpgradeAuthority,
+
+    /// Numerical overflow
+    #[error("Numerical overflow")]
+    NumericalOverflow,
+
+    /// Star receipt account does not match the collection and supporter
+    #[error("Star receipt account does not match the collection and supporter")]
+    StarReceiptAccountMismatch,
+
+    /// This instruction is paused by the config admin
+    #[error("This instruction is paused by the config admin")]
+    ProgramPaused,
+
+    /// Account is not owned by the collection program
+    #[error("Account is not owned by the collection program")]
+    InvalidAccountOwner,
+
+    /// Collection account does not match the authority and slug
+    #[error("Collection account does not match the authority and slug")]
+    InvalidCollectionAccount,
+
+    /// Symbol is already taken by another collection
+    #[error("Symbol is already taken by another collection")]
+    SymbolAlreadyTaken,
+
+    /// Symbol account does not match the collection symbol
+    #[error("Symbol account does not match the collection symbol")]
+    SymbolAccountMismatch,
+
+    /// Metadata account is not the Metaplex metadata of the mint
+    #[error("Metadata account is not the Metaplex metadata of the mint")]
+    InvalidMetadataAccount,
+
+    /// Collection authority is not a verified creator of the token
+    #[error("Collection authority is not a verified creator of the token")]
+    CreatorNotVerified,
+
+    /// Submission account does not match the collection and mint
+    #[error("Submission account does not match the collection and mint")]
+    SubmissionAccountMismatch,
+
+    /// Curator account does not match the collection and member
+    #[error("Curator account does not match the collection and member")]
+    CuratorAccountMismatch,
+
+    /// Collection reached its maximum supply
+    #[error("Collection reached its maximum supply")]
+    CollectionFull,
+
+    /// Collection is locked against this change
+    #[error("Collection is locked against this change")]
+    CollectionImmutable,
+
+    /// Collection still has members
+    #[error("Collection still has members")]
+    CollectionNotEmpty,
+
+    /// Submitter does not hold the token
+    #[error("Submitter does not hold the token")]
+    SubmitterNotHolder,
 }
 
 impl PrintProgramError for CollectionError {
@@ -0,0 +1,139 @@
+//! Events logged with `sol_log_data` for indexers
+
+use {
+    crate::{id, state::AccountType},
+    solana_program::{entrypoint::ProgramResult, log::sol_log_data, pubkey::Pubkey},
+    borsh::{BorshDeserialize, BorshSerialize},
+    std::io,
+};
+
+/// A collection account was created.
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct CollectionCreated {
+    pub collection: Pubkey,
+    pub authority: Pubkey,
+    pub title: String,
+    pub symbol: String,
+    pub max_supply: Option<u64>,
+}
+
+/// A token was added to a collection at `index`, `supply` counts it.
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct TokenIncluded {
+    pub collection: Pubkey,
+    pub mint: Pubkey,
+    pub index: u64,
+    pub supply: u64,
+}
+
+/// A token left the collection, the last token moved to its `index` unless it was
+/// the last one.
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct TokenRemoved {
+    pub collection: Pubkey,
+    pub mint: Pubkey,
+    pub index: u64,
+    pub supply: u64,
+}
+
+/// Stars were lit on a collection, `supporter` is `None` for the free
+/// `LightUpStarsOnce` which takes no payer.
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct StarsLit {
+    pub collection: Pubkey,
+    pub supporter: Option<Pubkey>,
+    pub stars: u64,
+    pub lamports: u64,
+    /// Stars of the collection after this purchase.
+    pub total_stars: u64,
+}
+
+/// Lamports left the treasury.
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct Withdrawn {
+    pub treasury_manager: Pubkey,
+    pub recipient: Pubkey,
+    pub lamports: u64,
+}
+
+/// A collection or index account was closed by `CloseAccount`.
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct AccountClosed {
+    pub account: Pubkey,
+    pub account_type: AccountType,
+    pub recipient: Pubkey,
+}
+
+/// A legacy collection account moved to the current layout `version`. Its
+/// `legacy_supply` counted every inclusion, the supply restarts at zero and
+/// members are counted again as `MigrateIndexAccount` includes them.
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct CollectionMigrated {
+    pub collection: Pubkey,
+    pub version: u8,
+    pub legacy_supply: u64,
+}
+
+/// Every event of the program, logged as the borsh encoding of this enum so the
+/// first byte tells the events apart. New events go at the end.
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub enum CollectionEvent {
+    CollectionCreated(CollectionCreated),
+    TokenIncluded(TokenIncluded),
+    TokenRemoved(TokenRemoved),
+    StarsLit(StarsLit),
+    Withdrawn(Withdrawn),
+    AccountClosed(AccountClosed),
+    CollectionMigrated(CollectionMigrated),
+}
+
+impl CollectionEvent {
+    /// Logs the event as a `Program data:` line.
+    pub fn emit(&self) -> ProgramResult {
+        sol_log_data(&[&self.try_to_vec()?]);
+        Ok(())
+    }
+}
+
+/// Decodes the events the program logged in the log messages of a transaction,
+/// in the order they were emitted.
+///
+/// `Program data:` lines are only taken while this program is the innermost
+/// invocation, data logged by other programs is skipped. Events are kept once
+/// the invocation that logged them and every invocation around it succeeded,
+/// the events of a failed invocation were rolled back with it.
+pub fn decode_events<S: AsRef<str>>(logs: &[S]) -> Result<Vec<CollectionEvent>, io::Error> {
+    let program_id = id().to_string();
+    // the program of each open invocation along with the events it logged so far
+    let mut invocations: Vec<(&str, Vec<CollectionEvent>)> = Vec::new();
+    let mut events = Vec::new();
+    for log in logs {
+        let log = log.as_ref();
+        if let Some(data) = log.strip_prefix("Program data: ") {
+            let invocation = match invocations.last_mut() {
+                Some((program, invocation_events)) if *program == program_id => invocation_events,
+                _ => continue,
+            };
+            // a single field, the encoded event
+            let data = base64::decode(data.trim())
+                .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+            invocation.push(CollectionEvent::try_from_slice(&data)?);
+        } else if let Some(rest) = log.strip_prefix("Program ") {
+            let mut words = rest.split(' ');
+            let (program, status) = (words.next().unwrap_or_default(), words.next().unwrap_or_default());
+            if status == "invoke" {
+                invocations.push((program, Vec::new()));
+            } else if status == "success" {
+                if let Some((_, invocation_events)) = invocations.pop() {
+                    match invocations.last_mut() {
+                        Some((_, parent_events)) => parent_events.extend(invocation_events),
+                        None => events.extend(invocation_events),
+                    }
+                }
+            } else if status == "failed:" {
+                invocations.pop();
+            }
+        }
+    }
+    Ok(events)
+}
@@ -2,16 +2,21 @@
 use {
     borsh::{BorshDeserialize, BorshSerialize},
     solana_program::{
+        bpf_loader_upgradeable,
         instruction::{AccountMeta, Instruction},
+        program_error::ProgramError,
         pubkey::Pubkey,
         sysvar,
         system_program,
     },
-    crate::state::AccountType,
+    crate::{
+        state::{deserialize_or_default, AccountType, FieldLimits},
+        utils::{get_collection_account, get_config_account, get_curator_account, get_metadata_account, get_star_receipt_account, get_submission_account, get_symbol_account},
+    },
 };
 
 #[repr(C)]
-#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[derive(BorshSerialize, PartialEq, Debug, Clone)]
 pub struct CreateCollectionAccountArgs {
     pub title: String,
     pub symbol: String,
@@ -21,9 +26,99 @@ pub struct CreateCollectionAccountArgs {
     pub short_description: Option<String>,
     pub banner: Option<String>,
     pub tags: Option<Vec<String>>,
+    /// Most tokens the collection will ever hold, `None` for no cap.
+    pub max_supply: Option<u64>,
+}
+
+impl BorshDeserialize for CreateCollectionAccountArgs {
+    fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+        Ok(CreateCollectionAccountArgs {
+            title: BorshDeserialize::deserialize(buf)?,
+            symbol: BorshDeserialize::deserialize(buf)?,
+            description: BorshDeserialize::deserialize(buf)?,
+            icon_image: BorshDeserialize::deserialize(buf)?,
+            header_image: BorshDeserialize::deserialize(buf)?,
+            short_description: BorshDeserialize::deserialize(buf)?,
+            banner: BorshDeserialize::deserialize(buf)?,
+            tags: BorshDeserialize::deserialize(buf)?,
+            // clients built before the cap existed leave it out
+            max_supply: deserialize_or_default(buf)?,
+        })
+    }
+
+    /// `max_supply` may be missing at the end of the input, which a reader can
+    /// not tell, so the rest of it is decoded as a slice.
+    fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+        Self::deserialize(&mut data.as_slice())
+    }
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, PartialEq, Debug, Clone, Default)]
+pub struct UpdateCollectionAccountArgs {
+    pub title: Option<String>,
+    pub symbol: Option<String>,
+    pub description: Option<String>,
+    pub icon_image: Option<String>,
+    pub header_image: Option<String>,
+    pub short_description: Option<String>,
+    pub banner: Option<String>,
+    pub tags: Option<Vec<String>>,
+    /// New cap, can only lower the current one and not go below the supply.
+    pub max_supply: Option<u64>,
+    /// `CLEAR_*` flags of the optional fields to reset to `None`, a field can
+    /// not be set and cleared at once.
+    pub clear: u8,
+}
+
+/// Clears the header image of the collection.
+pub const CLEAR_HEADER_IMAGE: u8 = 1;
+/// Clears the short description of the collection.
+pub const CLEAR_SHORT_DESCRIPTION: u8 = 1 << 1;
+/// Clears the banner of the collection.
+pub const CLEAR_BANNER: u8 = 1 << 2;
+/// Clears the tags of the collection.
+pub const CLEAR_TAGS: u8 = 1 << 3;
+pub const CLEAR_ALL: u8 = CLEAR_HEADER_IMAGE | CLEAR_SHORT_DESCRIPTION | CLEAR_BANNER | CLEAR_TAGS;
+
+impl BorshDeserialize for UpdateCollectionAccountArgs {
+    fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+        Ok(UpdateCollectionAccountArgs {
+            title: BorshDeserialize::deserialize(buf)?,
+            symbol: BorshDeserialize::deserialize(buf)?,
+            description: BorshDeserialize::deserialize(buf)?,
+            icon_image: BorshDeserialize::deserialize(buf)?,
+            header_image: BorshDeserialize::deserialize(buf)?,
+            short_description: BorshDeserialize::deserialize(buf)?,
+            banner: BorshDeserialize::deserialize(buf)?,
+            tags: BorshDeserialize::deserialize(buf)?,
+            max_supply: deserialize_or_default(buf)?,
+            clear: deserialize_or_default(buf)?,
+        })
+    }
+
+    /// Decoded as a slice for the same reason as `CreateCollectionAccountArgs`.
+    fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+        Self::deserialize(&mut data.as_slice())
+    }
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct ConfigArgs {
+    pub admin: Pubkey,
+    pub treasury_manager: Pubkey,
+    pub stars_hundred_price: u64,
+    pub stars_thousand_price: u64,
+    pub field_limits: FieldLimits,
+    pub price_per_star: u64,
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Clone)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
 pub enum CollectionInstruction {
     /// create collection account
     ///
@@ -33,6 +128,8 @@ pub enum CollectionInstruction {
     ///   1. `[signer]` Funding account (must be a system account)
     ///   2. `[]` System rent account
     ///   3. `[]` System program id account
+    ///   4. `[]` Config account (pda of ['collection', 'config', program id])
+    ///   5. `[writable]` Optional, symbol account to reserve the symbol, the symbol is not reserved without it (pda of ['collection', program id, 'symbol', normalized symbol])
     CreateCollectionAccount(CreateCollectionAccountArgs),
 
     /// include token to the collection
@@ -43,10 +140,13 @@ pub enum CollectionInstruction {
     ///   1. `[signer]` Authority of collection account
     ///   2. `[]` Mint of token asset (supply must be 1)
     ///   3. `[]` Token account of mint (amount must be 1)
-    ///   4. `[writable]`  Collection index account (pda of ['collection', program id, mint id])
+    ///   4. `[writable]`  Collection index account (pda of ['collection', program id, collection id, mint id])
     ///   5. `[signer]` Funding account (must be a system account)
     ///   6. `[]` Rent info
     ///   7. `[]` System program id account
+    ///   8. `[writable]` Collection position account (pda of ['collection', program id, collection id, supply])
+    ///   9. `[]` Config account (pda of ['collection', 'config', program id])
+    ///   10. `[]` Optional, curator account of the signer with the include permission (pda of ['collection', program id, collection id, 'curator', signer])
     IncludeToken,
 
     /// light up collection stars once
@@ -64,6 +164,9 @@ pub enum CollectionInstruction {
     ///   1. `[signer]` Funding account (must be a system account)
     ///   2. `[writable]` Collection treasury account (pda of ['collection', 'treasury', program id])
     ///   3. `[]` System program id account
+    ///   4. `[]` Config account (pda of ['collection', 'config', program id])
+    ///   5. `[writable]` Star receipt account (pda of ['collection', program id, 'receipt', collection id, funding account id])
+    ///   6. `[]` Rent info
     LightUpStarsHundred,
 
     /// light up collection stars one thousand
@@ -74,6 +177,9 @@ pub enum CollectionInstruction {
     ///   1. `[signer]` Funding account (must be a system account)
     ///   2. `[writable]` Collection treasury account (pda of ['collection', 'treasury', program id])
     ///   3. `[]` System program id account
+    ///   4. `[]` Config account (pda of ['collection', 'config', program id])
+    ///   5. `[writable]` Star receipt account (pda of ['collection', program id, 'receipt', collection id, funding account id])
+    ///   6. `[]` Rent info
     LightUpStarsThousand,
 
     /// create collection account
@@ -83,16 +189,283 @@ pub enum CollectionInstruction {
     ///   0. `[writeable]` Account
     ///   1. `[writeable]` Funding recipient account (must be a system account)
     ///   2. `[signer]` Account's authority
+    ///
+    /// A `CollectionAccount` can only be closed once all its tokens are removed, closing
+    /// it also releases its symbol, unless the symbol is empty or too long to reserve:
+    ///
+    ///   3. `[writable]` Symbol account of the collection symbol (pda of ['collection', program id, 'symbol', normalized symbol])
+    ///
+    /// Closing a `CollectionIndexAccount` removes the token from the collection, the
+    /// authority is the collection authority or the token holder, and also expects:
+    ///
+    ///   3. `[writeable]` Collcection account
+    ///   4. `[writable]` Collection position account of the token (pda of ['collection', program id, collection id, index])
+    ///   5. `[writable]` Collection position account of the last token (pda of ['collection', program id, collection id, supply - 1])
+    ///   6. `[writable]` Collection index account of the last token, moved into the freed position
+    ///   7. `[]` Token account of the holder (only when the holder closes it)
     CloseAccount(AccountType),
 
-    /// withdraw funds in program pda
+    /// withdraw funds in program pda, `None` withdraws everything above the rent exempt minimum
     ///
     /// Accounts expected by:
     /// 
     ///   0. `[signer]` Program id owner account (must be a system account)
     ///   1. `[writable]` Collection treasury account (pda of ['collection', 'treasury', program id])
     ///   2. `[writable]` Destination account
-    Withdraw,
+    ///   3. `[]` Config account (pda of ['collection', 'config', program id])
+    ///   4. `[]` System program id account
+    Withdraw { amount: Option<u64> },
+
+    /// update collection account, fields left as `None` are kept unchanged
+    ///
+    /// Accounts expected by:
+    /// 
+    ///   0. `[writeable]` Collcection account
+    ///   1. `[writeable, signer]` Authority of collection account or a curator (pays the rent difference, the authority also receives the refunds)
+    ///   2. `[]` Rent info
+    ///   3. `[]` System program id account
+    ///   4. `[]` Config account (pda of ['collection', 'config', program id])
+    ///   5. `[writable]` Symbol account of the current symbol, only when `symbol` is set (pda of ['collection', program id, 'symbol', normalized symbol])
+    ///   6. `[writable]` Symbol account of the new symbol, only when `symbol` is set, the reservation moves to it
+    ///   7. `[]` Optional, curator account of the signer, 5. when `symbol` is not set (pda of ['collection', program id, collection id, 'curator', signer])
+    ///   8. `[writable]` Collection authority receiving the refunds, only with the curator account
+    ///
+    /// Curators need the edit permission to change the metadata and the include and
+    /// remove permissions to change `max_supply`.
+    ///
+    /// Symbols that are empty or too long to reserve have no symbol account, it is
+    /// left out and the following accounts move up.
+    UpdateCollectionAccount(UpdateCollectionAccountArgs),
+
+    /// propose a new authority for the collection, it takes over once it accepts
+    ///
+    /// Accounts expected by:
+    /// 
+    ///   0. `[writeable]` Collcection account
+    ///   1. `[writeable, signer]` Authority of collection account (pays for the pending authority space)
+    ///   2. `[]` Proposed authority
+    ///   3. `[]` Rent info
+    ///   4. `[]` System program id account
+    ProposeAuthority,
+
+    /// accept a proposed authority transfer
+    ///
+    /// Accounts expected by:
+    /// 
+    ///   0. `[writeable]` Collcection account
+    ///   1. `[writeable, signer]` Pending authority of collection account
+    ///   2. `[]` Rent info
+    ///   3. `[]` System program id account
+    AcceptAuthority,
+
+    /// cancel a proposed authority transfer
+    ///
+    /// Accounts expected by:
+    /// 
+    ///   0. `[writeable]` Collcection account
+    ///   1. `[writeable, signer]` Authority of collection account
+    ///   2. `[]` Rent info
+    ///   3. `[]` System program id account
+    CancelAuthorityTransfer,
+
+    /// remove token from the collection
+    ///
+    /// Accounts expected by:
+    /// 
+    ///   0. `[writeable]` Collcection account
+    ///   1. `[signer]` Authority of collection account
+    ///   2. `[writable]` Collection index account (pda of ['collection', program id, collection id, mint id])
+    ///   3. `[writeable]` Funding recipient account (must be a system account)
+    ///   4. `[writable]` Collection position account of the removed token (pda of ['collection', program id, collection id, index])
+    ///   5. `[writable]` Collection position account of the last token (pda of ['collection', program id, collection id, supply - 1])
+    ///   6. `[writable]` Collection index account of the last token, moved into the freed position
+    ///   7. `[]` Optional, curator account of the signer with the remove permission (pda of ['collection', program id, collection id, 'curator', signer])
+    RemoveToken,
+
+    /// move an index account from the legacy per mint address to the per collection address,
+    /// the token is appended at the current supply and is checked like an included one.
+    /// A legacy account of a mint that was included again at the per collection address
+    /// is only closed.
+    ///
+    /// Accounts expected by:
+    /// 
+    ///   0. `[writable]` Collcection account
+    ///   1. `[writable, signer]` Authority of collection account (receives the rent of the legacy index account)
+    ///   2. `[writable]` Legacy collection index account (pda of ['collection', program id, mint id])
+    ///   3. `[writable]` Collection index account (pda of ['collection', program id, collection id, mint id])
+    ///   4. `[writable, signer]` Funding account (must be a system account)
+    ///   5. `[]` Rent info
+    ///   6. `[]` System program id account
+    ///   7. `[writable]` Collection position account (pda of ['collection', program id, collection id, index])
+    ///   8. `[]` Config account (pda of ['collection', 'config', program id])
+    MigrateIndexAccount,
+
+    /// initialize the program config, must be signed by the program upgrade authority
+    ///
+    /// Accounts expected by:
+    /// 
+    ///   0. `[writable]` Config account (pda of ['collection', 'config', program id])
+    ///   1. `[signer]` Program upgrade authority
+    ///   2. `[]` Program data account (pda of [program id] of the upgradeable loader)
+    ///   3. `[writable, signer]` Funding account (must be a system account)
+    ///   4. `[]` Rent info
+    ///   5. `[]` System program id account
+    InitializeConfig(ConfigArgs),
+
+    /// update the program config
+    ///
+    /// Accounts expected by:
+    /// 
+    ///   0. `[writable]` Config account (pda of ['collection', 'config', program id])
+    ///   1. `[signer]` Config admin
+    UpdateConfig(ConfigArgs),
+
+    /// light up `amount` collection stars at the configured price per star
+    ///
+    /// Accounts expected by:
+    /// 
+    ///   0. `[writeable]` Collcection account
+    ///   1. `[writable, signer]` Funding account (must be a system account)
+    ///   2. `[writable]` Collection treasury account (pda of ['collection', 'treasury', program id])
+    ///   3. `[]` System program id account
+    ///   4. `[]` Config account (pda of ['collection', 'config', program id])
+    ///   5. `[writable]` Star receipt account (pda of ['collection', program id, 'receipt', collection id, funding account id])
+    ///   6. `[]` Rent info
+    LightUpStars { amount: u64 },
+
+    /// pause or resume the membership and payment instructions
+    ///
+    /// Accounts expected by:
+    /// 
+    ///   0. `[writable]` Config account (pda of ['collection', 'config', program id])
+    ///   1. `[signer]` Config admin
+    SetPaused { membership: bool, payments: bool },
+
+    /// create collection account at the address derived from the authority and the slug
+    ///
+    /// Accounts expected by:
+    /// 
+    ///   0. `[writeable]` Collcection account (pda of ['collection', authority id, slug])
+    ///   1. `[writable, signer]` Authority of collection account, also the funding account
+    ///   2. `[]` System rent account
+    ///   3. `[]` System program id account
+    ///   4. `[]` Config account (pda of ['collection', 'config', program id])
+    ///   5. `[writable]` Optional, symbol account to reserve the symbol, the symbol is not reserved without it (pda of ['collection', program id, 'symbol', normalized symbol])
+    CreateCollectionPda { slug: String, args: CreateCollectionAccountArgs },
+
+    /// include token to the collection when the collection authority is a verified
+    /// creator in the Metaplex metadata of the mint, the token may be held by anyone
+    ///
+    /// Accounts expected by:
+    /// 
+    ///   0. `[writeable]` Collcection account
+    ///   1. `[signer]` Authority of collection account
+    ///   2. `[]` Mint of token asset (supply must be 1)
+    ///   3. `[]` Metadata account of mint (pda of ['metadata', metadata program id, mint id])
+    ///   4. `[writable]`  Collection index account (pda of ['collection', program id, collection id, mint id])
+    ///   5. `[signer]` Funding account (must be a system account)
+    ///   6. `[]` Rent info
+    ///   7. `[]` System program id account
+    ///   8. `[writable]` Collection position account (pda of ['collection', program id, collection id, supply])
+    ///   9. `[]` Config account (pda of ['collection', 'config', program id])
+    IncludeTokenByCreator,
+
+    /// submit a token to the collection, any holder of the token can submit it and
+    /// takes over a submission left by a previous holder
+    ///
+    /// Accounts expected by:
+    /// 
+    ///   0. `[]` Collection account
+    ///   1. `[writable, signer]` Submitter, owner of the token account, pays the submission rent
+    ///   2. `[]` Mint of token asset (supply must be 1)
+    ///   3. `[]` Token account of mint (amount must be 1)
+    ///   4. `[writable]` Submission account (pda of ['collection', program id, collection id, 'submission', mint id])
+    ///   5. `[]` Rent info
+    ///   6. `[]` System program id account
+    ///   7. `[]` Config account (pda of ['collection', 'config', program id])
+    ///   8. `[writable]` Previous submitter, only when the mint is already submitted, its rent is refunded
+    SubmitToken,
+
+    /// approve a submission while the submitter still holds the token, the token is
+    /// included and the submission rent refunded
+    ///
+    /// Accounts expected by:
+    /// 
+    ///   0. `[writeable]` Collcection account
+    ///   1. `[signer]` Authority of collection account
+    ///   2. `[]` Mint of token asset
+    ///   3. `[writable]` Submission account (pda of ['collection', program id, collection id, 'submission', mint id])
+    ///   4. `[writable]` Submitter account, receives the submission rent
+    ///   5. `[writable]`  Collection index account (pda of ['collection', program id, collection id, mint id])
+    ///   6. `[signer]` Funding account (must be a system account)
+    ///   7. `[]` Rent info
+    ///   8. `[]` System program id account
+    ///   9. `[writable]` Collection position account (pda of ['collection', program id, collection id, supply])
+    ///   10. `[]` Config account (pda of ['collection', 'config', program id])
+    ///   11. `[]` Token account of the submitter (amount must be 1)
+    ///   12. `[]` Optional, curator account of the signer with the include permission (pda of ['collection', program id, collection id, 'curator', signer])
+    ApproveSubmission,
+
+    /// reject a submission, the submission rent is refunded
+    ///
+    /// Accounts expected by:
+    /// 
+    ///   0. `[]` Collection account
+    ///   1. `[signer]` Authority of collection account
+    ///   2. `[writable]` Submission account (pda of ['collection', program id, collection id, 'submission', mint id])
+    ///   3. `[writable]` Submitter account, receives the submission rent
+    ///   4. `[]` Optional, curator account of the signer with the include permission (pda of ['collection', program id, collection id, 'curator', signer])
+    RejectSubmission,
+
+    /// grant a member curator permissions on the collection, an existing curator
+    /// gets its permissions replaced
+    ///
+    /// Accounts expected by:
+    /// 
+    ///   0. `[]` Collection account
+    ///   1. `[signer]` Authority of collection account
+    ///   2. `[]` Member account
+    ///   3. `[writable]` Curator account (pda of ['collection', program id, collection id, 'curator', member id])
+    ///   4. `[writable, signer]` Funding account (must be a system account)
+    ///   5. `[]` Rent info
+    ///   6. `[]` System program id account
+    AddCurator { permissions: u8 },
+
+    /// revoke the curator role of a member
+    ///
+    /// Accounts expected by:
+    /// 
+    ///   0. `[]` Collection account
+    ///   1. `[signer]` Authority of collection account
+    ///   2. `[writable]` Curator account (pda of ['collection', program id, collection id, 'curator', member id])
+    ///   3. `[writable]` Funding recipient account (must be a system account)
+    RemoveCurator,
+
+    /// lock the collection, a lock can never be lifted again
+    ///
+    ///   - `metadata` freezes title, symbol, description, images and tags
+    ///   - `membership` freezes the tokens of the collection and its maximum supply
+    ///
+    /// Locking either also prevents closing the collection.
+    ///
+    /// Accounts expected by:
+    /// 
+    ///   0. `[writeable]` Collcection account
+    ///   1. `[writeable, signer]` Authority of collection account
+    ///   2. `[]` Rent info
+    ///   3. `[]` System program id account
+    LockCollection { metadata: bool, membership: bool },
+
+    /// rewrite a collection account in the current layout, anyone may pay for it. The supply
+    /// of an unversioned collection restarts at zero, `MigrateIndexAccount` counts its members again
+    ///
+    /// Accounts expected by:
+    /// 
+    ///   0. `[writeable]` Collcection account
+    ///   1. `[writeable, signer]` Funding account (must be a system account)
+    ///   2. `[]` Rent info
+    ///   3. `[]` System program id account
+    MigrateCollectionAccount,
 }
 
 /// Creates a `CreateCollectionAccount` instruction.
@@ -102,19 +475,26 @@ pub fn create_collection_account(
     from_account: Pubkey,
     args: CreateCollectionAccountArgs,
 ) -> Instruction {
-    Instruction {
+    let symbol_account = get_symbol_account(&args.symbol);
+    let mut instruction = Instruction {
         program_id,
         accounts: vec![
             AccountMeta::new(collection_account, true),
             AccountMeta::new_readonly(from_account, true),
             AccountMeta::new_readonly(sysvar::rent::id(), false),
             AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(get_config_account().0, false),
         ],
         data: CollectionInstruction::CreateCollectionAccount(args).try_to_vec().unwrap(),
+    };
+    if let Some((symbol_account, _)) = symbol_account {
+        instruction.accounts.push(AccountMeta::new(symbol_account, false));
     }
+    instruction
 }
 
 /// Creates an `IncludeToken` instruction.
+#[allow(clippy::too_many_arguments)]
 pub fn include_token(
     program_id: Pubkey,
     collection_account: Pubkey,
@@ -123,6 +503,7 @@ pub fn include_token(
     mint_token_account: Pubkey,
     index_account: Pubkey,
     payer_account: Pubkey,
+    position_account: Pubkey,
 ) -> Instruction{
     Instruction {
         program_id,
@@ -135,119 +516,886 @@ pub fn include_token(
             AccountMeta::new(payer_account, true),
             AccountMeta::new_readonly(sysvar::rent::id(), false),
             AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new(position_account, false),
+            AccountMeta::new_readonly(get_config_account().0, false),
         ],
         data: CollectionInstruction::IncludeToken.try_to_vec().unwrap(),
     }
 }
 
-/// Creates a `LightUpStarsOnce` instruction.
-pub fn light_up_stars_once(
+/// Creates a `IncludeTokenByCreator` instruction.
+pub fn include_token_by_creator(
     program_id: Pubkey,
     collection_account: Pubkey,
+    collection_authority_account: Pubkey,
+    mint_account: Pubkey,
+    index_account: Pubkey,
+    payer_account: Pubkey,
+    position_account: Pubkey,
 ) -> Instruction{
     Instruction {
         program_id,
         accounts: vec![
-            AccountMeta::new(collection_account, true),
+            AccountMeta::new(collection_account, false),
+            AccountMeta::new(collection_authority_account, true),
+            AccountMeta::new_readonly(mint_account, false),
+            AccountMeta::new_readonly(get_metadata_account(&mint_account).0, false),
+            AccountMeta::new(index_account, false),
+            AccountMeta::new(payer_account, true),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new(position_account, false),
+            AccountMeta::new_readonly(get_config_account().0, false),
         ],
-        data: CollectionInstruction::LightUpStarsOnce.try_to_vec().unwrap(),
+        data: CollectionInstruction::IncludeTokenByCreator.try_to_vec().unwrap(),
     }
 }
 
-/// Creates a `LightUpStarsHundred` instruction.
-pub fn light_up_stars_hundred(
+/// Creates a `SubmitToken` instruction.
+pub fn submit_token(
     program_id: Pubkey,
     collection_account: Pubkey,
-    source_account: Pubkey,
-    destination_account: Pubkey,
+    submitter_account: Pubkey,
+    mint_account: Pubkey,
+    mint_token_account: Pubkey,
+) -> Instruction{
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(collection_account, false),
+            AccountMeta::new(submitter_account, true),
+            AccountMeta::new_readonly(mint_account, false),
+            AccountMeta::new_readonly(mint_token_account, false),
+            AccountMeta::new(get_submission_account(&collection_account, &mint_account).0, false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(get_config_account().0, false),
+        ],
+        data: CollectionInstruction::SubmitToken.try_to_vec().unwrap(),
+    }
+}
+
+/// Creates a `SubmitToken` instruction taking over the submission of `previous_submitter_account`.
+pub fn replace_submission(
+    program_id: Pubkey,
+    collection_account: Pubkey,
+    submitter_account: Pubkey,
+    mint_account: Pubkey,
+    mint_token_account: Pubkey,
+    previous_submitter_account: Pubkey,
+) -> Instruction {
+    let mut instruction = submit_token(program_id, collection_account, submitter_account, mint_account, mint_token_account);
+    instruction.accounts.push(AccountMeta::new(previous_submitter_account, false));
+    instruction
+}
+
+/// Creates a `ApproveSubmission` instruction.
+#[allow(clippy::too_many_arguments)]
+pub fn approve_submission(
+    program_id: Pubkey,
+    collection_account: Pubkey,
+    collection_authority_account: Pubkey,
+    mint_account: Pubkey,
+    submitter_account: Pubkey,
+    submitter_token_account: Pubkey,
+    index_account: Pubkey,
+    payer_account: Pubkey,
+    position_account: Pubkey,
 ) -> Instruction{
     Instruction {
         program_id,
         accounts: vec![
             AccountMeta::new(collection_account, false),
-            AccountMeta::new(source_account, true),
-            AccountMeta::new(destination_account, false),
+            AccountMeta::new_readonly(collection_authority_account, true),
+            AccountMeta::new_readonly(mint_account, false),
+            AccountMeta::new(get_submission_account(&collection_account, &mint_account).0, false),
+            AccountMeta::new(submitter_account, false),
+            AccountMeta::new(index_account, false),
+            AccountMeta::new(payer_account, true),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
             AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new(position_account, false),
+            AccountMeta::new_readonly(get_config_account().0, false),
+            AccountMeta::new_readonly(submitter_token_account, false),
         ],
-        data: CollectionInstruction::LightUpStarsHundred.try_to_vec().unwrap(),
+        data: CollectionInstruction::ApproveSubmission.try_to_vec().unwrap(),
     }
 }
 
-/// Creates a `CloseAccount` instruction.
-pub fn close_account(
+/// Creates a `RejectSubmission` instruction.
+pub fn reject_submission(
     program_id: Pubkey,
-    state_account: Pubkey,
-    recipient_account: Pubkey,
-    authority_account: Pubkey,
-    account_type: AccountType,
+    collection_account: Pubkey,
+    collection_authority_account: Pubkey,
+    mint_account: Pubkey,
+    submitter_account: Pubkey,
 ) -> Instruction{
     Instruction {
         program_id,
         accounts: vec![
-            AccountMeta::new(state_account, false),
-            AccountMeta::new(recipient_account, false),
-            AccountMeta::new(authority_account, true),
+            AccountMeta::new_readonly(collection_account, false),
+            AccountMeta::new_readonly(collection_authority_account, true),
+            AccountMeta::new(get_submission_account(&collection_account, &mint_account).0, false),
+            AccountMeta::new(submitter_account, false),
         ],
-        data: CollectionInstruction::CloseAccount(account_type).try_to_vec().unwrap(),
+        data: CollectionInstruction::RejectSubmission.try_to_vec().unwrap(),
     }
 }
 
-/// Creates a `Withdraw` instruction.
-pub fn withdraw(
+/// Creates a `AddCurator` instruction.
+pub fn add_curator(
     program_id: Pubkey,
-    treasury_manager_account: Pubkey,
-    treasury_account: Pubkey,
+    collection_account: Pubkey,
+    collection_authority_account: Pubkey,
+    member_account: Pubkey,
+    payer_account: Pubkey,
+    permissions: u8,
+) -> Instruction{
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(collection_account, false),
+            AccountMeta::new_readonly(collection_authority_account, true),
+            AccountMeta::new_readonly(member_account, false),
+            AccountMeta::new(get_curator_account(&collection_account, &member_account).0, false),
+            AccountMeta::new(payer_account, true),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: CollectionInstruction::AddCurator { permissions }.try_to_vec().unwrap(),
+    }
+}
+
+/// Creates a `RemoveCurator` instruction.
+pub fn remove_curator(
+    program_id: Pubkey,
+    collection_account: Pubkey,
+    collection_authority_account: Pubkey,
+    member_account: Pubkey,
     recipient_account: Pubkey,
-) -> Instruction {
+) -> Instruction{
     Instruction {
         program_id,
         accounts: vec![
-            AccountMeta::new(treasury_manager_account, true),
-            AccountMeta::new(treasury_account, false),
+            AccountMeta::new_readonly(collection_account, false),
+            AccountMeta::new_readonly(collection_authority_account, true),
+            AccountMeta::new(get_curator_account(&collection_account, &member_account).0, false),
             AccountMeta::new(recipient_account, false),
         ],
-        data: CollectionInstruction::Withdraw.try_to_vec().unwrap(),
+        data: CollectionInstruction::RemoveCurator.try_to_vec().unwrap(),
     }
 }
 
-impl CreateCollectionAccountArgs {
-    const MAX_TITLE_LENGTH: usize = 32;
-
-    const MAX_SYMBOL_LENGTH: usize = 10;
-
-    const MAX_URI_LENGTH: usize = 200;
-
-    const MAX_DESCRIPTION_LENGTH: usize = 800;
-
-    const MAX_SHORT_DESCRIPTION_LENGTH: usize = 800;
-
-    const MAX_TAG_LENGTH: usize = 20;
+/// Appends the curator account of `curator` to an `IncludeToken`, `RemoveToken`,
+/// `ApproveSubmission` or `RejectSubmission` instruction signed by the curator.
+pub fn with_curator(
+    mut instruction: Instruction,
+    collection_account: Pubkey,
+    curator: Pubkey,
+) -> Instruction {
+    let (curator_account, _) = get_curator_account(&collection_account, &curator);
+    instruction.accounts.push(AccountMeta::new_readonly(curator_account, false));
+    instruction
+}
 
-    const MAX_TAGS_ARRAY_LENGTH: usize = 6;
+/// Appends the curator account of `curator` and the collection authority, which
+/// receives the refunds, to an `UpdateCollectionAccount` instruction signed by the curator.
+pub fn with_curator_and_authority(
+    instruction: Instruction,
+    collection_account: Pubkey,
+    curator: Pubkey,
+    collection_authority: Pubkey,
+) -> Instruction {
+    let mut instruction = with_curator(instruction, collection_account, curator);
+    instruction.accounts.push(AccountMeta::new(collection_authority, false));
+    instruction
+}
 
-    pub fn is_valid(&self) -> bool {
-        self.title.len() <= CreateCollectionAccountArgs::MAX_TITLE_LENGTH
-        && self.symbol.len() <= CreateCollectionAccountArgs::MAX_SYMBOL_LENGTH
-        && self.description.len() <= CreateCollectionAccountArgs::MAX_DESCRIPTION_LENGTH
-        && self.icon_image.len() <= CreateCollectionAccountArgs::MAX_URI_LENGTH 
-        && (self.header_image.is_none() || self.header_image.as_ref().unwrap().len() <= CreateCollectionAccountArgs::MAX_URI_LENGTH)
-        && (self.short_description.is_none() || self.short_description.as_ref().unwrap().len() <= CreateCollectionAccountArgs::MAX_SHORT_DESCRIPTION_LENGTH)
-        && (self.banner.is_none() || self.banner.as_ref().unwrap().len() <= CreateCollectionAccountArgs::MAX_URI_LENGTH)
-        && self.check_tags()
+/// Creates a `LightUpStarsOnce` instruction.
+pub fn light_up_stars_once(
+    program_id: Pubkey,
+    collection_account: Pubkey,
+) -> Instruction{
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(collection_account, true),
+        ],
+        data: CollectionInstruction::LightUpStarsOnce.try_to_vec().unwrap(),
+    }
+}
+
+/// Creates a `LightUpStarsHundred` instruction.
+pub fn light_up_stars_hundred(
+    program_id: Pubkey,
+    collection_account: Pubkey,
+    source_account: Pubkey,
+    destination_account: Pubkey,
+) -> Instruction{
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(collection_account, false),
+            AccountMeta::new(source_account, true),
+            AccountMeta::new(destination_account, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(get_config_account().0, false),
+            AccountMeta::new(get_star_receipt_account(&collection_account, &source_account).0, false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+        data: CollectionInstruction::LightUpStarsHundred.try_to_vec().unwrap(),
+    }
+}
+
+/// Creates a `LightUpStarsThousand` instruction.
+pub fn light_up_stars_thousand(
+    program_id: Pubkey,
+    collection_account: Pubkey,
+    source_account: Pubkey,
+    destination_account: Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(collection_account, false),
+            AccountMeta::new(source_account, true),
+            AccountMeta::new(destination_account, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(get_config_account().0, false),
+            AccountMeta::new(get_star_receipt_account(&collection_account, &source_account).0, false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+        data: CollectionInstruction::LightUpStarsThousand.try_to_vec().unwrap(),
+    }
+}
+
+/// Creates a `LightUpStars` instruction.
+pub fn light_up_stars(
+    program_id: Pubkey,
+    collection_account: Pubkey,
+    source_account: Pubkey,
+    destination_account: Pubkey,
+    amount: u64,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(collection_account, false),
+            AccountMeta::new(source_account, true),
+            AccountMeta::new(destination_account, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(get_config_account().0, false),
+            AccountMeta::new(get_star_receipt_account(&collection_account, &source_account).0, false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+        data: CollectionInstruction::LightUpStars { amount }.try_to_vec().unwrap(),
+    }
+}
+
+/// Creates a `CloseAccount` instruction.
+pub fn close_account(
+    program_id: Pubkey,
+    state_account: Pubkey,
+    recipient_account: Pubkey,
+    authority_account: Pubkey,
+    account_type: AccountType,
+) -> Instruction{
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(state_account, false),
+            AccountMeta::new(recipient_account, false),
+            AccountMeta::new(authority_account, true),
+        ],
+        data: CollectionInstruction::CloseAccount(account_type).try_to_vec().unwrap(),
+    }
+}
+
+/// Creates a `CloseAccount` instruction for a `CollectionIndexAccount`.
+#[allow(clippy::too_many_arguments)]
+pub fn close_index_account(
+    program_id: Pubkey,
+    index_account: Pubkey,
+    recipient_account: Pubkey,
+    authority_account: Pubkey,
+    collection_account: Pubkey,
+    position_account: Pubkey,
+    last_position_account: Pubkey,
+    last_index_account: Pubkey,
+    holder_token_account: Option<Pubkey>,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(index_account, false),
+        AccountMeta::new(recipient_account, false),
+        AccountMeta::new(authority_account, true),
+        AccountMeta::new(collection_account, false),
+        AccountMeta::new(position_account, false),
+        AccountMeta::new(last_position_account, false),
+        AccountMeta::new(last_index_account, false),
+    ];
+    if let Some(holder_token_account) = holder_token_account {
+        accounts.push(AccountMeta::new_readonly(holder_token_account, false));
+    }
+    Instruction {
+        program_id,
+        accounts,
+        data: CollectionInstruction::CloseAccount(AccountType::CollectionIndexAccount).try_to_vec().unwrap(),
+    }
+}
+
+/// Creates a `Withdraw` instruction.
+pub fn withdraw(
+    program_id: Pubkey,
+    treasury_manager_account: Pubkey,
+    treasury_account: Pubkey,
+    recipient_account: Pubkey,
+    amount: Option<u64>,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(treasury_manager_account, true),
+            AccountMeta::new(treasury_account, false),
+            AccountMeta::new(recipient_account, false),
+            AccountMeta::new_readonly(get_config_account().0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: CollectionInstruction::Withdraw { amount }.try_to_vec().unwrap(),
+    }
+}
+
+/// Creates an `UpdateCollectionAccount` instruction.
+pub fn update_collection_account(
+    program_id: Pubkey,
+    collection_account: Pubkey,
+    authority_account: Pubkey,
+    args: UpdateCollectionAccountArgs,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(collection_account, false),
+            AccountMeta::new(authority_account, true),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(get_config_account().0, false),
+        ],
+        data: CollectionInstruction::UpdateCollectionAccount(args).try_to_vec().unwrap(),
+    }
+}
+
+/// Creates an `UpdateCollectionAccount` instruction that may change the symbol,
+/// `current_symbol` is the symbol the collection holds before the update.
+pub fn update_collection_account_with_symbol(
+    program_id: Pubkey,
+    collection_account: Pubkey,
+    authority_account: Pubkey,
+    current_symbol: &str,
+    args: UpdateCollectionAccountArgs,
+) -> Instruction {
+    let symbol_accounts = match &args.symbol {
+        Some(symbol) => [get_symbol_account(current_symbol), get_symbol_account(symbol)],
+        None => [None, None],
+    };
+    let mut instruction = update_collection_account(program_id, collection_account, authority_account, args);
+    for (symbol_account, _) in symbol_accounts.iter().flatten() {
+        instruction.accounts.push(AccountMeta::new(*symbol_account, false));
+    }
+    instruction
+}
+
+/// Creates a `ProposeAuthority` instruction.
+pub fn propose_authority(
+    program_id: Pubkey,
+    collection_account: Pubkey,
+    authority_account: Pubkey,
+    new_authority_account: Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(collection_account, false),
+            AccountMeta::new(authority_account, true),
+            AccountMeta::new_readonly(new_authority_account, false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: CollectionInstruction::ProposeAuthority.try_to_vec().unwrap(),
+    }
+}
+
+/// Creates an `AcceptAuthority` instruction.
+pub fn accept_authority(
+    program_id: Pubkey,
+    collection_account: Pubkey,
+    new_authority_account: Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(collection_account, false),
+            AccountMeta::new(new_authority_account, true),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: CollectionInstruction::AcceptAuthority.try_to_vec().unwrap(),
+    }
+}
+
+/// Creates a `CancelAuthorityTransfer` instruction.
+pub fn cancel_authority_transfer(
+    program_id: Pubkey,
+    collection_account: Pubkey,
+    authority_account: Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(collection_account, false),
+            AccountMeta::new(authority_account, true),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: CollectionInstruction::CancelAuthorityTransfer.try_to_vec().unwrap(),
+    }
+}
+
+/// Creates a `LockCollection` instruction.
+pub fn lock_collection(
+    program_id: Pubkey,
+    collection_account: Pubkey,
+    authority_account: Pubkey,
+    metadata: bool,
+    membership: bool,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(collection_account, false),
+            AccountMeta::new(authority_account, true),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: CollectionInstruction::LockCollection { metadata, membership }.try_to_vec().unwrap(),
+    }
+}
+
+/// Creates a `MigrateCollectionAccount` instruction.
+pub fn migrate_collection_account(
+    program_id: Pubkey,
+    collection_account: Pubkey,
+    payer_account: Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(collection_account, false),
+            AccountMeta::new(payer_account, true),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: CollectionInstruction::MigrateCollectionAccount.try_to_vec().unwrap(),
+    }
+}
+
+/// Creates a `RemoveToken` instruction.
+#[allow(clippy::too_many_arguments)]
+pub fn remove_token(
+    program_id: Pubkey,
+    collection_account: Pubkey,
+    collection_authority_account: Pubkey,
+    index_account: Pubkey,
+    recipient_account: Pubkey,
+    position_account: Pubkey,
+    last_position_account: Pubkey,
+    last_index_account: Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(collection_account, false),
+            AccountMeta::new_readonly(collection_authority_account, true),
+            AccountMeta::new(index_account, false),
+            AccountMeta::new(recipient_account, false),
+            AccountMeta::new(position_account, false),
+            AccountMeta::new(last_position_account, false),
+            AccountMeta::new(last_index_account, false),
+        ],
+        data: CollectionInstruction::RemoveToken.try_to_vec().unwrap(),
+    }
+}
+
+/// Creates a `MigrateIndexAccount` instruction.
+pub fn migrate_index_account(
+    program_id: Pubkey,
+    collection_account: Pubkey,
+    collection_authority_account: Pubkey,
+    legacy_index_account: Pubkey,
+    index_account: Pubkey,
+    payer_account: Pubkey,
+    position_account: Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(collection_account, false),
+            AccountMeta::new(collection_authority_account, true),
+            AccountMeta::new(legacy_index_account, false),
+            AccountMeta::new(index_account, false),
+            AccountMeta::new(payer_account, true),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new(position_account, false),
+            AccountMeta::new_readonly(get_config_account().0, false),
+        ],
+        data: CollectionInstruction::MigrateIndexAccount.try_to_vec().unwrap(),
+    }
+}
+
+/// Creates an `InitializeConfig` instruction.
+pub fn initialize_config(
+    program_id: Pubkey,
+    upgrade_authority_account: Pubkey,
+    payer_account: Pubkey,
+    args: ConfigArgs,
+) -> Instruction {
+    let (program_data_account, _) = Pubkey::find_program_address(
+        &[program_id.as_ref()],
+        &bpf_loader_upgradeable::id(),
+    );
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(get_config_account().0, false),
+            AccountMeta::new_readonly(upgrade_authority_account, true),
+            AccountMeta::new_readonly(program_data_account, false),
+            AccountMeta::new(payer_account, true),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: CollectionInstruction::InitializeConfig(args).try_to_vec().unwrap(),
+    }
+}
+
+/// Creates an `UpdateConfig` instruction.
+pub fn update_config(
+    program_id: Pubkey,
+    admin_account: Pubkey,
+    args: ConfigArgs,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(get_config_account().0, false),
+            AccountMeta::new_readonly(admin_account, true),
+        ],
+        data: CollectionInstruction::UpdateConfig(args).try_to_vec().unwrap(),
+    }
+}
+
+/// Creates a `CreateCollectionPda` instruction.
+pub fn create_collection_pda(
+    program_id: Pubkey,
+    authority_account: Pubkey,
+    slug: String,
+    args: CreateCollectionAccountArgs,
+) -> Instruction {
+    let (collection_account, _) = get_collection_account(&authority_account, &slug);
+    let symbol_account = get_symbol_account(&args.symbol);
+    let mut instruction = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(collection_account, false),
+            AccountMeta::new(authority_account, true),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(get_config_account().0, false),
+        ],
+        data: CollectionInstruction::CreateCollectionPda { slug, args }.try_to_vec().unwrap(),
+    };
+    if let Some((symbol_account, _)) = symbol_account {
+        instruction.accounts.push(AccountMeta::new(symbol_account, false));
+    }
+    instruction
+}
+
+/// Creates a `CloseAccount` instruction for a `CollectionAccount` and its `symbol`.
+pub fn close_collection_account(
+    program_id: Pubkey,
+    collection_account: Pubkey,
+    recipient_account: Pubkey,
+    authority_account: Pubkey,
+    symbol: &str,
+) -> Instruction {
+    let symbol_account = get_symbol_account(symbol);
+    let mut instruction = close_account(
+        program_id,
+        collection_account,
+        recipient_account,
+        authority_account,
+        AccountType::CollectionAccount,
+    );
+    if let Some((symbol_account, _)) = symbol_account {
+        instruction.accounts.push(AccountMeta::new(symbol_account, false));
+    }
+    instruction
+}
+
+/// Creates a `SetPaused` instruction.
+pub fn set_paused(
+    program_id: Pubkey,
+    admin_account: Pubkey,
+    membership: bool,
+    payments: bool,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(get_config_account().0, false),
+            AccountMeta::new_readonly(admin_account, true),
+        ],
+        data: CollectionInstruction::SetPaused { membership, payments }.try_to_vec().unwrap(),
+    }
+}
+
+/// Slugs are used as a pda seed, so they are limited to 32 bytes of
+/// lowercase letters, digits and dashes.
+pub fn is_valid_slug(slug: &str) -> bool {
+    const MAX_SLUG_LENGTH: usize = 32;
+    !slug.is_empty()
+        && slug.len() <= MAX_SLUG_LENGTH
+        && slug.bytes().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == b'-')
+}
+
+impl CreateCollectionAccountArgs {
+    /// Checks the fields against the default limits.
+    pub fn is_valid(&self) -> bool {
+        self.is_valid_with_limits(&FieldLimits::default())
+    }
+
+    pub fn is_valid_with_limits(&self, limits: &FieldLimits) -> bool {
+        limits.check_length(&self.title, limits.max_title_length)
+        && limits.check_length(&self.symbol, limits.max_symbol_length)
+        && limits.check_length(&self.description, limits.max_description_length)
+        && limits.check_length(&self.icon_image, limits.max_uri_length)
+        && (self.header_image.is_none() || limits.check_length(self.header_image.as_ref().unwrap(), limits.max_uri_length))
+        && (self.short_description.is_none() || limits.check_length(self.short_description.as_ref().unwrap(), limits.max_short_description_length))
+        && (self.banner.is_none() || limits.check_length(self.banner.as_ref().unwrap(), limits.max_uri_length))
+        && (self.tags.is_none() || limits.check_tags(self.tags.as_ref().unwrap()))
+        && self.max_supply != Some(0)
     }
 
     pub fn check_tags(&self) -> bool {
         if self.tags.is_none() {
             return true;
         }
-        if self.tags.as_ref().unwrap().len() > CreateCollectionAccountArgs::MAX_TAGS_ARRAY_LENGTH {
-            return false;
+        FieldLimits::default().check_tags(self.tags.as_ref().unwrap())
+    }
+}
+
+impl UpdateCollectionAccountArgs {
+    /// Checks every provided field against the default limits.
+    pub fn is_valid(&self) -> bool {
+        self.is_valid_with_limits(&FieldLimits::default())
+    }
+
+    /// Checks every provided field against the same limits as `CreateCollectionAccountArgs`.
+    pub fn is_valid_with_limits(&self, limits: &FieldLimits) -> bool {
+        let check = |value: &Option<String>, max_length: u32| {
+            value.is_none() || limits.check_length(value.as_ref().unwrap(), max_length)
+        };
+        check(&self.title, limits.max_title_length)
+        && check(&self.symbol, limits.max_symbol_length)
+        && check(&self.description, limits.max_description_length)
+        && check(&self.icon_image, limits.max_uri_length)
+        && check(&self.header_image, limits.max_uri_length)
+        && check(&self.short_description, limits.max_short_description_length)
+        && check(&self.banner, limits.max_uri_length)
+        && (self.tags.is_none() || limits.check_tags(self.tags.as_ref().unwrap()))
+        && self.max_supply != Some(0)
+        && self.clear & !CLEAR_ALL == 0
+        && !(self.clears(CLEAR_HEADER_IMAGE) && self.header_image.is_some())
+        && !(self.clears(CLEAR_SHORT_DESCRIPTION) && self.short_description.is_some())
+        && !(self.clears(CLEAR_BANNER) && self.banner.is_some())
+        && !(self.clears(CLEAR_TAGS) && self.tags.is_some())
+    }
+
+    /// Whether the field of the `CLEAR_*` flag is reset.
+    pub fn clears(&self, flag: u8) -> bool {
+        self.clear & flag != 0
+    }
+
+    /// Whether any field other than the maximum supply is changed.
+    pub fn updates_metadata(&self) -> bool {
+        self.title.is_some()
+        || self.symbol.is_some()
+        || self.description.is_some()
+        || self.icon_image.is_some()
+        || self.header_image.is_some()
+        || self.short_description.is_some()
+        || self.banner.is_some()
+        || self.tags.is_some()
+        || self.clear != 0
+    }
+}
+
+impl CollectionInstruction {
+    pub fn name(&self) -> &'static str {
+        match self {
+            CollectionInstruction::CreateCollectionAccount(_) => "CreateCollectionAccount",
+            CollectionInstruction::IncludeToken => "IncludeToken",
+            CollectionInstruction::LightUpStarsOnce => "LightUpStarsOnce",
+            CollectionInstruction::LightUpStarsHundred => "LightUpStarsHundred",
+            CollectionInstruction::LightUpStarsThousand => "LightUpStarsThousand",
+            CollectionInstruction::CloseAccount(_) => "CloseAccount",
+            CollectionInstruction::Withdraw { .. } => "Withdraw",
+            CollectionInstruction::UpdateCollectionAccount(_) => "UpdateCollectionAccount",
+            CollectionInstruction::ProposeAuthority => "ProposeAuthority",
+            CollectionInstruction::AcceptAuthority => "AcceptAuthority",
+            CollectionInstruction::CancelAuthorityTransfer => "CancelAuthorityTransfer",
+            CollectionInstruction::RemoveToken => "RemoveToken",
+            CollectionInstruction::MigrateIndexAccount => "MigrateIndexAccount",
+            CollectionInstruction::InitializeConfig(_) => "InitializeConfig",
+            CollectionInstruction::UpdateConfig(_) => "UpdateConfig",
+            CollectionInstruction::LightUpStars { .. } => "LightUpStars",
+            CollectionInstruction::SetPaused { .. } => "SetPaused",
+            CollectionInstruction::CreateCollectionPda { .. } => "CreateCollectionPda",
+            CollectionInstruction::IncludeTokenByCreator => "IncludeTokenByCreator",
+            CollectionInstruction::SubmitToken => "SubmitToken",
+            CollectionInstruction::ApproveSubmission => "ApproveSubmission",
+            CollectionInstruction::RejectSubmission => "RejectSubmission",
+            CollectionInstruction::AddCurator { .. } => "AddCurator",
+            CollectionInstruction::RemoveCurator => "RemoveCurator",
+            CollectionInstruction::LockCollection { .. } => "LockCollection",
+            CollectionInstruction::MigrateCollectionAccount => "MigrateCollectionAccount",
         }
-        for tag in self.tags.as_ref().unwrap() {
-            if tag.len() >= CreateCollectionAccountArgs::MAX_TAG_LENGTH {
-                return false;
+    }
+
+    /// Roles of the accounts expected by the instruction, the required accounts
+    /// followed by the optional ones, in the order documented on each variant.
+    pub fn account_names(&self) -> (&'static [&'static str], &'static [&'static str]) {
+        const STARS: &[&str] = &["collection", "payer", "treasury", "system_program", "config", "star_receipt", "rent"];
+        const CREATE: &[&str] = &["collection", "authority", "rent", "system_program", "config"];
+        const UPDATE: &[&str] = &["collection", "authority", "rent", "system_program", "config"];
+        const UPDATE_SYMBOL: &[&str] = &["collection", "authority", "rent", "system_program", "config", "symbol", "new_symbol"];
+        match self {
+            CollectionInstruction::CreateCollectionAccount(_)
+            | CollectionInstruction::CreateCollectionPda { .. } => (CREATE, &["symbol"]),
+            CollectionInstruction::IncludeToken => (
+                &["collection", "authority", "mint", "token_account", "index", "payer", "rent", "system_program", "position", "config"],
+                &["curator"],
+            ),
+            CollectionInstruction::LightUpStarsOnce => (&["collection"], &[]),
+            CollectionInstruction::LightUpStarsHundred
+            | CollectionInstruction::LightUpStarsThousand
+            | CollectionInstruction::LightUpStars { .. } => (STARS, &[]),
+            CollectionInstruction::CloseAccount(AccountType::CollectionAccount | AccountType::VersionedCollectionAccount) => {
+                (&["collection", "recipient", "authority"], &["symbol"])
+            }
+            CollectionInstruction::CloseAccount(AccountType::CollectionIndexAccount) => (
+                &["index", "recipient", "authority", "collection", "position", "last_position", "last_index"],
+                &["holder_token_account"],
+            ),
+            CollectionInstruction::CloseAccount(_) => (&["account", "recipient", "authority"], &[]),
+            CollectionInstruction::Withdraw { .. } => {
+                (&["treasury_manager", "treasury", "recipient", "config", "system_program"], &[])
+            }
+            CollectionInstruction::UpdateCollectionAccount(args) if args.symbol.is_some() => {
+                (UPDATE_SYMBOL, &["curator", "collection_authority"])
+            }
+            CollectionInstruction::UpdateCollectionAccount(_) => (UPDATE, &["curator", "collection_authority"]),
+            CollectionInstruction::ProposeAuthority => {
+                (&["collection", "authority", "proposed_authority", "rent", "system_program"], &[])
+            }
+            CollectionInstruction::AcceptAuthority => {
+                (&["collection", "pending_authority", "rent", "system_program"], &[])
+            }
+            CollectionInstruction::CancelAuthorityTransfer
+            | CollectionInstruction::LockCollection { .. } => {
+                (&["collection", "authority", "rent", "system_program"], &[])
+            }
+            CollectionInstruction::RemoveToken => (
+                &["collection", "authority", "index", "recipient", "position", "last_position", "last_index"],
+                &["curator"],
+            ),
+            CollectionInstruction::MigrateIndexAccount => (
+                &["collection", "authority", "legacy_index", "index", "payer", "rent", "system_program", "position", "config"],
+                &[],
+            ),
+            CollectionInstruction::InitializeConfig(_) => {
+                (&["config", "upgrade_authority", "program_data", "payer", "rent", "system_program"], &[])
+            }
+            CollectionInstruction::UpdateConfig(_)
+            | CollectionInstruction::SetPaused { .. } => (&["config", "admin"], &[]),
+            CollectionInstruction::IncludeTokenByCreator => (
+                &["collection", "authority", "mint", "metadata", "index", "payer", "rent", "system_program", "position", "config"],
+                &[],
+            ),
+            CollectionInstruction::SubmitToken => (
+                &["collection", "submitter", "mint", "token_account", "submission", "rent", "system_program", "config"],
+                &["previous_submitter"],
+            ),
+            CollectionInstruction::ApproveSubmission => (
+                &["collection", "authority", "mint", "submission", "submitter", "index", "payer", "rent", "system_program", "position", "config", "submitter_token_account"],
+                &["curator"],
+            ),
+            CollectionInstruction::RejectSubmission => {
+                (&["collection", "authority", "submission", "submitter"], &["curator"])
+            }
+            CollectionInstruction::AddCurator { .. } => (
+                &["collection", "authority", "member", "curator", "payer", "rent", "system_program"],
+                &[],
+            ),
+            CollectionInstruction::RemoveCurator => (&["collection", "authority", "curator", "recipient"], &[]),
+            CollectionInstruction::MigrateCollectionAccount => {
+                (&["collection", "payer", "rent", "system_program"], &[])
             }
         }
-        true
     }
-}
\ No newline at end of file
+}
+
+/// Account of a decoded instruction, named after the role it plays.
+#[derive(PartialEq, Debug, Clone)]
+pub struct ParsedAccount {
+    pub name: &'static str,
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+/// A collection instruction decoded for display.
+#[derive(PartialEq, Debug, Clone)]
+pub struct ParsedCollectionInstruction {
+    pub instruction: CollectionInstruction,
+    /// Accounts past the documented ones are named "unknown".
+    pub accounts: Vec<ParsedAccount>,
+}
+
+/// Decodes the data of a collection instruction and names its accounts.
+pub fn decode_instruction(instruction: &Instruction) -> Result<ParsedCollectionInstruction, ProgramError> {
+    if instruction.program_id != crate::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let collection_instruction = CollectionInstruction::try_from_slice(&instruction.data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+    let (required, optional) = collection_instruction.account_names();
+    if instruction.accounts.len() < required.len() {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let names = required.iter().chain(optional.iter());
+    let accounts = instruction
+        .accounts
+        .iter()
+        .zip(names.copied().chain(std::iter::repeat("unknown")))
+        .map(|(meta, name)| ParsedAccount {
+            name,
+            pubkey: meta.pubkey,
+            is_signer: meta.is_signer,
+            is_writable: meta.is_writable,
+        })
+        .collect();
+    Ok(ParsedCollectionInstruction {
+        instruction: collection_instruction,
+        accounts,
+    })
+}
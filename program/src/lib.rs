@@ -7,5 +7,7 @@ pub mod utils;
 pub mod instruction;
 pub mod error; 
 pub mod state;
+pub mod metadata;
+pub mod event;
 
 solana_program::declare_id!("co111CrRL738X8TKrqmLcNBstgLFZjuMtZRBW2FGpbC");
\ No newline at end of file
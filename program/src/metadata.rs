@@ -0,0 +1,71 @@
+//! Read only view of the Metaplex token metadata account, limited to the
+//! leading fields needed to find the verified creators of a mint.
+
+use {
+    crate::error::CollectionError,
+    borsh::{BorshDeserialize, BorshSerialize},
+    solana_program::{
+        account_info::AccountInfo,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+};
+
+/// The Metaplex token metadata program.
+pub mod program {
+    solana_program::declare_id!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+}
+
+pub const PREFIX: &str = "metadata";
+
+/// Account key of `MetadataV1` in the token metadata program.
+pub const METADATA_V1_KEY: u8 = 4;
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct Creator {
+    pub address: Pubkey,
+    pub verified: bool,
+    pub share: u8,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct Data {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub seller_fee_basis_points: u16,
+    pub creators: Option<Vec<Creator>>,
+}
+
+/// Leading fields of the metadata account, the fields appended by later
+/// versions of the metadata program are ignored.
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct Metadata {
+    pub key: u8,
+    pub update_authority: Pubkey,
+    pub mint: Pubkey,
+    pub data: Data,
+}
+
+impl Metadata {
+    pub fn from_account_info(account_info: &AccountInfo) -> Result<Metadata, ProgramError> {
+        if *account_info.owner != program::id() {
+            return Err(CollectionError::InvalidMetadataAccount.into());
+        }
+        let data = account_info.data.borrow();
+        let metadata = Metadata::deserialize(&mut &data[..])
+            .map_err(|_| CollectionError::InvalidMetadataAccount)?;
+        if metadata.key != METADATA_V1_KEY {
+            return Err(CollectionError::InvalidMetadataAccount.into());
+        }
+        Ok(metadata)
+    }
+
+    pub fn is_verified_creator(&self, address: &Pubkey) -> bool {
+        self.data
+            .creators
+            .iter()
+            .flatten()
+            .any(|creator| creator.verified && creator.address == *address)
+    }
+}
@@ -1,28 +1,32 @@
 use solana_program::program_pack::IsInitialized;
 use {
     crate::{
-        instruction::{CollectionInstruction, CreateCollectionAccountArgs},
-        utils::{create_new_account, create_or_allocate_account_raw, get_index_account, get_treasury_account},
-        state::{PREFIX, AccountType, CollectionAccountData, CollectionIndexAccountData},
+        instruction::{is_valid_slug, CollectionInstruction, CLEAR_BANNER, CLEAR_HEADER_IMAGE, CLEAR_SHORT_DESCRIPTION, CLEAR_TAGS, ConfigArgs, CreateCollectionAccountArgs, UpdateCollectionAccountArgs},
+        utils::{create_new_account, create_or_allocate_account_raw, get_collection_account, get_config_account, get_curator_account, get_index_account, get_legacy_index_account, get_metadata_account, get_position_account, get_star_receipt_account, get_submission_account, get_symbol_account, get_treasury_account, normalize_symbol, resize_account},
+        state::{PREFIX, AccountType, CollectionAccountData, CollectionIndexAccountData, CollectionPositionAccountData, ConfigAccountData, FieldLimits, PauseClass, StarReceiptAccountData, SubmissionAccountData, SymbolAccountData, CuratorAccountData, collection_account_type, COLLECTION_ACCOUNT_VERSION, CURATOR_ALL, CURATOR_EDIT, CURATOR_INCLUDE, CURATOR_REMOVE},
         error::CollectionError,
+        event::{AccountClosed, CollectionCreated, CollectionEvent, CollectionMigrated, StarsLit, TokenIncluded, TokenRemoved, Withdrawn},
+        metadata::Metadata,
         check_id,
     },
     solana_program::{
         account_info::{AccountInfo, next_account_info},
+        bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+        program_utils::limited_deserialize,
         system_instruction::transfer,
         entrypoint::ProgramResult, 
         pubkey::Pubkey,
         program_pack::Pack,
-        native_token::sol_to_lamports,
-        program::invoke,
+        program::{invoke, invoke_signed},
         program_option::COption,
+        program_error::ProgramError,
         msg,
+        sysvar::{clock::Clock, rent::Rent, Sysvar},
     },
     borsh::{BorshDeserialize, BorshSerialize},
     spl_token::state::Mint as spl_mint,
     spl_token::state::Account as spl_account,
 };
-use std::str::FromStr;
 
 pub fn process_instruction(
     program_id: &Pubkey,
@@ -30,6 +34,9 @@ pub fn process_instruction(
     input: &[u8],
 ) -> ProgramResult {
     let instruction = CollectionInstruction::try_from_slice(input)?;
+    if let Some(class) = pause_class(&instruction) {
+        assert_not_paused(program_id, accounts, class)?;
+    }
     match instruction {
         CollectionInstruction::CreateCollectionAccount(args) => {
             msg!("Instruction: Create Collection Account");
@@ -51,13 +58,89 @@ pub fn process_instruction(
             msg!("Instruction: Light Up Stars One Hundred");
             process_light_up_stars_hundred(program_id, accounts)
         },
+        CollectionInstruction::LightUpStars { amount } => {
+            msg!("Instruction: Light Up Stars");
+            process_light_up_stars(program_id, accounts, amount)
+        },
+        CollectionInstruction::CreateCollectionPda { slug, args } => {
+            msg!("Instruction: Create Collection Pda");
+            process_create_collection_pda(program_id, accounts, &slug, &args)
+        },
+        CollectionInstruction::IncludeTokenByCreator => {
+            msg!("Instruction: Include Token By Creator");
+            process_include_token_by_creator(program_id, accounts)
+        },
+        CollectionInstruction::SubmitToken => {
+            msg!("Instruction: Submit Token");
+            process_submit_token(program_id, accounts)
+        },
+        CollectionInstruction::ApproveSubmission => {
+            msg!("Instruction: Approve Submission");
+            process_approve_submission(program_id, accounts)
+        },
+        CollectionInstruction::RejectSubmission => {
+            msg!("Instruction: Reject Submission");
+            process_reject_submission(program_id, accounts)
+        },
+        CollectionInstruction::AddCurator { permissions } => {
+            msg!("Instruction: Add Curator");
+            process_add_curator(program_id, accounts, permissions)
+        },
+        CollectionInstruction::RemoveCurator => {
+            msg!("Instruction: Remove Curator");
+            process_remove_curator(program_id, accounts)
+        },
+        CollectionInstruction::LockCollection { metadata, membership } => {
+            msg!("Instruction: Lock Collection");
+            process_lock_collection(program_id, accounts, metadata, membership)
+        },
+        CollectionInstruction::MigrateCollectionAccount => {
+            msg!("Instruction: Migrate Collection Account");
+            process_migrate_collection_account(program_id, accounts)
+        },
+        CollectionInstruction::SetPaused { membership, payments } => {
+            msg!("Instruction: Set Paused");
+            process_set_paused(program_id, accounts, membership, payments)
+        },
         CollectionInstruction::CloseAccount(account_type) => {
             msg!("Instruction: Close Account");
             process_close_account(program_id, accounts, account_type)
         },
-        CollectionInstruction::Withdraw => {
+        CollectionInstruction::Withdraw { amount } => {
             msg!("Instruction: Withdraw");
-            process_withdraw(program_id, accounts)
+            process_withdraw(program_id, accounts, amount)
+        },
+        CollectionInstruction::UpdateCollectionAccount(args) => {
+            msg!("Instruction: Update Collection Account");
+            process_update_collection_account(program_id, accounts, &args)
+        },
+        CollectionInstruction::ProposeAuthority => {
+            msg!("Instruction: Propose Authority");
+            process_propose_authority(program_id, accounts)
+        },
+        CollectionInstruction::AcceptAuthority => {
+            msg!("Instruction: Accept Authority");
+            process_accept_authority(program_id, accounts)
+        },
+        CollectionInstruction::CancelAuthorityTransfer => {
+            msg!("Instruction: Cancel Authority Transfer");
+            process_cancel_authority_transfer(program_id, accounts)
+        },
+        CollectionInstruction::RemoveToken => {
+            msg!("Instruction: Remove Token");
+            process_remove_token(program_id, accounts)
+        },
+        CollectionInstruction::MigrateIndexAccount => {
+            msg!("Instruction: Migrate Index Account");
+            process_migrate_index_account(program_id, accounts)
+        },
+        CollectionInstruction::InitializeConfig(args) => {
+            msg!("Instruction: Initialize Config");
+            process_initialize_config(program_id, accounts, &args)
+        },
+        CollectionInstruction::UpdateConfig(args) => {
+            msg!("Instruction: Update Config");
+            process_update_config(program_id, accounts, &args)
         }
     }
 }
@@ -68,26 +151,19 @@ pub fn process_create_collection_account(
     args: &CreateCollectionAccountArgs,
 ) -> ProgramResult {
     assert_program_id(program_id)?;
-    assert_create_collection_args(args)?;
     let account_info_iter = &mut accounts.iter();
     let collection_account_info = next_account_info(account_info_iter)?;
     let form_account_info = next_account_info(account_info_iter)?;
     let rent_account_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let config_account_info = next_account_info(account_info_iter)?;
+    let symbol_account_info = next_account_info(account_info_iter).ok();
 
-    let collection_account_data = CollectionAccountData {
-        account_type: AccountType::CollectionAccount,
-        title: args.title.clone(),
-        symbol: args.symbol.clone(),
-        description: args.description.clone(),
-        icon_image: args.icon_image.clone(),
-        stars: 0 as u64,
-        supply: 0 as u64,
-        authority: *form_account_info.key,
-        header_image: args.header_image.clone(),
-        short_description: args.short_description.clone(),
-        banner: args.banner.clone(),
-        tags: args.tags.clone(),
-    };
+    let config = load_config(program_id, config_account_info)?;
+    assert_create_collection_args(args, &config.field_limits)?;
+    assert_uninitialized(collection_account_info)?;
+
+    let collection_account_data = new_collection_account_data(form_account_info.key, args);
     let mut data: Vec<u8> = Vec::new();
     collection_account_data.serialize(&mut data)?;
     create_new_account(
@@ -98,280 +174,1980 @@ pub fn process_create_collection_account(
         rent_account_info,
     ).unwrap();
     
-    collection_account_data.serialize(&mut *collection_account_info.data.borrow_mut())?;
-    Ok(())
+    collection_account_data.serialize(&mut &mut collection_account_info.data.borrow_mut()[..])?;
+    if let Some(symbol_account_info) = symbol_account_info {
+        reserve_symbol(
+            program_id,
+            collection_account_info,
+            &args.symbol,
+            symbol_account_info,
+            form_account_info,
+            rent_account_info,
+            system_program_info,
+        )?;
+    }
+    emit_collection_created(collection_account_info, &collection_account_data)
 }
 
-pub fn process_include_token(
+pub fn process_create_collection_pda(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
+    slug: &str,
+    args: &CreateCollectionAccountArgs,
 ) -> ProgramResult {
     assert_program_id(program_id)?;
     let account_info_iter = &mut accounts.iter();
     let collection_account_info = next_account_info(account_info_iter)?;
-    let collection_auth_account_info = next_account_info(account_info_iter)?;
-    let mint_account_info = next_account_info(account_info_iter)?;
-    let mint_token_account_info = next_account_info(account_info_iter)?;
-    let index_account_info = next_account_info(account_info_iter)?;
-    let payer_account_info = next_account_info(account_info_iter)?;
+    let authority_account_info = next_account_info(account_info_iter)?;
     let rent_sysvar_info = next_account_info(account_info_iter)?;
     let system_program_info = next_account_info(account_info_iter)?;
+    let config_account_info = next_account_info(account_info_iter)?;
+    let symbol_account_info = next_account_info(account_info_iter).ok();
 
-    let mut collection_account_data = CollectionAccountData::try_from_slice_unchecked(
-        &collection_account_info.data.borrow_mut())?;
-    if !collection_account_data.is_initialized() {
-        return Err(CollectionError::Uninitialized.into());
+    let config = load_config(program_id, config_account_info)?;
+    assert_create_collection_args(args, &config.field_limits)?;
+    if !is_valid_slug(slug) {
+        return Err(CollectionError::InvalidInstructionArguments.into());
     }
-    // check collection's authority 
-    if collection_account_data.authority != *collection_auth_account_info.key 
-        || !collection_auth_account_info.is_signer {
+    if !authority_account_info.is_signer {
         return Err(CollectionError::NotCollectionAuthority.into());
     }
-    assert_mint_authority(
-        mint_account_info,
-        mint_token_account_info,
-        collection_auth_account_info,
-    )?;
-    
-    let (index_account, bump_seed) = get_index_account(
-        mint_account_info.key, 
-    );
-    if index_account != *index_account_info.key {
-        return Err(CollectionError::CollectionIndexAccountMismatch.into());
+    let (collection_account, bump_seed) = get_collection_account(authority_account_info.key, slug);
+    if collection_account != *collection_account_info.key {
+        return Err(CollectionError::InvalidCollectionAccount.into());
     }
+    assert_uninitialized(collection_account_info)?;
+
+    let collection_account_data = new_collection_account_data(authority_account_info.key, args);
+    let mut data: Vec<u8> = Vec::new();
+    collection_account_data.serialize(&mut data)?;
     let signer_seeds = &[
         PREFIX.as_bytes(),
-        program_id.as_ref(),
-        mint_account_info.key.as_ref(),
+        authority_account_info.key.as_ref(),
+        slug.as_bytes(),
         &[bump_seed],
     ];
     create_or_allocate_account_raw(
         *program_id,
-        index_account_info,
+        collection_account_info,
         rent_sysvar_info,
         system_program_info,
-        payer_account_info,
-        CollectionIndexAccountData::LEN,
+        authority_account_info,
+        data.len(),
         signer_seeds,
     )?;
-    let index_account_data = CollectionIndexAccountData::new(
-        *collection_account_info.key, 
-        *mint_account_info.key, 
-        collection_account_data.supply,
-    );
 
-    index_account_data.serialize(&mut *index_account_info.data.borrow_mut())?;
-    collection_account_data.supply += 1;
-    collection_account_data.serialize(&mut *collection_account_info.data.borrow_mut())?;
-    Ok(())
+    msg!("create collection {} at {}", slug, collection_account_info.key);
+    collection_account_data.serialize(&mut &mut collection_account_info.data.borrow_mut()[..])?;
+    if let Some(symbol_account_info) = symbol_account_info {
+        reserve_symbol(
+            program_id,
+            collection_account_info,
+            &args.symbol,
+            symbol_account_info,
+            authority_account_info,
+            rent_sysvar_info,
+            system_program_info,
+        )?;
+    }
+    emit_collection_created(collection_account_info, &collection_account_data)
 }
 
-pub fn process_light_up_stars_once(
+pub fn process_update_collection_account(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
+    args: &UpdateCollectionAccountArgs,
 ) -> ProgramResult {
     assert_program_id(program_id)?;
     let account_info_iter = &mut accounts.iter();
     let collection_account_info = next_account_info(account_info_iter)?;
-    let mut collection_account_data = CollectionAccountData::try_from_slice_unchecked(
-        &collection_account_info.data.borrow_mut())?;
-    if !collection_account_data.is_initialized() {
-        return Err(CollectionError::Uninitialized.into());
+    let authority_account_info = next_account_info(account_info_iter)?;
+    let rent_sysvar_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let config_account_info = next_account_info(account_info_iter)?;
+
+    let config = load_config(program_id, config_account_info)?;
+    assert_update_collection_args(args, &config.field_limits)?;
+
+    let mut collection_account_data = load_collection_account(program_id, collection_account_info)?;
+    // the current and the new symbol accounts, only when the symbol is set and only
+    // for symbols that can be reserved
+    let mut symbol_account_info = |symbol: &str| {
+        get_symbol_account(symbol).map(|_| next_account_info(account_info_iter)).transpose()
+    };
+    let (symbol_account_info, new_symbol_account_info) = match &args.symbol {
+        Some(symbol) => (symbol_account_info(&collection_account_data.symbol)?, symbol_account_info(symbol)?),
+        None => (None, None),
+    };
+    let curator_account_info = next_account_info(account_info_iter).ok();
+    let mut permission = CURATOR_EDIT;
+    if args.max_supply.is_some() {
+        // the cap limits the membership, it takes the permissions to include and remove
+        permission = CURATOR_INCLUDE | CURATOR_REMOVE;
+        if args.updates_metadata() {
+            permission |= CURATOR_EDIT;
+        }
+    }
+    assert_collection_authority(
+        program_id,
+        collection_account_info,
+        &collection_account_data,
+        authority_account_info,
+        curator_account_info,
+        permission,
+    )?;
+    // the rent the collection authority funded goes back to it, a curator only pays
+    let refund_account_info = if collection_account_data.authority == *authority_account_info.key {
+        authority_account_info
+    } else {
+        let collection_authority_account_info = next_account_info(account_info_iter)?;
+        if collection_account_data.authority != *collection_authority_account_info.key {
+            return Err(CollectionError::NotCollectionAuthority.into());
+        }
+        collection_authority_account_info
+    };
+    // saving moves the account to the current layout, the cap is checked against
+    // the supply it is saved with
+    upgrade_collection_account_data(&mut collection_account_data, collection_account_info)?;
+    if args.updates_metadata() {
+        assert_metadata_mutable(&collection_account_data)?;
+    }
+    if args.max_supply.is_some() {
+        assert_membership_mutable(&collection_account_data)?;
+    }
+
+    if let Some(title) = &args.title {
+        collection_account_data.title = title.clone();
+    }
+    let mut released_symbol = None;
+    if let Some(symbol) = &args.symbol {
+        if normalize_symbol(symbol) != normalize_symbol(&collection_account_data.symbol) {
+            if let Some(new_symbol_account_info) = new_symbol_account_info {
+                reserve_symbol(
+                    program_id,
+                    collection_account_info,
+                    symbol,
+                    new_symbol_account_info,
+                    authority_account_info,
+                    rent_sysvar_info,
+                    system_program_info,
+                )?;
+            }
+            released_symbol = symbol_account_info
+                .map(|symbol_account_info| (collection_account_data.symbol.clone(), symbol_account_info));
+        }
+        collection_account_data.symbol = symbol.clone();
+    }
+    if let Some(description) = &args.description {
+        collection_account_data.description = description.clone();
+    }
+    if let Some(icon_image) = &args.icon_image {
+        collection_account_data.icon_image = icon_image.clone();
+    }
+    if args.header_image.is_some() || args.clears(CLEAR_HEADER_IMAGE) {
+        collection_account_data.header_image = args.header_image.clone();
+    }
+    if args.short_description.is_some() || args.clears(CLEAR_SHORT_DESCRIPTION) {
+        collection_account_data.short_description = args.short_description.clone();
+    }
+    if args.banner.is_some() || args.clears(CLEAR_BANNER) {
+        collection_account_data.banner = args.banner.clone();
+    }
+    if args.tags.is_some() || args.clears(CLEAR_TAGS) {
+        collection_account_data.tags = args.tags.clone();
+    }
+    if let Some(max_supply) = args.max_supply {
+        // the cap only ever goes down and never below the tokens already included
+        if max_supply < collection_account_data.supply
+            || matches!(collection_account_data.max_supply, Some(current) if max_supply > current) {
+            return Err(CollectionError::InvalidInstructionArguments.into());
+        }
+        collection_account_data.max_supply = Some(max_supply);
+    }
+
+    save_collection_account_data(
+        &mut collection_account_data,
+        collection_account_info,
+        authority_account_info,
+        refund_account_info,
+        rent_sysvar_info,
+        system_program_info,
+    )?;
+    // the rent of the previous symbol goes back once the collection is resized
+    if let Some((symbol, symbol_account_info)) = released_symbol {
+        release_symbol(
+            program_id,
+            collection_account_info,
+            &symbol,
+            symbol_account_info,
+            refund_account_info,
+        )?;
     }
-    collection_account_data.stars += 1;
-    collection_account_data.serialize(&mut *collection_account_info.data.borrow_mut())?;
     Ok(())
 }
 
-pub fn process_light_up_stars_hundred(
+pub fn process_propose_authority(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
 ) -> ProgramResult {
     assert_program_id(program_id)?;
     let account_info_iter = &mut accounts.iter();
     let collection_account_info = next_account_info(account_info_iter)?;
-    let source_account_info = next_account_info(account_info_iter)?;
-    let destination_account_info = next_account_info(account_info_iter)?;
+    let authority_account_info = next_account_info(account_info_iter)?;
+    let new_authority_account_info = next_account_info(account_info_iter)?;
+    let rent_sysvar_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
 
-    assert_treasury_account(destination_account_info)?;
-    let lamports = sol_to_lamports(0.01);
-    invoke(
-        &transfer(
-            source_account_info.key,
-            destination_account_info.key,
-            lamports,
-        ), 
-        &[
-            source_account_info.clone(),
-            destination_account_info.clone(),
-        ],
-    )?;
-    let mut collection_account_data = CollectionAccountData::try_from_slice_unchecked(
-        &collection_account_info.data.borrow_mut())?;
-    if !collection_account_data.is_initialized() {
-        return Err(CollectionError::Uninitialized.into());
+    let mut collection_account_data = load_collection_account(program_id, collection_account_info)?;
+    if collection_account_data.authority != *authority_account_info.key
+        || !authority_account_info.is_signer {
+        return Err(CollectionError::NotCollectionAuthority.into());
     }
-    collection_account_data.stars += 100;
-    collection_account_data.serialize(&mut *collection_account_info.data.borrow_mut())?;
 
-    Ok(())
+    msg!("pending authority: {}", new_authority_account_info.key.to_string());
+    collection_account_data.pending_authority = Some(*new_authority_account_info.key);
+    save_collection_account_data(
+        &mut collection_account_data,
+        collection_account_info,
+        authority_account_info,
+        authority_account_info,
+        rent_sysvar_info,
+        system_program_info,
+    )
 }
 
-pub fn process_light_up_stars_thousand(
+pub fn process_accept_authority(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
 ) -> ProgramResult {
     assert_program_id(program_id)?;
     let account_info_iter = &mut accounts.iter();
     let collection_account_info = next_account_info(account_info_iter)?;
-    let source_account_info = next_account_info(account_info_iter)?;
-    let destination_account_info = next_account_info(account_info_iter)?;
+    let new_authority_account_info = next_account_info(account_info_iter)?;
+    let rent_sysvar_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
 
-    assert_treasury_account(destination_account_info)?;
-    let lamports = sol_to_lamports(1 as f64);
-    invoke(
-        &transfer(
-            source_account_info.key,
-            destination_account_info.key,
-            lamports,
-        ), 
-        &[
-            source_account_info.clone(),
-            destination_account_info.clone(),
-        ],
-    )?;
-    let mut collection_account_data = CollectionAccountData::try_from_slice_unchecked(
-        &collection_account_info.data.borrow_mut())?;
-    if !collection_account_data.is_initialized() {
-        return Err(CollectionError::Uninitialized.into());
+    let mut collection_account_data = load_collection_account(program_id, collection_account_info)?;
+    match collection_account_data.pending_authority {
+        None => return Err(CollectionError::NoPendingAuthority.into()),
+        Some(pending_authority) => {
+            if pending_authority != *new_authority_account_info.key
+                || !new_authority_account_info.is_signer {
+                return Err(CollectionError::NotPendingAuthority.into());
+            }
+        }
     }
-    collection_account_data.stars += 1000;
-    collection_account_data.serialize(&mut *collection_account_info.data.borrow_mut())?;
 
-    Ok(())
+    collection_account_data.authority = *new_authority_account_info.key;
+    collection_account_data.pending_authority = None;
+    save_collection_account_data(
+        &mut collection_account_data,
+        collection_account_info,
+        new_authority_account_info,
+        new_authority_account_info,
+        rent_sysvar_info,
+        system_program_info,
+    )
 }
 
-pub fn process_withdraw(
+pub fn process_cancel_authority_transfer(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
 ) -> ProgramResult {
     assert_program_id(program_id)?;
     let account_info_iter = &mut accounts.iter();
-    let treasury_manager_account_info = next_account_info(account_info_iter)?;
-    let treasury_account_info = next_account_info(account_info_iter)?;
-    let recipient_account_info = next_account_info(account_info_iter)?;
+    let collection_account_info = next_account_info(account_info_iter)?;
+    let authority_account_info = next_account_info(account_info_iter)?;
+    let rent_sysvar_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
 
-    assert_treasury_manager(treasury_manager_account_info)?;
-    assert_treasury_account(treasury_account_info)?;
-    let lamports = treasury_account_info.lamports();
-    if lamports == 0 {
-        return Err(CollectionError::InsufficientFunds.into());
+    let mut collection_account_data = load_collection_account(program_id, collection_account_info)?;
+    if collection_account_data.authority != *authority_account_info.key
+        || !authority_account_info.is_signer {
+        return Err(CollectionError::NotCollectionAuthority.into());
     }
-    let recipient_starting_lamports = recipient_account_info.lamports();
-    **recipient_account_info.lamports.borrow_mut() = recipient_starting_lamports.checked_add(treasury_account_info.lamports()).unwrap();
-    **treasury_account_info.lamports.borrow_mut() = 0;
-    Ok(())
+    if collection_account_data.pending_authority.is_none() {
+        return Err(CollectionError::NoPendingAuthority.into());
+    }
+
+    collection_account_data.pending_authority = None;
+    save_collection_account_data(
+        &mut collection_account_data,
+        collection_account_info,
+        authority_account_info,
+        authority_account_info,
+        rent_sysvar_info,
+        system_program_info,
+    )
 }
 
-pub fn process_close_account(
+pub fn process_lock_collection(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
-    account_type: AccountType,
+    metadata: bool,
+    membership: bool,
 ) -> ProgramResult {
     assert_program_id(program_id)?;
     let account_info_iter = &mut accounts.iter();
-    let account_info = next_account_info(account_info_iter)?;
-    let recipient_account_info = next_account_info(account_info_iter)?;
-    let authority_account_info  = next_account_info(account_info_iter)?;
-    match account_type {
-        AccountType::Uninitialized => {
-            return Err(CollectionError::InvalidAccountType.into());
-        }, 
-        AccountType::CollectionAccount => {
-            msg!("close collection account: {}", account_info.key.to_string());
-            let collection_data = CollectionAccountData::try_from_slice_unchecked(&account_info.data.borrow_mut())?;
-            if collection_data.authority != *authority_account_info.key 
-                || !authority_account_info.is_signer {
-                return Err(CollectionError::NotCollectionAuthority.into());
-            }
-            let recipient_starting_lamports = recipient_account_info.lamports();
-            **recipient_account_info.lamports.borrow_mut() = recipient_starting_lamports.checked_add(account_info.lamports()).unwrap();
-            **account_info.lamports.borrow_mut() = 0;
-
-            let mut account_data = account_info.data.borrow_mut();
-            account_data.fill(0);
-        },
-        AccountType::CollectionIndexAccount => {
-            CollectionIndexAccountData::try_from_slice_unchecked(&account_info.data.borrow_mut())?;
-            let recipient_starting_lamports = recipient_account_info.lamports();
-            **recipient_account_info.lamports.borrow_mut() = recipient_starting_lamports.checked_add(account_info.lamports()).unwrap();
-            **account_info.lamports.borrow_mut() = 0;
-
-            let mut account_data = account_info.data.borrow_mut();
-            account_data.fill(0);
-        }
-    }
-    Ok(())
-}
-
-fn assert_mint_authority(
-    mint_account_info: &AccountInfo, 
-    mint_token_account: &AccountInfo, 
-    collection_auth_account_info: &AccountInfo,
-) -> ProgramResult {
-    if *mint_account_info.owner != spl_token::id() 
-        || *mint_token_account.owner != spl_token::id() {
-        return Err(CollectionError::InvalidNFT.into());
+    let collection_account_info = next_account_info(account_info_iter)?;
+    let authority_account_info = next_account_info(account_info_iter)?;
+    let rent_sysvar_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
 
+    let mut collection_account_data = load_collection_account(program_id, collection_account_info)?;
+    if collection_account_data.authority != *authority_account_info.key
+        || !authority_account_info.is_signer {
+        return Err(CollectionError::NotCollectionAuthority.into());
     }
-    let mint = spl_mint::unpack_unchecked(&mint_account_info.data.borrow())?;
-    let token_account = spl_account::unpack_unchecked(&mint_token_account.data.borrow())?;
-    if !mint.is_initialized() 
-        || mint.supply != 1 
-        || mint.decimals != 0 
-        || !token_account.is_initialized()
-        || token_account.mint != *mint_account_info.key
-        || token_account.amount != 1 {
-        return Err(CollectionError::InvalidNFT.into());
+    if !metadata && !membership {
+        return Err(CollectionError::InvalidInstructionArguments.into());
     }
 
-    if (token_account.owner == *collection_auth_account_info.key) 
-        || (token_account.delegate.is_some() && token_account.delegate == COption::Some(*collection_auth_account_info.key) && token_account.delegated_amount == 1) {
-        return Ok(());
-    } 
-    return Err(CollectionError::NotCollectionAuthority.into());
-}
-
-fn assert_program_id(program_id: &Pubkey) -> ProgramResult {
-    if !check_id(program_id) {
-        return Err(CollectionError::InvalidProgramId.into());
-    }
-    Ok(())
+    // locks are only ever added, `false` keeps the current state
+    collection_account_data.metadata_locked |= metadata;
+    collection_account_data.membership_locked |= membership;
+    msg!(
+        "lock collection, metadata: {}, membership: {}",
+        collection_account_data.metadata_locked,
+        collection_account_data.membership_locked,
+    );
+    save_collection_account_data(
+        &mut collection_account_data,
+        collection_account_info,
+        authority_account_info,
+        authority_account_info,
+        rent_sysvar_info,
+        system_program_info,
+    )
 }
 
-fn assert_create_collection_args(args: &CreateCollectionAccountArgs) -> ProgramResult {
-    if !args.is_valid() {
-        return Err(CollectionError::InvalidInstructionArguments.into());
-    }
-    Ok(())
-}
+pub fn process_migrate_collection_account(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    assert_program_id(program_id)?;
+    let account_info_iter = &mut accounts.iter();
+    let collection_account_info = next_account_info(account_info_iter)?;
+    let payer_account_info = next_account_info(account_info_iter)?;
+    let rent_sysvar_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
 
-fn assert_treasury_account(treasury_account_info: &AccountInfo) -> ProgramResult {
-    let (pda, _) = get_treasury_account();
-    if *treasury_account_info.key != pda {
-        return Err(CollectionError::InvalidTreasuryAccount.into());
+    let mut collection_account_data = load_collection_account(program_id, collection_account_info)?;
+    if collection_account_data.is_current_version() {
+        msg!("collection account is already at version {}", COLLECTION_ACCOUNT_VERSION);
+        return Ok(());
     }
-    Ok(())
-}
-
-fn get_treasury_manager_account() -> Pubkey {
-    Pubkey::from_str(&"Ep1P3v2rMZ2FkyPx5uuGMaTztdSdtdvaUjcahT9y3EQv".to_string()).unwrap()
+    msg!(
+        "migrate collection account from version {} to {}",
+        collection_account_data.version,
+        COLLECTION_ACCOUNT_VERSION,
+    );
+    save_collection_account_data(
+        &mut collection_account_data,
+        collection_account_info,
+        payer_account_info,
+        payer_account_info,
+        rent_sysvar_info,
+        system_program_info,
+    )
 }
 
-fn assert_treasury_manager(manager_account_info: &AccountInfo) -> ProgramResult {
-    if *manager_account_info.key != get_treasury_manager_account() 
-        || !manager_account_info.is_signer {
-        return Err(CollectionError::NotTreasuryManager.into());
+pub fn process_include_token(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    assert_program_id(program_id)?;
+    let account_info_iter = &mut accounts.iter();
+    let collection_account_info = next_account_info(account_info_iter)?;
+    let collection_auth_account_info = next_account_info(account_info_iter)?;
+    let mint_account_info = next_account_info(account_info_iter)?;
+    let mint_token_account_info = next_account_info(account_info_iter)?;
+    let index_account_info = next_account_info(account_info_iter)?;
+    let payer_account_info = next_account_info(account_info_iter)?;
+    let rent_sysvar_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let position_account_info = next_account_info(account_info_iter)?;
+    let _config_account_info = next_account_info(account_info_iter)?;
+    let curator_account_info = next_account_info(account_info_iter).ok();
+
+    let mut collection_account_data = load_collection_account(program_id, collection_account_info)?;
+    // check collection's authority 
+    assert_collection_authority(
+        program_id,
+        collection_account_info,
+        &collection_account_data,
+        collection_auth_account_info,
+        curator_account_info,
+        CURATOR_INCLUDE,
+    )?;
+    assert_mint_authority(
+        mint_account_info,
+        mint_token_account_info,
+        collection_auth_account_info,
+    )?;
+    add_token_to_collection(
+        program_id,
+        collection_account_info,
+        &mut collection_account_data,
+        mint_account_info.key,
+        index_account_info,
+        position_account_info,
+        payer_account_info,
+        rent_sysvar_info,
+        system_program_info,
+    )
+}
+
+pub fn process_include_token_by_creator(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    assert_program_id(program_id)?;
+    let account_info_iter = &mut accounts.iter();
+    let collection_account_info = next_account_info(account_info_iter)?;
+    let collection_auth_account_info = next_account_info(account_info_iter)?;
+    let mint_account_info = next_account_info(account_info_iter)?;
+    let metadata_account_info = next_account_info(account_info_iter)?;
+    let index_account_info = next_account_info(account_info_iter)?;
+    let payer_account_info = next_account_info(account_info_iter)?;
+    let rent_sysvar_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let position_account_info = next_account_info(account_info_iter)?;
+
+    let mut collection_account_data = load_collection_account(program_id, collection_account_info)?;
+    if collection_account_data.authority != *collection_auth_account_info.key 
+        || !collection_auth_account_info.is_signer {
+        return Err(CollectionError::NotCollectionAuthority.into());
+    }
+    assert_verified_creator(
+        mint_account_info,
+        metadata_account_info,
+        collection_auth_account_info,
+    )?;
+    add_token_to_collection(
+        program_id,
+        collection_account_info,
+        &mut collection_account_data,
+        mint_account_info.key,
+        index_account_info,
+        position_account_info,
+        payer_account_info,
+        rent_sysvar_info,
+        system_program_info,
+    )
+}
+
+pub fn process_submit_token(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    assert_program_id(program_id)?;
+    let account_info_iter = &mut accounts.iter();
+    let collection_account_info = next_account_info(account_info_iter)?;
+    let submitter_account_info = next_account_info(account_info_iter)?;
+    let mint_account_info = next_account_info(account_info_iter)?;
+    let mint_token_account_info = next_account_info(account_info_iter)?;
+    let submission_account_info = next_account_info(account_info_iter)?;
+    let rent_sysvar_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let _config_account_info = next_account_info(account_info_iter)?;
+
+    let collection_account_data = load_collection_account(program_id, collection_account_info)?;
+    assert_membership_mutable(&collection_account_data)?;
+    if !submitter_account_info.is_signer {
+        return Err(CollectionError::NotCollectionAuthority.into());
+    }
+    assert_mint_authority(
+        mint_account_info,
+        mint_token_account_info,
+        submitter_account_info,
+    )?;
+    // the submission is approved on behalf of its submitter, so delegates can not submit
+    if !holds_token(mint_token_account_info, mint_account_info.key, submitter_account_info.key) {
+        return Err(CollectionError::SubmitterNotHolder.into());
+    }
+
+    let (submission_account, bump_seed) = get_submission_account(
+        collection_account_info.key,
+        mint_account_info.key,
+    );
+    if submission_account != *submission_account_info.key {
+        return Err(CollectionError::SubmissionAccountMismatch.into());
+    }
+    if !submission_account_info.data_is_empty() {
+        let mut submission_account_data: SubmissionAccountData = load_account(
+            program_id,
+            submission_account_info,
+            AccountType::SubmissionAccount,
+        )?;
+        if submission_account_data.submitter == *submitter_account_info.key {
+            return Err(CollectionError::AlreadyInitialized.into());
+        }
+        // the submitter holds the token now, so a submission of anyone else is stale
+        // and is taken over, the new submitter refunds the rent of the previous one
+        let previous_submitter_account_info = next_account_info(account_info_iter)?;
+        if submission_account_data.submitter != *previous_submitter_account_info.key {
+            return Err(CollectionError::SubmissionAccountMismatch.into());
+        }
+        invoke(
+            &transfer(
+                submitter_account_info.key,
+                previous_submitter_account_info.key,
+                submission_account_info.lamports(),
+            ),
+            &[
+                submitter_account_info.clone(),
+                previous_submitter_account_info.clone(),
+            ],
+        )?;
+
+        msg!("replace submission of {} to {}", mint_account_info.key, collection_account_info.key);
+        submission_account_data.submitter = *submitter_account_info.key;
+        submission_account_data.serialize(&mut &mut submission_account_info.data.borrow_mut()[..])?;
+        return Ok(());
+    }
+    let signer_seeds = &[
+        PREFIX.as_bytes(),
+        program_id.as_ref(),
+        collection_account_info.key.as_ref(),
+        "submission".as_bytes(),
+        mint_account_info.key.as_ref(),
+        &[bump_seed],
+    ];
+    create_or_allocate_account_raw(
+        *program_id,
+        submission_account_info,
+        rent_sysvar_info,
+        system_program_info,
+        submitter_account_info,
+        SubmissionAccountData::LEN,
+        signer_seeds,
+    )?;
+
+    msg!("submit {} to {}", mint_account_info.key, collection_account_info.key);
+    let submission_account_data = SubmissionAccountData::new(
+        *collection_account_info.key,
+        *mint_account_info.key,
+        *submitter_account_info.key,
+    );
+    submission_account_data.serialize(&mut &mut submission_account_info.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+pub fn process_approve_submission(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    assert_program_id(program_id)?;
+    let account_info_iter = &mut accounts.iter();
+    let collection_account_info = next_account_info(account_info_iter)?;
+    let collection_auth_account_info = next_account_info(account_info_iter)?;
+    let mint_account_info = next_account_info(account_info_iter)?;
+    let submission_account_info = next_account_info(account_info_iter)?;
+    let submitter_account_info = next_account_info(account_info_iter)?;
+    let index_account_info = next_account_info(account_info_iter)?;
+    let payer_account_info = next_account_info(account_info_iter)?;
+    let rent_sysvar_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let position_account_info = next_account_info(account_info_iter)?;
+    let _config_account_info = next_account_info(account_info_iter)?;
+    let submitter_token_account_info = next_account_info(account_info_iter)?;
+    let curator_account_info = next_account_info(account_info_iter).ok();
+
+    let mut collection_account_data = load_collection_account(program_id, collection_account_info)?;
+    assert_collection_authority(
+        program_id,
+        collection_account_info,
+        &collection_account_data,
+        collection_auth_account_info,
+        curator_account_info,
+        CURATOR_INCLUDE,
+    )?;
+    let submission_account_data = load_submission_account(
+        program_id,
+        collection_account_info,
+        submission_account_info,
+        submitter_account_info,
+    )?;
+    if submission_account_data.mint != *mint_account_info.key {
+        return Err(CollectionError::SubmissionAccountMismatch.into());
+    }
+    // the token may have changed hands since it was submitted
+    if !holds_token(submitter_token_account_info, mint_account_info.key, submitter_account_info.key) {
+        return Err(CollectionError::SubmitterNotHolder.into());
+    }
+
+    add_token_to_collection(
+        program_id,
+        collection_account_info,
+        &mut collection_account_data,
+        mint_account_info.key,
+        index_account_info,
+        position_account_info,
+        payer_account_info,
+        rent_sysvar_info,
+        system_program_info,
+    )?;
+    close_program_account(submission_account_info, submitter_account_info)
+}
+
+pub fn process_reject_submission(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    assert_program_id(program_id)?;
+    let account_info_iter = &mut accounts.iter();
+    let collection_account_info = next_account_info(account_info_iter)?;
+    let collection_auth_account_info = next_account_info(account_info_iter)?;
+    let submission_account_info = next_account_info(account_info_iter)?;
+    let submitter_account_info = next_account_info(account_info_iter)?;
+    let curator_account_info = next_account_info(account_info_iter).ok();
+
+    let collection_account_data = load_collection_account(program_id, collection_account_info)?;
+    assert_collection_authority(
+        program_id,
+        collection_account_info,
+        &collection_account_data,
+        collection_auth_account_info,
+        curator_account_info,
+        CURATOR_INCLUDE,
+    )?;
+    let submission_account_data = load_submission_account(
+        program_id,
+        collection_account_info,
+        submission_account_info,
+        submitter_account_info,
+    )?;
+
+    msg!("reject {} from {}", submission_account_data.mint, collection_account_info.key);
+    close_program_account(submission_account_info, submitter_account_info)
+}
+
+pub fn process_add_curator(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    permissions: u8,
+) -> ProgramResult {
+    assert_program_id(program_id)?;
+    let account_info_iter = &mut accounts.iter();
+    let collection_account_info = next_account_info(account_info_iter)?;
+    let authority_account_info = next_account_info(account_info_iter)?;
+    let member_account_info = next_account_info(account_info_iter)?;
+    let curator_account_info = next_account_info(account_info_iter)?;
+    let payer_account_info = next_account_info(account_info_iter)?;
+    let rent_sysvar_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    let collection_account_data = load_collection_account(program_id, collection_account_info)?;
+    if collection_account_data.authority != *authority_account_info.key
+        || !authority_account_info.is_signer {
+        return Err(CollectionError::NotCollectionAuthority.into());
+    }
+    if permissions == 0 || permissions & !CURATOR_ALL != 0 {
+        return Err(CollectionError::InvalidInstructionArguments.into());
+    }
+
+    let (curator_account, bump_seed) = get_curator_account(
+        collection_account_info.key,
+        member_account_info.key,
+    );
+    if curator_account != *curator_account_info.key {
+        return Err(CollectionError::CuratorAccountMismatch.into());
+    }
+
+    let mut curator_account_data = if curator_account_info.data_is_empty() {
+        let signer_seeds = &[
+            PREFIX.as_bytes(),
+            program_id.as_ref(),
+            collection_account_info.key.as_ref(),
+            "curator".as_bytes(),
+            member_account_info.key.as_ref(),
+            &[bump_seed],
+        ];
+        create_or_allocate_account_raw(
+            *program_id,
+            curator_account_info,
+            rent_sysvar_info,
+            system_program_info,
+            payer_account_info,
+            CuratorAccountData::LEN,
+            signer_seeds,
+        )?;
+        CuratorAccountData::new(*collection_account_info.key, *member_account_info.key, permissions)
+    } else {
+        load_account(program_id, curator_account_info, AccountType::CuratorAccount)?
+    };
+
+    msg!("curator {} permissions: {}", member_account_info.key, permissions);
+    curator_account_data.permissions = permissions;
+    curator_account_data.serialize(&mut &mut curator_account_info.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+pub fn process_remove_curator(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    assert_program_id(program_id)?;
+    let account_info_iter = &mut accounts.iter();
+    let collection_account_info = next_account_info(account_info_iter)?;
+    let authority_account_info = next_account_info(account_info_iter)?;
+    let curator_account_info = next_account_info(account_info_iter)?;
+    let recipient_account_info = next_account_info(account_info_iter)?;
+
+    let collection_account_data = load_collection_account(program_id, collection_account_info)?;
+    if collection_account_data.authority != *authority_account_info.key
+        || !authority_account_info.is_signer {
+        return Err(CollectionError::NotCollectionAuthority.into());
+    }
+    let curator_account_data: CuratorAccountData = load_account(
+        program_id,
+        curator_account_info,
+        AccountType::CuratorAccount,
+    )?;
+    if curator_account_data.collection != *collection_account_info.key {
+        return Err(CollectionError::CuratorAccountMismatch.into());
+    }
+
+    msg!("remove curator {}", curator_account_data.curator);
+    close_program_account(curator_account_info, recipient_account_info)
+}
+
+/// The collection authority may act on everything, a curator only on what its
+/// role grants, its curator account must be passed.
+fn assert_collection_authority(
+    program_id: &Pubkey,
+    collection_account_info: &AccountInfo,
+    collection_account_data: &CollectionAccountData,
+    authority_account_info: &AccountInfo,
+    curator_account_info: Option<&AccountInfo>,
+    permission: u8,
+) -> ProgramResult {
+    if !authority_account_info.is_signer {
+        return Err(CollectionError::NotCollectionAuthority.into());
+    }
+    if collection_account_data.authority == *authority_account_info.key {
+        return Ok(());
+    }
+    let curator_account_info = curator_account_info.ok_or(CollectionError::NotCollectionAuthority)?;
+    let (curator_account, _) = get_curator_account(
+        collection_account_info.key,
+        authority_account_info.key,
+    );
+    if curator_account != *curator_account_info.key {
+        return Err(CollectionError::CuratorAccountMismatch.into());
+    }
+    let curator_account_data: CuratorAccountData = load_account(
+        program_id,
+        curator_account_info,
+        AccountType::CuratorAccount,
+    )?;
+    if curator_account_data.collection != *collection_account_info.key
+        || !curator_account_data.has_permission(permission) {
+        return Err(CollectionError::NotCollectionAuthority.into());
+    }
+    Ok(())
+}
+
+/// Loads a submission of the collection, the rent goes back to its submitter.
+fn load_submission_account(
+    program_id: &Pubkey,
+    collection_account_info: &AccountInfo,
+    submission_account_info: &AccountInfo,
+    submitter_account_info: &AccountInfo,
+) -> Result<SubmissionAccountData, ProgramError> {
+    let submission_account_data: SubmissionAccountData = load_account(
+        program_id,
+        submission_account_info,
+        AccountType::SubmissionAccount,
+    )?;
+    let (submission_account, _) = get_submission_account(
+        collection_account_info.key,
+        &submission_account_data.mint,
+    );
+    if submission_account != *submission_account_info.key
+        || submission_account_data.collection != *collection_account_info.key
+        || submission_account_data.submitter != *submitter_account_info.key {
+        return Err(CollectionError::SubmissionAccountMismatch.into());
+    }
+    Ok(submission_account_data)
+}
+
+/// Creates the index and position accounts of the mint and bumps the supply.
+#[allow(clippy::too_many_arguments)]
+fn add_token_to_collection<'a>(
+    program_id: &Pubkey,
+    collection_account_info: &AccountInfo<'a>,
+    collection_account_data: &mut CollectionAccountData,
+    mint: &Pubkey,
+    index_account_info: &AccountInfo<'a>,
+    position_account_info: &AccountInfo<'a>,
+    payer_account_info: &AccountInfo<'a>,
+    rent_sysvar_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+) -> ProgramResult {
+    assert_membership_mutable(collection_account_data)?;
+    upgrade_collection_account_data(collection_account_data, collection_account_info)?;
+    if collection_account_data.is_full() {
+        return Err(CollectionError::CollectionFull.into());
+    }
+    let (index_account, bump_seed) = get_index_account(
+        collection_account_info.key,
+        mint,
+    );
+    if index_account != *index_account_info.key {
+        return Err(CollectionError::CollectionIndexAccountMismatch.into());
+    }
+    assert_uninitialized(index_account_info)?;
+    let signer_seeds = &[
+        PREFIX.as_bytes(),
+        program_id.as_ref(),
+        collection_account_info.key.as_ref(),
+        mint.as_ref(),
+        &[bump_seed],
+    ];
+    create_or_allocate_account_raw(
+        *program_id,
+        index_account_info,
+        rent_sysvar_info,
+        system_program_info,
+        payer_account_info,
+        CollectionIndexAccountData::LEN,
+        signer_seeds,
+    )?;
+    let index_account_data = CollectionIndexAccountData::new(
+        *collection_account_info.key, 
+        *mint,
+        collection_account_data.supply,
+    );
+
+    index_account_data.serialize(&mut &mut index_account_info.data.borrow_mut()[..])?;
+    create_position_account(
+        program_id,
+        collection_account_info.key,
+        mint,
+        collection_account_data.supply,
+        position_account_info,
+        payer_account_info,
+        rent_sysvar_info,
+        system_program_info,
+    )?;
+    collection_account_data.supply += 1;
+    save_collection_account_data(
+        collection_account_data,
+        collection_account_info,
+        payer_account_info,
+        payer_account_info,
+        rent_sysvar_info,
+        system_program_info,
+    )?;
+    CollectionEvent::TokenIncluded(TokenIncluded {
+        collection: *collection_account_info.key,
+        mint: *mint,
+        index: index_account_data.index,
+        supply: collection_account_data.supply,
+    }).emit()
+}
+
+pub fn process_remove_token(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    assert_program_id(program_id)?;
+    let account_info_iter = &mut accounts.iter();
+    let collection_account_info = next_account_info(account_info_iter)?;
+    let collection_auth_account_info = next_account_info(account_info_iter)?;
+    let index_account_info = next_account_info(account_info_iter)?;
+    let recipient_account_info = next_account_info(account_info_iter)?;
+    let position_account_info = next_account_info(account_info_iter)?;
+    let last_position_account_info = next_account_info(account_info_iter)?;
+    let last_index_account_info = next_account_info(account_info_iter)?;
+    let curator_account_info = next_account_info(account_info_iter).ok();
+
+    let mut collection_account_data = load_collection_account(program_id, collection_account_info)?;
+    // check collection's authority 
+    assert_collection_authority(
+        program_id,
+        collection_account_info,
+        &collection_account_data,
+        collection_auth_account_info,
+        curator_account_info,
+        CURATOR_REMOVE,
+    )?;
+
+    let index_account_data = load_index_account(
+        program_id,
+        collection_account_info,
+        index_account_info,
+    )?;
+    remove_index_account(
+        program_id,
+        collection_account_info,
+        &mut collection_account_data,
+        index_account_info,
+        &index_account_data,
+        recipient_account_info,
+        position_account_info,
+        last_position_account_info,
+        last_index_account_info,
+    )
+}
+
+pub fn process_migrate_index_account(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    assert_program_id(program_id)?;
+    let account_info_iter = &mut accounts.iter();
+    let collection_account_info = next_account_info(account_info_iter)?;
+    let collection_auth_account_info = next_account_info(account_info_iter)?;
+    let legacy_index_account_info = next_account_info(account_info_iter)?;
+    let index_account_info = next_account_info(account_info_iter)?;
+    let payer_account_info = next_account_info(account_info_iter)?;
+    let rent_sysvar_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let position_account_info = next_account_info(account_info_iter)?;
+
+    let mut collection_account_data = load_collection_account(program_id, collection_account_info)?;
+    // check collection's authority 
+    if collection_account_data.authority != *collection_auth_account_info.key 
+        || !collection_auth_account_info.is_signer {
+        return Err(CollectionError::NotCollectionAuthority.into());
+    }
+
+    let legacy_index_account_data: CollectionIndexAccountData = load_account(
+        program_id,
+        legacy_index_account_info,
+        AccountType::CollectionIndexAccount,
+    )?;
+    let mint = legacy_index_account_data.mint;
+    let (legacy_index_account, _) = get_legacy_index_account(&mint);
+    if legacy_index_account != *legacy_index_account_info.key
+        || legacy_index_account_data.collection != *collection_account_info.key {
+        return Err(CollectionError::CollectionIndexAccountMismatch.into());
+    }
+
+    let (index_account, _) = get_index_account(
+        collection_account_info.key,
+        &mint,
+    );
+    if index_account != *index_account_info.key {
+        return Err(CollectionError::CollectionIndexAccountMismatch.into());
+    }
+    if assert_uninitialized(index_account_info).is_err() {
+        // the mint was included again at the per collection address, the legacy
+        // account is stale and only has to be closed
+        load_index_account(program_id, collection_account_info, index_account_info)?;
+        msg!("close stale legacy index account of mint: {}", mint.to_string());
+        return close_program_account(legacy_index_account_info, collection_auth_account_info);
+    }
+
+    // legacy indexes have gaps as closing never decremented the supply, the
+    // token is appended after the members that already have a position and
+    // goes through the same checks as a token included now
+    msg!("migrate index account of mint: {}", mint.to_string());
+    add_token_to_collection(
+        program_id,
+        collection_account_info,
+        &mut collection_account_data,
+        &mint,
+        index_account_info,
+        position_account_info,
+        payer_account_info,
+        rent_sysvar_info,
+        system_program_info,
+    )?;
+    // the legacy account was funded when the authority included the token
+    close_program_account(legacy_index_account_info, collection_auth_account_info)
+}
+
+pub fn process_light_up_stars_once(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    assert_program_id(program_id)?;
+    let account_info_iter = &mut accounts.iter();
+    let collection_account_info = next_account_info(account_info_iter)?;
+    let mut collection_account_data = load_collection_account(program_id, collection_account_info)?;
+    collection_account_data.stars = collection_account_data.stars
+        .checked_add(1)
+        .ok_or(CollectionError::NumericalOverflow)?;
+    write_collection_account_data(&collection_account_data, collection_account_info)?;
+    CollectionEvent::StarsLit(StarsLit {
+        collection: *collection_account_info.key,
+        supporter: None,
+        stars: 1,
+        lamports: 0,
+        total_stars: collection_account_data.stars,
+    }).emit()
+}
+
+pub fn process_light_up_stars(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    light_up_stars(program_id, accounts, amount, |config| amount.checked_mul(config.price_per_star))
+}
+
+pub fn process_light_up_stars_hundred(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    light_up_stars(program_id, accounts, 100, |config| Some(config.stars_hundred_price))
+}
+
+pub fn process_light_up_stars_thousand(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    light_up_stars(program_id, accounts, 1000, |config| Some(config.stars_thousand_price))
+}
+
+pub fn process_withdraw(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: Option<u64>,
+) -> ProgramResult {
+    assert_program_id(program_id)?;
+    let account_info_iter = &mut accounts.iter();
+    let treasury_manager_account_info = next_account_info(account_info_iter)?;
+    let treasury_account_info = next_account_info(account_info_iter)?;
+    let recipient_account_info = next_account_info(account_info_iter)?;
+    let config_account_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    let config = load_config(program_id, config_account_info)?;
+    assert_treasury_manager(treasury_manager_account_info, &config)?;
+    let bump_seed = assert_treasury_account(treasury_account_info)?;
+
+    // the treasury keeps its rent exempt minimum
+    let spendable = treasury_account_info.lamports()
+        .saturating_sub(Rent::get()?.minimum_balance(treasury_account_info.data_len()));
+    let lamports = amount.unwrap_or(spendable);
+    if lamports == 0 || lamports > spendable {
+        return Err(CollectionError::InsufficientFunds.into());
+    }
+
+    msg!("withdraw {} lamports to {}", lamports, recipient_account_info.key);
+    invoke_signed(
+        &transfer(
+            treasury_account_info.key,
+            recipient_account_info.key,
+            lamports,
+        ),
+        &[
+            treasury_account_info.clone(),
+            recipient_account_info.clone(),
+            system_program_info.clone(),
+        ],
+        &[&[PREFIX.as_bytes(), "treasury".as_bytes(), program_id.as_ref(), &[bump_seed]]],
+    )?;
+    CollectionEvent::Withdrawn(Withdrawn {
+        treasury_manager: *treasury_manager_account_info.key,
+        recipient: *recipient_account_info.key,
+        lamports,
+    }).emit()
+}
+
+pub fn process_initialize_config(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: &ConfigArgs,
+) -> ProgramResult {
+    assert_program_id(program_id)?;
+    let account_info_iter = &mut accounts.iter();
+    let config_account_info = next_account_info(account_info_iter)?;
+    let upgrade_authority_account_info = next_account_info(account_info_iter)?;
+    let program_data_account_info = next_account_info(account_info_iter)?;
+    let payer_account_info = next_account_info(account_info_iter)?;
+    let rent_sysvar_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    assert_upgrade_authority(program_id, upgrade_authority_account_info, program_data_account_info)?;
+    let (config_account, bump_seed) = get_config_account();
+    if config_account != *config_account_info.key {
+        return Err(CollectionError::InvalidConfigAccount.into());
+    }
+    if !config_account_info.data_is_empty() {
+        return Err(CollectionError::AlreadyInitialized.into());
+    }
+
+    let signer_seeds = &[
+        PREFIX.as_bytes(),
+        "config".as_bytes(),
+        program_id.as_ref(),
+        &[bump_seed],
+    ];
+    create_or_allocate_account_raw(
+        *program_id,
+        config_account_info,
+        rent_sysvar_info,
+        system_program_info,
+        payer_account_info,
+        ConfigAccountData::LEN,
+        signer_seeds,
+    )?;
+
+    let config_account_data = ConfigAccountData {
+        account_type: AccountType::ConfigAccount,
+        admin: args.admin,
+        treasury_manager: args.treasury_manager,
+        stars_hundred_price: args.stars_hundred_price,
+        stars_thousand_price: args.stars_thousand_price,
+        field_limits: args.field_limits,
+        price_per_star: args.price_per_star,
+        membership_paused: false,
+        payments_paused: false,
+    };
+    config_account_data.serialize(&mut &mut config_account_info.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+pub fn process_update_config(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: &ConfigArgs,
+) -> ProgramResult {
+    assert_program_id(program_id)?;
+    let account_info_iter = &mut accounts.iter();
+    let config_account_info = next_account_info(account_info_iter)?;
+    let admin_account_info = next_account_info(account_info_iter)?;
+
+    if config_account_info.data_is_empty() {
+        return Err(CollectionError::Uninitialized.into());
+    }
+    let mut config_account_data = load_config(program_id, config_account_info)?;
+    if config_account_data.admin != *admin_account_info.key
+        || !admin_account_info.is_signer {
+        return Err(CollectionError::NotConfigAdmin.into());
+    }
+
+    config_account_data.admin = args.admin;
+    config_account_data.treasury_manager = args.treasury_manager;
+    config_account_data.stars_hundred_price = args.stars_hundred_price;
+    config_account_data.stars_thousand_price = args.stars_thousand_price;
+    config_account_data.field_limits = args.field_limits;
+    config_account_data.price_per_star = args.price_per_star;
+    config_account_data.serialize(&mut &mut config_account_info.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+pub fn process_set_paused(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    membership: bool,
+    payments: bool,
+) -> ProgramResult {
+    assert_program_id(program_id)?;
+    let account_info_iter = &mut accounts.iter();
+    let config_account_info = next_account_info(account_info_iter)?;
+    let admin_account_info = next_account_info(account_info_iter)?;
+
+    if config_account_info.data_is_empty() {
+        return Err(CollectionError::Uninitialized.into());
+    }
+    let mut config_account_data = load_config(program_id, config_account_info)?;
+    if config_account_data.admin != *admin_account_info.key
+        || !admin_account_info.is_signer {
+        return Err(CollectionError::NotConfigAdmin.into());
+    }
+
+    msg!("membership paused: {}, payments paused: {}", membership, payments);
+    config_account_data.membership_paused = membership;
+    config_account_data.payments_paused = payments;
+    config_account_data.serialize(&mut &mut config_account_info.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+pub fn process_close_account(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    account_type: AccountType,
+) -> ProgramResult {
+    assert_program_id(program_id)?;
+    let account_info_iter = &mut accounts.iter();
+    let account_info = next_account_info(account_info_iter)?;
+    let recipient_account_info = next_account_info(account_info_iter)?;
+    let authority_account_info  = next_account_info(account_info_iter)?;
+    match account_type {
+        // positions are compacted by `RemoveToken`, the config lives as long as the program
+        // and receipts are the permanent record of a supporter, symbols are released
+        // together with their collection, submissions by `RejectSubmission` and
+        // curators by `RemoveCurator`
+        AccountType::Uninitialized
+            | AccountType::CollectionPositionAccount
+            | AccountType::ConfigAccount
+            | AccountType::StarReceiptAccount
+            | AccountType::SymbolAccount
+            | AccountType::SubmissionAccount
+            | AccountType::CuratorAccount => {
+            return Err(CollectionError::InvalidAccountType.into());
+        }, 
+        AccountType::CollectionAccount | AccountType::VersionedCollectionAccount => {
+            msg!("close collection account: {}", account_info.key.to_string());
+            let collection_data = load_collection_account(program_id, account_info)?;
+            if collection_data.authority != *authority_account_info.key 
+                || !authority_account_info.is_signer {
+                return Err(CollectionError::NotCollectionAuthority.into());
+            }
+            assert_metadata_mutable(&collection_data)?;
+            assert_membership_mutable(&collection_data)?;
+            // the index and position accounts can only be closed through the collection
+            if collection_data.supply != 0 {
+                return Err(CollectionError::CollectionNotEmpty.into());
+            }
+            // symbols that can not be reserved come without a symbol account
+            if get_symbol_account(&collection_data.symbol).is_some() {
+                let symbol_account_info = next_account_info(account_info_iter)?;
+                release_symbol(
+                    program_id,
+                    account_info,
+                    &collection_data.symbol,
+                    symbol_account_info,
+                    recipient_account_info,
+                )?;
+            }
+            close_program_account(account_info, recipient_account_info)?;
+        },
+        AccountType::CollectionIndexAccount => {
+            let collection_account_info = next_account_info(account_info_iter)?;
+            let position_account_info = next_account_info(account_info_iter)?;
+            let last_position_account_info = next_account_info(account_info_iter)?;
+            let last_index_account_info = next_account_info(account_info_iter)?;
+            let holder_token_account_info = next_account_info(account_info_iter).ok();
+
+            let mut collection_data = load_collection_account(program_id, collection_account_info)?;
+            let index_data = load_index_account(program_id, collection_account_info, account_info)?;
+            assert_index_closer(
+                &collection_data,
+                &index_data,
+                authority_account_info,
+                holder_token_account_info,
+            )?;
+            msg!("close collection index account: {}", account_info.key.to_string());
+            remove_index_account(
+                program_id,
+                collection_account_info,
+                &mut collection_data,
+                account_info,
+                &index_data,
+                recipient_account_info,
+                position_account_info,
+                last_position_account_info,
+                last_index_account_info,
+            )?;
+        }
+    }
+    CollectionEvent::AccountClosed(AccountClosed {
+        account: *account_info.key,
+        account_type,
+        recipient: *recipient_account_info.key,
+    }).emit()
+}
+
+/// Creates the position account mapping `index` of the collection back to the mint.
+#[allow(clippy::too_many_arguments)]
+fn create_position_account<'a>(
+    program_id: &Pubkey,
+    collection: &Pubkey,
+    mint: &Pubkey,
+    index: u64,
+    position_account_info: &AccountInfo<'a>,
+    payer_account_info: &AccountInfo<'a>,
+    rent_sysvar_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+) -> ProgramResult {
+    let (position_account, bump_seed) = get_position_account(collection, index);
+    if position_account != *position_account_info.key {
+        return Err(CollectionError::CollectionPositionAccountMismatch.into());
+    }
+    assert_uninitialized(position_account_info)?;
+    let index_bytes = index.to_le_bytes();
+    let signer_seeds = &[
+        PREFIX.as_bytes(),
+        program_id.as_ref(),
+        collection.as_ref(),
+        index_bytes.as_ref(),
+        &[bump_seed],
+    ];
+    create_or_allocate_account_raw(
+        *program_id,
+        position_account_info,
+        rent_sysvar_info,
+        system_program_info,
+        payer_account_info,
+        CollectionPositionAccountData::LEN,
+        signer_seeds,
+    )?;
+
+    let position_account_data = CollectionPositionAccountData::new(*collection, *mint, index);
+    position_account_data.serialize(&mut &mut position_account_info.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+/// Charges the price computed from the config to the funding account and adds
+/// `amount` stars to the collection.
+fn light_up_stars<F>(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+    price: F,
+) -> ProgramResult
+where
+    F: Fn(&ConfigAccountData) -> Option<u64>,
+{
+    assert_program_id(program_id)?;
+    let account_info_iter = &mut accounts.iter();
+    let collection_account_info = next_account_info(account_info_iter)?;
+    let source_account_info = next_account_info(account_info_iter)?;
+    let destination_account_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let config_account_info = next_account_info(account_info_iter)?;
+    let receipt_account_info = next_account_info(account_info_iter)?;
+    let rent_sysvar_info = next_account_info(account_info_iter)?;
+
+    if amount == 0 {
+        return Err(CollectionError::InvalidInstructionArguments.into());
     }
+    assert_treasury_account(destination_account_info)?;
+    let config = load_config(program_id, config_account_info)?;
+    let lamports = price(&config).ok_or(CollectionError::NumericalOverflow)?;
+
+    let mut collection_account_data = load_collection_account(program_id, collection_account_info)?;
+    collection_account_data.stars = collection_account_data.stars
+        .checked_add(amount)
+        .ok_or(CollectionError::NumericalOverflow)?;
+
+    invoke(
+        &transfer(
+            source_account_info.key,
+            destination_account_info.key,
+            lamports,
+        ), 
+        &[
+            source_account_info.clone(),
+            destination_account_info.clone(),
+        ],
+    )?;
+    write_collection_account_data(&collection_account_data, collection_account_info)?;
+
+    record_star_receipt(
+        program_id,
+        collection_account_info,
+        source_account_info,
+        receipt_account_info,
+        rent_sysvar_info,
+        system_program_info,
+        amount,
+        lamports,
+    )?;
+    CollectionEvent::StarsLit(StarsLit {
+        collection: *collection_account_info.key,
+        supporter: Some(*source_account_info.key),
+        stars: amount,
+        lamports,
+        total_stars: collection_account_data.stars,
+    }).emit()
+}
+
+/// Adds the purchase to the receipt of the supporter, creating it on the first purchase.
+#[allow(clippy::too_many_arguments)]
+fn record_star_receipt<'a>(
+    program_id: &Pubkey,
+    collection_account_info: &AccountInfo<'a>,
+    supporter_account_info: &AccountInfo<'a>,
+    receipt_account_info: &AccountInfo<'a>,
+    rent_sysvar_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+    stars: u64,
+    lamports: u64,
+) -> ProgramResult {
+    let (receipt_account, bump_seed) = get_star_receipt_account(
+        collection_account_info.key,
+        supporter_account_info.key,
+    );
+    if receipt_account != *receipt_account_info.key {
+        return Err(CollectionError::StarReceiptAccountMismatch.into());
+    }
+
+    let mut receipt_account_data = if receipt_account_info.data_is_empty() {
+        let signer_seeds = &[
+            PREFIX.as_bytes(),
+            program_id.as_ref(),
+            "receipt".as_bytes(),
+            collection_account_info.key.as_ref(),
+            supporter_account_info.key.as_ref(),
+            &[bump_seed],
+        ];
+        create_or_allocate_account_raw(
+            *program_id,
+            receipt_account_info,
+            rent_sysvar_info,
+            system_program_info,
+            supporter_account_info,
+            StarReceiptAccountData::LEN,
+            signer_seeds,
+        )?;
+        StarReceiptAccountData::new(*collection_account_info.key, *supporter_account_info.key)
+    } else {
+        load_account(program_id, receipt_account_info, AccountType::StarReceiptAccount)?
+    };
+
+    receipt_account_data.stars = receipt_account_data.stars
+        .checked_add(stars)
+        .ok_or(CollectionError::NumericalOverflow)?;
+    receipt_account_data.lamports = receipt_account_data.lamports
+        .checked_add(lamports)
+        .ok_or(CollectionError::NumericalOverflow)?;
+    receipt_account_data.last_slot = Clock::get()?.slot;
+    receipt_account_data.serialize(&mut &mut receipt_account_info.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+/// Loads an index account of the collection, checking its address and owner.
+fn load_index_account(
+    program_id: &Pubkey,
+    collection_account_info: &AccountInfo,
+    index_account_info: &AccountInfo,
+) -> Result<CollectionIndexAccountData, ProgramError> {
+    let index_account_data: CollectionIndexAccountData = load_account(
+        program_id,
+        index_account_info,
+        AccountType::CollectionIndexAccount,
+    )?;
+    let (index_account, _) = get_index_account(
+        &index_account_data.collection,
+        &index_account_data.mint,
+    );
+    if index_account != *index_account_info.key
+        || index_account_data.collection != *collection_account_info.key {
+        return Err(CollectionError::CollectionIndexAccountMismatch.into());
+    }
+
+    Ok(index_account_data)
+}
+
+/// Closes the index account and its position, moving the last token of the
+/// collection into the freed position and decrementing the supply.
+#[allow(clippy::too_many_arguments)]
+fn remove_index_account<'a>(
+    program_id: &Pubkey,
+    collection_account_info: &AccountInfo<'a>,
+    collection_account_data: &mut CollectionAccountData,
+    index_account_info: &AccountInfo<'a>,
+    index_account_data: &CollectionIndexAccountData,
+    recipient_account_info: &AccountInfo<'a>,
+    position_account_info: &AccountInfo<'a>,
+    last_position_account_info: &AccountInfo<'a>,
+    last_index_account_info: &AccountInfo<'a>,
+) -> ProgramResult {
+    assert_membership_mutable(collection_account_data)?;
+    let (position_account, _) = get_position_account(
+        collection_account_info.key,
+        index_account_data.index,
+    );
+    if position_account != *position_account_info.key {
+        return Err(CollectionError::CollectionPositionAccountMismatch.into());
+    }
+    let last_index = collection_account_data.supply
+        .checked_sub(1)
+        .ok_or(ProgramError::InvalidAccountData)?;
+    let (last_position_account, _) = get_position_account(
+        collection_account_info.key,
+        last_index,
+    );
+    if last_position_account != *last_position_account_info.key {
+        return Err(CollectionError::CollectionPositionAccountMismatch.into());
+    }
+
+    msg!("remove token: {}", index_account_data.mint.to_string());
+    if index_account_data.index != last_index {
+        // swap remove, the last token takes over the freed position
+        let last_position_account_data: CollectionPositionAccountData = load_account(
+            program_id,
+            last_position_account_info,
+            AccountType::CollectionPositionAccount,
+        )?;
+        let (last_index_account, _) = get_index_account(
+            collection_account_info.key,
+            &last_position_account_data.mint,
+        );
+        if last_index_account != *last_index_account_info.key {
+            return Err(CollectionError::CollectionIndexAccountMismatch.into());
+        }
+        let mut last_index_account_data: CollectionIndexAccountData = load_account(
+            program_id,
+            last_index_account_info,
+            AccountType::CollectionIndexAccount,
+        )?;
+
+        last_index_account_data.index = index_account_data.index;
+        last_index_account_data.serialize(&mut &mut last_index_account_info.data.borrow_mut()[..])?;
+        let position_account_data = CollectionPositionAccountData::new(
+            *collection_account_info.key,
+            last_position_account_data.mint,
+            index_account_data.index,
+        );
+        position_account_data.serialize(&mut &mut position_account_info.data.borrow_mut()[..])?;
+        close_program_account(last_position_account_info, recipient_account_info)?;
+    } else {
+        close_program_account(position_account_info, recipient_account_info)?;
+    }
+
+    close_program_account(index_account_info, recipient_account_info)?;
+    collection_account_data.supply = last_index;
+    write_collection_account_data(collection_account_data, collection_account_info)?;
+    CollectionEvent::TokenRemoved(TokenRemoved {
+        collection: *collection_account_info.key,
+        mint: index_account_data.mint,
+        index: index_account_data.index,
+        supply: collection_account_data.supply,
+    }).emit()
+}
+
+/// Moves all lamports of a program owned account to the recipient and wipes its data.
+fn close_program_account(
+    account_info: &AccountInfo,
+    recipient_account_info: &AccountInfo,
+) -> ProgramResult {
+    let recipient_starting_lamports = recipient_account_info.lamports();
+    **recipient_account_info.lamports.borrow_mut() = recipient_starting_lamports.checked_add(account_info.lamports()).unwrap();
+    **account_info.lamports.borrow_mut() = 0;
+
+    let mut account_data = account_info.data.borrow_mut();
+    account_data.fill(0);
+    Ok(())
+}
+
+fn emit_collection_created(
+    collection_account_info: &AccountInfo,
+    collection_account_data: &CollectionAccountData,
+) -> ProgramResult {
+    CollectionEvent::CollectionCreated(CollectionCreated {
+        collection: *collection_account_info.key,
+        authority: collection_account_data.authority,
+        title: collection_account_data.title.clone(),
+        symbol: collection_account_data.symbol.clone(),
+        max_supply: collection_account_data.max_supply,
+    }).emit()
+}
+
+/// Moves legacy collection data to the current layout, logging the supply it
+/// restarts from.
+fn upgrade_collection_account_data(
+    collection_account_data: &mut CollectionAccountData,
+    collection_account_info: &AccountInfo,
+) -> ProgramResult {
+    if collection_account_data.is_current_version() {
+        return Ok(());
+    }
+    let legacy_supply = collection_account_data.supply;
+    collection_account_data.upgrade();
+    CollectionEvent::CollectionMigrated(CollectionMigrated {
+        collection: *collection_account_info.key,
+        version: collection_account_data.version,
+        legacy_supply,
+    }).emit()
+}
+
+/// Writes the collection data back in the current layout, resizing the account
+/// first when the serialized length changed.
+fn save_collection_account_data<'a>(
+    collection_account_data: &mut CollectionAccountData,
+    collection_account_info: &AccountInfo<'a>,
+    payer_account_info: &AccountInfo<'a>,
+    refund_account_info: &AccountInfo<'a>,
+    rent_sysvar_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+) -> ProgramResult {
+    upgrade_collection_account_data(collection_account_data, collection_account_info)?;
+    let mut data: Vec<u8> = Vec::new();
+    collection_account_data.serialize(&mut data)?;
+    if data.len() != collection_account_info.data_len() {
+        resize_account(
+            collection_account_info,
+            data.len(),
+            payer_account_info,
+            refund_account_info,
+            rent_sysvar_info,
+            system_program_info,
+        )?;
+    }
+
+    collection_account_data.serialize(&mut &mut collection_account_info.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+/// Writes the collection data back in place, in the layout it was read from so
+/// unmigrated accounts keep working.
+fn write_collection_account_data(
+    collection_account_data: &CollectionAccountData,
+    collection_account_info: &AccountInfo,
+) -> ProgramResult {
+    if collection_account_data.is_current_version() {
+        collection_account_data.serialize(&mut &mut collection_account_info.data.borrow_mut()[..])?;
+    } else {
+        collection_account_data.serialize_v0(&mut &mut collection_account_info.data.borrow_mut()[..])?;
+    }
+    Ok(())
+}
+
+fn assert_mint_authority(
+    mint_account_info: &AccountInfo, 
+    mint_token_account: &AccountInfo, 
+    collection_auth_account_info: &AccountInfo,
+) -> ProgramResult {
+    if *mint_account_info.owner != spl_token::id() 
+        || *mint_token_account.owner != spl_token::id() {
+        return Err(CollectionError::InvalidNFT.into());
+
+    }
+    let mint = spl_mint::unpack_unchecked(&mint_account_info.data.borrow())?;
+    let token_account = spl_account::unpack_unchecked(&mint_token_account.data.borrow())?;
+    if !mint.is_initialized() 
+        || mint.supply != 1 
+        || mint.decimals != 0 
+        || !token_account.is_initialized()
+        || token_account.mint != *mint_account_info.key
+        || token_account.amount != 1 {
+        return Err(CollectionError::InvalidNFT.into());
+    }
+
+    if (token_account.owner == *collection_auth_account_info.key) 
+        || (token_account.delegate.is_some() && token_account.delegate == COption::Some(*collection_auth_account_info.key) && token_account.delegated_amount == 1) {
+        return Ok(());
+    } 
+    Err(CollectionError::NotCollectionAuthority.into())
+}
+
+/// The mint must be an nft whose Metaplex metadata lists the collection authority
+/// as a verified creator.
+fn assert_verified_creator(
+    mint_account_info: &AccountInfo, 
+    metadata_account_info: &AccountInfo, 
+    collection_auth_account_info: &AccountInfo,
+) -> ProgramResult {
+    if *mint_account_info.owner != spl_token::id() {
+        return Err(CollectionError::InvalidNFT.into());
+    }
+    let mint = spl_mint::unpack_unchecked(&mint_account_info.data.borrow())?;
+    if !mint.is_initialized() 
+        || mint.supply != 1 
+        || mint.decimals != 0 {
+        return Err(CollectionError::InvalidNFT.into());
+    }
+
+    let (metadata_account, _) = get_metadata_account(mint_account_info.key);
+    if metadata_account != *metadata_account_info.key {
+        return Err(CollectionError::InvalidMetadataAccount.into());
+    }
+    let metadata = Metadata::from_account_info(metadata_account_info)?;
+    if metadata.mint != *mint_account_info.key {
+        return Err(CollectionError::InvalidMetadataAccount.into());
+    }
+    if !metadata.is_verified_creator(collection_auth_account_info.key) {
+        return Err(CollectionError::CreatorNotVerified.into());
+    }
+    Ok(())
+}
+
+/// An index can be closed by the collection authority, or by the holder of the
+/// token when its token account is passed.
+fn assert_index_closer(
+    collection_account_data: &CollectionAccountData,
+    index_account_data: &CollectionIndexAccountData,
+    authority_account_info: &AccountInfo,
+    holder_token_account_info: Option<&AccountInfo>,
+) -> ProgramResult {
+    if !authority_account_info.is_signer {
+        return Err(CollectionError::NotCollectionAuthority.into());
+    }
+    if collection_account_data.authority == *authority_account_info.key {
+        return Ok(());
+    }
+    match holder_token_account_info {
+        Some(token_account_info) if holds_token(token_account_info, &index_account_data.mint, authority_account_info.key) => Ok(()),
+        _ => Err(CollectionError::NotCollectionAuthority.into()),
+    }
+}
+
+/// Whether the token account is owned by `holder` and holds the single token of `mint`.
+fn holds_token(token_account_info: &AccountInfo, mint: &Pubkey, holder: &Pubkey) -> bool {
+    if *token_account_info.owner != spl_token::id() {
+        return false;
+    }
+    matches!(
+        spl_account::unpack(&token_account_info.data.borrow()),
+        Ok(token_account) if token_account.mint == *mint && token_account.owner == *holder && token_account.amount == 1
+    )
+}
+
+fn assert_program_id(program_id: &Pubkey) -> ProgramResult {
+    if !check_id(program_id) {
+        return Err(CollectionError::InvalidProgramId.into());
+    }
+    Ok(())
+}
+
+fn assert_metadata_mutable(collection_account_data: &CollectionAccountData) -> ProgramResult {
+    if collection_account_data.metadata_locked {
+        return Err(CollectionError::CollectionImmutable.into());
+    }
+    Ok(())
+}
+
+fn assert_membership_mutable(collection_account_data: &CollectionAccountData) -> ProgramResult {
+    if collection_account_data.membership_locked {
+        return Err(CollectionError::CollectionImmutable.into());
+    }
+    Ok(())
+}
+
+fn assert_create_collection_args(args: &CreateCollectionAccountArgs, limits: &FieldLimits) -> ProgramResult {
+    if !args.is_valid_with_limits(limits) {
+        return Err(CollectionError::InvalidInstructionArguments.into());
+    }
+    Ok(())
+}
+
+fn assert_update_collection_args(args: &UpdateCollectionAccountArgs, limits: &FieldLimits) -> ProgramResult {
+    if !args.is_valid_with_limits(limits) {
+        return Err(CollectionError::InvalidInstructionArguments.into());
+    }
+    Ok(())
+}
+
+/// Checks the treasury address and returns its bump seed.
+fn assert_treasury_account(treasury_account_info: &AccountInfo) -> Result<u8, ProgramError> {
+    let (pda, bump_seed) = get_treasury_account();
+    if *treasury_account_info.key != pda {
+        return Err(CollectionError::InvalidTreasuryAccount.into());
+    }
+    Ok(bump_seed)
+}
+
+fn assert_treasury_manager(manager_account_info: &AccountInfo, config: &ConfigAccountData) -> ProgramResult {
+    if *manager_account_info.key != config.treasury_manager
+        || !manager_account_info.is_signer {
+        return Err(CollectionError::NotTreasuryManager.into());
+    }
+    Ok(())
+}
+
+/// Reserves the normalized symbol for the collection.
+fn reserve_symbol<'a>(
+    program_id: &Pubkey,
+    collection_account_info: &AccountInfo<'a>,
+    symbol: &str,
+    symbol_account_info: &AccountInfo<'a>,
+    payer_account_info: &AccountInfo<'a>,
+    rent_sysvar_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+) -> ProgramResult {
+    let normalized_symbol = normalize_symbol(symbol);
+    let (symbol_account, bump_seed) = get_symbol_account(symbol)
+        .ok_or(CollectionError::InvalidInstructionArguments)?;
+    if symbol_account != *symbol_account_info.key {
+        return Err(CollectionError::SymbolAccountMismatch.into());
+    }
+    if !symbol_account_info.data_is_empty() {
+        return Err(CollectionError::SymbolAlreadyTaken.into());
+    }
+
+    let signer_seeds = &[
+        PREFIX.as_bytes(),
+        program_id.as_ref(),
+        "symbol".as_bytes(),
+        normalized_symbol.as_bytes(),
+        &[bump_seed],
+    ];
+    create_or_allocate_account_raw(
+        *program_id,
+        symbol_account_info,
+        rent_sysvar_info,
+        system_program_info,
+        payer_account_info,
+        SymbolAccountData::LEN,
+        signer_seeds,
+    )?;
+
+    msg!("reserve symbol: {}", normalized_symbol);
+    let symbol_account_data = SymbolAccountData {
+        account_type: AccountType::SymbolAccount,
+        collection: *collection_account_info.key,
+    };
+    symbol_account_data.serialize(&mut &mut symbol_account_info.data.borrow_mut()[..])?;
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Releases the symbol reserved by the collection, collections created before the
+/// symbol registry or without a symbol account may hold no reservation and are
+/// left as is.
+fn release_symbol(
+    program_id: &Pubkey,
+    collection_account_info: &AccountInfo,
+    symbol: &str,
+    symbol_account_info: &AccountInfo,
+    recipient_account_info: &AccountInfo,
+) -> ProgramResult {
+    let normalized_symbol = normalize_symbol(symbol);
+    let (symbol_account, _) = match get_symbol_account(symbol) {
+        Some(symbol_account) => symbol_account,
+        None => return Ok(()),
+    };
+    if symbol_account != *symbol_account_info.key {
+        return Err(CollectionError::SymbolAccountMismatch.into());
+    }
+    if symbol_account_info.data_is_empty() {
+        return Ok(());
+    }
+    let symbol_account_data: SymbolAccountData = load_account(
+        program_id,
+        symbol_account_info,
+        AccountType::SymbolAccount,
+    )?;
+    if symbol_account_data.collection != *collection_account_info.key {
+        return Ok(());
+    }
+
+    msg!("release symbol: {}", normalized_symbol);
+    close_program_account(symbol_account_info, recipient_account_info)
+}
+
+fn new_collection_account_data(
+    authority: &Pubkey,
+    args: &CreateCollectionAccountArgs,
+) -> CollectionAccountData {
+    CollectionAccountData {
+        account_type: AccountType::VersionedCollectionAccount,
+        version: COLLECTION_ACCOUNT_VERSION,
+        title: args.title.clone(),
+        symbol: args.symbol.clone(),
+        description: args.description.clone(),
+        icon_image: args.icon_image.clone(),
+        stars: 0,
+        supply: 0,
+        authority: *authority,
+        header_image: args.header_image.clone(),
+        short_description: args.short_description.clone(),
+        banner: args.banner.clone(),
+        tags: args.tags.clone(),
+        pending_authority: None,
+        max_supply: args.max_supply,
+        metadata_locked: false,
+        membership_locked: false,
+    }
+}
+
+/// Loads a program owned account, checking its owner and that it holds `account_type`.
+fn load_account<T: BorshDeserialize>(
+    program_id: &Pubkey,
+    account_info: &AccountInfo,
+    account_type: AccountType,
+) -> Result<T, ProgramError> {
+    if account_info.owner != program_id {
+        return Err(CollectionError::InvalidAccountOwner.into());
+    }
+    let data = account_info.data.borrow();
+    match data.first() {
+        None => return Err(CollectionError::Uninitialized.into()),
+        Some(discriminator) if *discriminator == AccountType::Uninitialized as u8 => {
+            return Err(CollectionError::Uninitialized.into());
+        },
+        Some(discriminator) if *discriminator != account_type as u8 => {
+            return Err(CollectionError::InvalidAccountType.into());
+        },
+        _ => {},
+    }
+    let result = T::deserialize(&mut &data[..])?;
+    Ok(result)
+}
+
+fn load_collection_account(
+    program_id: &Pubkey,
+    collection_account_info: &AccountInfo,
+) -> Result<CollectionAccountData, ProgramError> {
+    let account_type = collection_account_type(&collection_account_info.data.borrow());
+    load_account(program_id, collection_account_info, account_type)
+}
+
+/// Fails when the account already holds program data, so it is never created twice.
+fn assert_uninitialized(account_info: &AccountInfo) -> ProgramResult {
+    let data = account_info.data.borrow();
+    if let Some(discriminator) = data.first() {
+        if *discriminator != AccountType::Uninitialized as u8 {
+            return Err(CollectionError::AlreadyInitialized.into());
+        }
+    }
+    Ok(())
+}
+
+/// Loads the program config, falling back to the defaults until `InitializeConfig` ran.
+fn load_config(program_id: &Pubkey, config_account_info: &AccountInfo) -> Result<ConfigAccountData, ProgramError> {
+    let (config_account, _) = get_config_account();
+    if config_account != *config_account_info.key {
+        return Err(CollectionError::InvalidConfigAccount.into());
+    }
+    if config_account_info.data_is_empty() {
+        return Ok(ConfigAccountData::default());
+    }
+    load_account(program_id, config_account_info, AccountType::ConfigAccount)
+}
+
+fn assert_upgrade_authority(
+    program_id: &Pubkey,
+    upgrade_authority_account_info: &AccountInfo,
+    program_data_account_info: &AccountInfo,
+) -> ProgramResult {
+    let (program_data_account, _) = Pubkey::find_program_address(
+        &[program_id.as_ref()],
+        &bpf_loader_upgradeable::id(),
+    );
+    if program_data_account != *program_data_account_info.key
+        || *program_data_account_info.owner != bpf_loader_upgradeable::id() {
+        return Err(CollectionError::NotUpgradeAuthority.into());
+    }
+    let upgrade_authority_address = match limited_deserialize(
+        &program_data_account_info.data.borrow(),
+        UpgradeableLoaderState::size_of_programdata_metadata() as u64,
+    ) {
+        Ok(UpgradeableLoaderState::ProgramData { upgrade_authority_address, .. }) => upgrade_authority_address,
+        _ => return Err(CollectionError::NotUpgradeAuthority.into()),
+    };
+    if upgrade_authority_address != Some(*upgrade_authority_account_info.key)
+        || !upgrade_authority_account_info.is_signer {
+        return Err(CollectionError::NotUpgradeAuthority.into());
+    }
+    Ok(())
+}
+
+/// Instructions the config admin can halt, grouped by what they do.
+fn pause_class(instruction: &CollectionInstruction) -> Option<PauseClass> {
+    match instruction {
+        CollectionInstruction::IncludeToken
+            | CollectionInstruction::IncludeTokenByCreator
+            | CollectionInstruction::SubmitToken
+            | CollectionInstruction::ApproveSubmission
+            | CollectionInstruction::MigrateIndexAccount => Some(PauseClass::Membership),
+        CollectionInstruction::LightUpStarsHundred
+            | CollectionInstruction::LightUpStarsThousand
+            | CollectionInstruction::LightUpStars { .. } => Some(PauseClass::Payments),
+        _ => None,
+    }
+}
+
+/// Rejects the instruction while its class is paused, the config account must be
+/// among the accounts so the check can not be skipped.
+fn assert_not_paused(program_id: &Pubkey, accounts: &[AccountInfo], class: PauseClass) -> ProgramResult {
+    let (config_account, _) = get_config_account();
+    let config_account_info = accounts
+        .iter()
+        .find(|account_info| *account_info.key == config_account)
+        .ok_or(CollectionError::InvalidConfigAccount)?;
+    let config = load_config(program_id, config_account_info)?;
+    if config.is_paused(class) {
+        return Err(CollectionError::ProgramPaused.into());
+    }
+    Ok(())
+}
@@ -1,6 +1,6 @@
 use {
+    std::str::FromStr,
     solana_program::{
-        borsh::try_from_slice_unchecked,
         program_error::ProgramError,
         pubkey::Pubkey,
     },
@@ -9,18 +9,43 @@ use {
 
 pub const PREFIX: &str = "collection";
 
+/// Treasury manager used until the config account is initialized.
+pub const DEFAULT_TREASURY_MANAGER: &str = "Ep1P3v2rMZ2FkyPx5uuGMaTztdSdtdvaUjcahT9y3EQv";
+
+/// Price of `LightUpStarsHundred` until the config account is initialized (0.01 SOL).
+pub const DEFAULT_STARS_HUNDRED_PRICE: u64 = 10_000_000;
+
+/// Price of `LightUpStarsThousand` until the config account is initialized (1 SOL).
+pub const DEFAULT_STARS_THOUSAND_PRICE: u64 = 1_000_000_000;
+pub const DEFAULT_PRICE_PER_STAR: u64 = 100_000;
+
+/// Layout version written after the `VersionedCollectionAccount` account type.
+/// Version `0` is the `CollectionAccount` layout without a version byte, from
+/// before versioning.
+pub const COLLECTION_ACCOUNT_VERSION: u8 = 1;
+
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, Copy)]
 pub enum AccountType {
     Uninitialized,
     CollectionAccount,
     CollectionIndexAccount,
+    CollectionPositionAccount,
+    ConfigAccount,
+    StarReceiptAccount,
+    SymbolAccount,
+    SubmissionAccount,
+    CuratorAccount,
+    /// Collection account followed by a layout version byte.
+    VersionedCollectionAccount,
 }
 
 #[repr(C)]
-#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+#[derive(BorshSerialize, Debug, Clone)]
 pub struct CollectionAccountData {
     pub account_type: AccountType,
+    /// Layout version the account was read from, `COLLECTION_ACCOUNT_VERSION` once written.
+    pub version: u8,
     pub authority: Pubkey,
     pub title: String,
     pub symbol: String,
@@ -32,6 +57,128 @@ pub struct CollectionAccountData {
     pub short_description: Option<String>,
     pub banner: Option<String>,
     pub tags: Option<Vec<String>>,
+    pub pending_authority: Option<Pubkey>,
+    /// Upper bound of `supply`, `None` for collections without a cap.
+    pub max_supply: Option<u64>,
+    /// Title, symbol, description, images and tags can no longer change.
+    pub metadata_locked: bool,
+    /// Tokens can no longer be included or removed.
+    pub membership_locked: bool,
+}
+
+impl BorshDeserialize for CollectionAccountData {
+    fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+        match collection_account_type(buf) {
+            AccountType::VersionedCollectionAccount => {
+                if buf.get(1) != Some(&COLLECTION_ACCOUNT_VERSION) {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        "unknown collection account version",
+                    ));
+                }
+                Self::deserialize_v1(buf)
+            },
+            _ => Self::deserialize_v0(buf),
+        }
+    }
+
+    /// The layout is picked from the leading bytes and unversioned accounts may
+    /// end early, so the rest of the reader is decoded as a slice.
+    fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+        Self::deserialize(&mut data.as_slice())
+    }
+}
+
+/// Account type a collection account is stored under, versioned accounts have
+/// their own discriminator so the layout never has to be guessed.
+pub fn collection_account_type(data: &[u8]) -> AccountType {
+    match data.first() {
+        Some(discriminator) if *discriminator == AccountType::VersionedCollectionAccount as u8 => {
+            AccountType::VersionedCollectionAccount
+        },
+        _ => AccountType::CollectionAccount,
+    }
+}
+
+impl CollectionAccountData {
+    fn deserialize_v1(buf: &mut &[u8]) -> std::io::Result<Self> {
+        Ok(CollectionAccountData {
+            account_type: BorshDeserialize::deserialize(buf)?,
+            version: BorshDeserialize::deserialize(buf)?,
+            authority: BorshDeserialize::deserialize(buf)?,
+            title: BorshDeserialize::deserialize(buf)?,
+            symbol: BorshDeserialize::deserialize(buf)?,
+            description: BorshDeserialize::deserialize(buf)?,
+            icon_image: BorshDeserialize::deserialize(buf)?,
+            supply: BorshDeserialize::deserialize(buf)?,
+            stars: BorshDeserialize::deserialize(buf)?,
+            header_image: BorshDeserialize::deserialize(buf)?,
+            short_description: BorshDeserialize::deserialize(buf)?,
+            banner: BorshDeserialize::deserialize(buf)?,
+            tags: BorshDeserialize::deserialize(buf)?,
+            pending_authority: BorshDeserialize::deserialize(buf)?,
+            max_supply: BorshDeserialize::deserialize(buf)?,
+            metadata_locked: BorshDeserialize::deserialize(buf)?,
+            membership_locked: BorshDeserialize::deserialize(buf)?,
+        })
+    }
+
+    /// Writes the unversioned layout up to the tags, the fields appended after them
+    /// are left as they are. Only the counters change in place, so the length
+    /// never does.
+    pub fn serialize_v0<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        self.account_type.serialize(writer)?;
+        self.authority.serialize(writer)?;
+        self.title.serialize(writer)?;
+        self.symbol.serialize(writer)?;
+        self.description.serialize(writer)?;
+        self.icon_image.serialize(writer)?;
+        self.supply.serialize(writer)?;
+        self.stars.serialize(writer)?;
+        self.header_image.serialize(writer)?;
+        self.short_description.serialize(writer)?;
+        self.banner.serialize(writer)?;
+        self.tags.serialize(writer)
+    }
+
+    /// Unversioned layout, fields appended over time may be missing.
+    fn deserialize_v0(buf: &mut &[u8]) -> std::io::Result<Self> {
+        Ok(CollectionAccountData {
+            account_type: BorshDeserialize::deserialize(buf)?,
+            version: 0,
+            authority: BorshDeserialize::deserialize(buf)?,
+            title: BorshDeserialize::deserialize(buf)?,
+            symbol: BorshDeserialize::deserialize(buf)?,
+            description: BorshDeserialize::deserialize(buf)?,
+            icon_image: BorshDeserialize::deserialize(buf)?,
+            supply: BorshDeserialize::deserialize(buf)?,
+            stars: BorshDeserialize::deserialize(buf)?,
+            header_image: BorshDeserialize::deserialize(buf)?,
+            short_description: BorshDeserialize::deserialize(buf)?,
+            banner: BorshDeserialize::deserialize(buf)?,
+            tags: BorshDeserialize::deserialize(buf)?,
+            pending_authority: deserialize_or_default(buf)?,
+            max_supply: deserialize_or_default(buf)?,
+            metadata_locked: deserialize_or_default(buf)?,
+            membership_locked: deserialize_or_default(buf)?,
+        })
+    }
+}
+
+/// Fields appended after the first release are missing from accounts created
+/// before them, those accounts read the default value instead.
+pub(crate) fn deserialize_or_default<T: BorshDeserialize + Default>(buf: &mut &[u8]) -> std::io::Result<T> {
+    if buf.is_empty() {
+        return Ok(T::default());
+    }
+    T::deserialize(buf)
+}
+
+/// Decodes `T` from the start of `data`, account data may be followed by unused bytes.
+pub fn try_from_slice_unchecked<T: BorshDeserialize>(mut data: &[u8]) -> Result<T, ProgramError> {
+    Ok(T::deserialize(&mut data)?)
 }
 
 impl CollectionAccountData {
@@ -42,6 +189,39 @@ impl CollectionAccountData {
 
     pub fn is_initialized(&self) -> bool {
         self.account_type == AccountType::CollectionAccount
+            || self.account_type == AccountType::VersionedCollectionAccount
+    }
+
+    pub fn is_current_version(&self) -> bool {
+        self.account_type == AccountType::VersionedCollectionAccount
+            && self.version == COLLECTION_ACCOUNT_VERSION
+    }
+
+    /// Moves the data to the current layout. Unversioned accounts counted every
+    /// inclusion, closed index accounts included, and have no position accounts,
+    /// so the supply restarts and legacy members are counted again as
+    /// `MigrateIndexAccount` appends them.
+    pub fn upgrade(&mut self) {
+        if !self.is_current_version() {
+            self.account_type = AccountType::VersionedCollectionAccount;
+            self.version = COLLECTION_ACCOUNT_VERSION;
+            self.supply = 0;
+        }
+    }
+
+    /// Supply once the account is in the current layout, the next token goes to
+    /// this position. Differs from `supply` only for unversioned accounts, which
+    /// restart at zero on the first write.
+    pub fn effective_supply(&self) -> u64 {
+        if self.is_current_version() {
+            self.supply
+        } else {
+            0
+        }
+    }
+
+    pub fn is_full(&self) -> bool {
+        matches!(self.max_supply, Some(max_supply) if self.supply >= max_supply)
     }
 }
 
@@ -67,11 +247,288 @@ impl CollectionIndexAccountData {
     }
 
     pub fn new(collection: Pubkey, mint: Pubkey, index: u64) -> CollectionIndexAccountData {
-        return CollectionIndexAccountData {
+        CollectionIndexAccountData {
             account_type: AccountType::CollectionIndexAccount,
             collection,
             mint,
             index,
-        };
+        }
+    }
+}
+
+/// Maps a position of a collection back to the mint stored there, so members
+/// can be paged through without scanning program accounts.
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct CollectionPositionAccountData {
+    pub account_type: AccountType,
+    pub collection: Pubkey,
+    pub mint: Pubkey,
+    pub index: u64,
+}
+
+impl CollectionPositionAccountData {
+    pub const LEN: usize = 1 + 32 + 32 + 8;
+
+    pub fn try_from_slice_unchecked(data: &[u8]) -> Result<CollectionPositionAccountData, ProgramError> {
+        let result: CollectionPositionAccountData = try_from_slice_unchecked(data)?;
+        Ok(result)
+    }
+
+    pub fn is_initialized(&self) -> bool {
+        self.account_type == AccountType::CollectionPositionAccount
+    }
+
+    pub fn new(collection: Pubkey, mint: Pubkey, index: u64) -> CollectionPositionAccountData {
+        CollectionPositionAccountData {
+            account_type: AccountType::CollectionPositionAccount,
+            collection,
+            mint,
+            index,
+        }
+    }
+}
+
+/// Length limits of the collection fields.
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, Copy)]
+pub struct FieldLimits {
+    pub max_title_length: u32,
+    pub max_symbol_length: u32,
+    pub max_uri_length: u32,
+    pub max_description_length: u32,
+    pub max_short_description_length: u32,
+    pub max_tag_length: u32,
+    pub max_tags_array_length: u32,
+}
+
+impl FieldLimits {
+    pub const LEN: usize = 4 * 7;
+
+    pub fn check_length(&self, value: &str, max_length: u32) -> bool {
+        value.len() <= max_length as usize
+    }
+
+    pub fn check_tags(&self, tags: &[String]) -> bool {
+        if tags.len() > self.max_tags_array_length as usize {
+            return false;
+        }
+        for tag in tags {
+            if tag.len() >= self.max_tag_length as usize {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+impl Default for FieldLimits {
+    fn default() -> Self {
+        FieldLimits {
+            max_title_length: 32,
+            max_symbol_length: 10,
+            max_uri_length: 200,
+            max_description_length: 800,
+            max_short_description_length: 800,
+            max_tag_length: 20,
+            max_tags_array_length: 6,
+        }
+    }
+}
+
+/// Instruction classes the config admin can pause independently.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum PauseClass {
+    /// instructions adding tokens to collections
+    Membership,
+    /// instructions charging lamports for stars
+    Payments,
+}
+
+/// Program wide settings, stored in the pda of ['collection', 'config', program id].
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct ConfigAccountData {
+    pub account_type: AccountType,
+    pub admin: Pubkey,
+    pub treasury_manager: Pubkey,
+    pub stars_hundred_price: u64,
+    pub stars_thousand_price: u64,
+    pub field_limits: FieldLimits,
+    pub price_per_star: u64,
+    pub membership_paused: bool,
+    pub payments_paused: bool,
+}
+
+impl ConfigAccountData {
+    pub const LEN: usize = 1 + 32 + 32 + 8 + 8 + FieldLimits::LEN + 8 + 1 + 1;
+
+    pub fn try_from_slice_unchecked(data: &[u8]) -> Result<ConfigAccountData, ProgramError> {
+        let result: ConfigAccountData = try_from_slice_unchecked(data)?;
+        Ok(result)
     }
-}
\ No newline at end of file
+
+    pub fn is_initialized(&self) -> bool {
+        self.account_type == AccountType::ConfigAccount
+    }
+
+    pub fn is_paused(&self, class: PauseClass) -> bool {
+        match class {
+            PauseClass::Membership => self.membership_paused,
+            PauseClass::Payments => self.payments_paused,
+        }
+    }
+}
+
+/// Settings in effect while the config account is not initialized.
+impl Default for ConfigAccountData {
+    fn default() -> Self {
+        ConfigAccountData {
+            account_type: AccountType::Uninitialized,
+            admin: Pubkey::default(),
+            treasury_manager: Pubkey::from_str(DEFAULT_TREASURY_MANAGER).unwrap(),
+            stars_hundred_price: DEFAULT_STARS_HUNDRED_PRICE,
+            stars_thousand_price: DEFAULT_STARS_THOUSAND_PRICE,
+            field_limits: FieldLimits::default(),
+            price_per_star: DEFAULT_PRICE_PER_STAR,
+            membership_paused: false,
+            payments_paused: false,
+        }
+    }
+}
+
+/// Stars bought by one supporter of a collection, stored in the pda of
+/// ['collection', program id, 'receipt', collection id, supporter id].
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct StarReceiptAccountData {
+    pub account_type: AccountType,
+    pub collection: Pubkey,
+    pub supporter: Pubkey,
+    pub stars: u64,
+    pub lamports: u64,
+    pub last_slot: u64,
+}
+
+impl StarReceiptAccountData {
+    pub const LEN: usize = 1 + 32 + 32 + 8 + 8 + 8;
+
+    pub fn try_from_slice_unchecked(data: &[u8]) -> Result<StarReceiptAccountData, ProgramError> {
+        let result: StarReceiptAccountData = try_from_slice_unchecked(data)?;
+        Ok(result)
+    }
+
+    pub fn is_initialized(&self) -> bool {
+        self.account_type == AccountType::StarReceiptAccount
+    }
+
+    pub fn new(collection: Pubkey, supporter: Pubkey) -> StarReceiptAccountData {
+        StarReceiptAccountData {
+            account_type: AccountType::StarReceiptAccount,
+            collection,
+            supporter,
+            stars: 0,
+            lamports: 0,
+            last_slot: 0,
+        }
+    }
+}
+
+/// Reservation of a symbol by a collection, stored in the pda of
+/// ['collection', program id, 'symbol', normalized symbol].
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct SymbolAccountData {
+    pub account_type: AccountType,
+    pub collection: Pubkey,
+}
+
+impl SymbolAccountData {
+    pub const LEN: usize = 1 + 32;
+
+    pub fn try_from_slice_unchecked(data: &[u8]) -> Result<SymbolAccountData, ProgramError> {
+        let result: SymbolAccountData = try_from_slice_unchecked(data)?;
+        Ok(result)
+    }
+
+    pub fn is_initialized(&self) -> bool {
+        self.account_type == AccountType::SymbolAccount
+    }
+}
+
+/// Token submitted to a collection by its holder, waiting for the collection
+/// authority to approve or reject it.
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct SubmissionAccountData {
+    pub account_type: AccountType,
+    pub collection: Pubkey,
+    pub mint: Pubkey,
+    pub submitter: Pubkey,
+}
+
+impl SubmissionAccountData {
+    pub const LEN: usize = 1 + 32 + 32 + 32;
+
+    pub fn try_from_slice_unchecked(data: &[u8]) -> Result<SubmissionAccountData, ProgramError> {
+        let result: SubmissionAccountData = try_from_slice_unchecked(data)?;
+        Ok(result)
+    }
+
+    pub fn is_initialized(&self) -> bool {
+        self.account_type == AccountType::SubmissionAccount
+    }
+
+    pub fn new(collection: Pubkey, mint: Pubkey, submitter: Pubkey) -> SubmissionAccountData {
+        SubmissionAccountData {
+            account_type: AccountType::SubmissionAccount,
+            collection,
+            mint,
+            submitter,
+        }
+    }
+}
+
+/// Curators may include tokens, also by approving or rejecting submissions.
+pub const CURATOR_INCLUDE: u8 = 1;
+/// Curators may remove tokens.
+pub const CURATOR_REMOVE: u8 = 1 << 1;
+/// Curators may edit the collection metadata.
+pub const CURATOR_EDIT: u8 = 1 << 2;
+pub const CURATOR_ALL: u8 = CURATOR_INCLUDE | CURATOR_REMOVE | CURATOR_EDIT;
+
+/// Role of a member acting on behalf of the collection authority.
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct CuratorAccountData {
+    pub account_type: AccountType,
+    pub collection: Pubkey,
+    pub curator: Pubkey,
+    pub permissions: u8,
+}
+
+impl CuratorAccountData {
+    pub const LEN: usize = 1 + 32 + 32 + 1;
+
+    pub fn try_from_slice_unchecked(data: &[u8]) -> Result<CuratorAccountData, ProgramError> {
+        let result: CuratorAccountData = try_from_slice_unchecked(data)?;
+        Ok(result)
+    }
+
+    pub fn is_initialized(&self) -> bool {
+        self.account_type == AccountType::CuratorAccount
+    }
+
+    pub fn new(collection: Pubkey, curator: Pubkey, permissions: u8) -> CuratorAccountData {
+        CuratorAccountData {
+            account_type: AccountType::CuratorAccount,
+            collection,
+            curator,
+            permissions,
+        }
+    }
+
+    pub fn has_permission(&self, permission: u8) -> bool {
+        self.permissions & permission == permission
+    }
+}
@@ -1,6 +1,7 @@
 use {
     crate::{
         state::PREFIX,
+        metadata,
         id,
     },
     solana_program::{
@@ -8,7 +9,7 @@ use {
         entrypoint::ProgramResult,
         msg,
         program::{invoke, invoke_signed},
-        pubkey::Pubkey,
+        pubkey::{Pubkey, MAX_SEED_LEN},
         system_instruction,
         sysvar::{rent::Rent, Sysvar},
     },
@@ -64,7 +65,7 @@ pub fn create_or_allocate_account_raw<'a>(
     if required_lamports > 0 {
         msg!("Transfer {} lamports to the new account", required_lamports);
         invoke(
-            &system_instruction::transfer(&payer_info.key, new_account_info.key, required_lamports),
+            &system_instruction::transfer(payer_info.key, new_account_info.key, required_lamports),
             &[
                 payer_info.clone(),
                 new_account_info.clone(),
@@ -79,30 +80,100 @@ pub fn create_or_allocate_account_raw<'a>(
     invoke_signed(
         &system_instruction::allocate(new_account_info.key, size.try_into().unwrap()),
         accounts,
-        &[&signer_seeds],
+        &[signer_seeds],
     )?;
 
     msg!("Assign the account to the owning program");
     invoke_signed(
         &system_instruction::assign(new_account_info.key, &program_id),
         accounts,
-        &[&signer_seeds],
+        &[signer_seeds],
     )?;
 
     Ok(())
 }
 
+/// Resize a program owned account, the payer tops up the rent when the account
+/// grows and the refund account receives the surplus lamports when it shrinks.
+#[inline(always)]
+pub fn resize_account<'a>(
+    account_info: &AccountInfo<'a>,
+    new_size: usize,
+    payer_info: &AccountInfo<'a>,
+    refund_info: &AccountInfo<'a>,
+    rent_sysvar_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+) -> ProgramResult {
+    let rent = &Rent::from_account_info(rent_sysvar_info)?;
+    let required_lamports = rent.minimum_balance(new_size).max(1);
+    let current_lamports = account_info.lamports();
+
+    if required_lamports > current_lamports {
+        let lamports = required_lamports - current_lamports;
+        msg!("Transfer {} lamports to the resized account", lamports);
+        invoke(
+            &system_instruction::transfer(payer_info.key, account_info.key, lamports),
+            &[
+                payer_info.clone(),
+                account_info.clone(),
+                system_program_info.clone(),
+            ],
+        )?;
+    } else if current_lamports > required_lamports {
+        let lamports = current_lamports - required_lamports;
+        msg!("Refund {} lamports from the resized account", lamports);
+        let refund_starting_lamports = refund_info.lamports();
+        **refund_info.lamports.borrow_mut() = refund_starting_lamports.checked_add(lamports).unwrap();
+        **account_info.lamports.borrow_mut() = required_lamports;
+    }
+
+    account_info.realloc(new_size, false)
+}
+
 pub fn get_index_account(
+    collection_address: &Pubkey,
     spl_token_mint_address: &Pubkey,
 ) -> (Pubkey, u8) {
     let program_id = id();
     let seeds = &[
         PREFIX.as_bytes(),
         program_id.as_ref(),
+        collection_address.as_ref(),
         spl_token_mint_address.as_ref(),
     ];
-    let (collection_address, bump_seed) = Pubkey::find_program_address(seeds, &program_id);
-    (collection_address, bump_seed)
+    let (index_address, bump_seed) = Pubkey::find_program_address(seeds, &program_id);
+    (index_address, bump_seed)
+}
+
+/// Index account address used before a mint could belong to several collections,
+/// only needed to migrate existing index accounts.
+pub fn get_legacy_index_account(
+    spl_token_mint_address: &Pubkey,
+) -> (Pubkey, u8) {
+    let program_id = id();
+    let seeds = &[
+        PREFIX.as_bytes(),
+        program_id.as_ref(),
+        spl_token_mint_address.as_ref(),
+    ];
+    let (index_address, bump_seed) = Pubkey::find_program_address(seeds, &program_id);
+    (index_address, bump_seed)
+}
+
+pub fn get_position_account(
+    collection_address: &Pubkey,
+    index: u64,
+) -> (Pubkey, u8) {
+    let program_id = id();
+    let index_bytes = index.to_le_bytes();
+    let seeds = &[
+        PREFIX.as_bytes(),
+        program_id.as_ref(),
+        collection_address.as_ref(),
+        index_bytes.as_ref(),
+    ];
+    let (position_address, bump_seed) = Pubkey::find_program_address(seeds, &program_id);
+    (position_address, bump_seed)
 }
 
 pub fn get_treasury_account() -> (Pubkey, u8) {
@@ -116,3 +187,115 @@ pub fn get_treasury_account() -> (Pubkey, u8) {
     let (treasury_address, bump_seed) = Pubkey::find_program_address(seeds, &program_id);
     (treasury_address, bump_seed)
 }
+
+pub fn get_config_account() -> (Pubkey, u8) {
+    const CONFIG: &str = "config";
+    let program_id = id();
+    let seeds = &[
+        PREFIX.as_bytes(),
+        CONFIG.as_bytes(),
+        program_id.as_ref(),
+    ];
+    let (config_address, bump_seed) = Pubkey::find_program_address(seeds, &program_id);
+    (config_address, bump_seed)
+}
+
+pub fn get_star_receipt_account(
+    collection_address: &Pubkey,
+    supporter_address: &Pubkey,
+) -> (Pubkey, u8) {
+    const RECEIPT: &str = "receipt";
+    let program_id = id();
+    let seeds = &[
+        PREFIX.as_bytes(),
+        program_id.as_ref(),
+        RECEIPT.as_bytes(),
+        collection_address.as_ref(),
+        supporter_address.as_ref(),
+    ];
+    let (receipt_address, bump_seed) = Pubkey::find_program_address(seeds, &program_id);
+    (receipt_address, bump_seed)
+}
+
+pub fn get_collection_account(
+    authority_address: &Pubkey,
+    slug: &str,
+) -> (Pubkey, u8) {
+    let program_id = id();
+    let seeds = &[
+        PREFIX.as_bytes(),
+        authority_address.as_ref(),
+        slug.as_bytes(),
+    ];
+    let (collection_address, bump_seed) = Pubkey::find_program_address(seeds, &program_id);
+    (collection_address, bump_seed)
+}
+
+/// Symbols are reserved case insensitively and without surrounding whitespace.
+pub fn normalize_symbol(symbol: &str) -> String {
+    symbol.trim().to_uppercase()
+}
+
+/// Symbol account reserving `symbol`, `None` for symbols that can not be
+/// reserved because they are empty or too long for a seed once normalized.
+pub fn get_symbol_account(symbol: &str) -> Option<(Pubkey, u8)> {
+    const SYMBOL: &str = "symbol";
+    let program_id = id();
+    let normalized_symbol = normalize_symbol(symbol);
+    if normalized_symbol.is_empty() || normalized_symbol.len() > MAX_SEED_LEN {
+        return None;
+    }
+    let seeds = &[
+        PREFIX.as_bytes(),
+        program_id.as_ref(),
+        SYMBOL.as_bytes(),
+        normalized_symbol.as_bytes(),
+    ];
+    Pubkey::try_find_program_address(seeds, &program_id)
+}
+
+/// Metaplex metadata account of the mint, owned by the token metadata program.
+pub fn get_metadata_account(mint: &Pubkey) -> (Pubkey, u8) {
+    let metadata_program_id = metadata::program::id();
+    let seeds = &[
+        metadata::PREFIX.as_bytes(),
+        metadata_program_id.as_ref(),
+        mint.as_ref(),
+    ];
+    let (metadata_address, bump_seed) = Pubkey::find_program_address(seeds, &metadata_program_id);
+    (metadata_address, bump_seed)
+}
+
+pub fn get_submission_account(
+    collection_address: &Pubkey,
+    spl_token_mint_address: &Pubkey,
+) -> (Pubkey, u8) {
+    const SUBMISSION: &str = "submission";
+    let program_id = id();
+    let seeds = &[
+        PREFIX.as_bytes(),
+        program_id.as_ref(),
+        collection_address.as_ref(),
+        SUBMISSION.as_bytes(),
+        spl_token_mint_address.as_ref(),
+    ];
+    let (submission_address, bump_seed) = Pubkey::find_program_address(seeds, &program_id);
+    (submission_address, bump_seed)
+}
+
+pub fn get_curator_account(
+    collection_address: &Pubkey,
+    member_address: &Pubkey,
+) -> (Pubkey, u8) {
+    const CURATOR: &str = "curator";
+    let program_id = id();
+    let seeds = &[
+        PREFIX.as_bytes(),
+        program_id.as_ref(),
+        collection_address.as_ref(),
+        CURATOR.as_bytes(),
+        member_address.as_ref(),
+    ];
+    let (curator_address, bump_seed) = Pubkey::find_program_address(seeds, &program_id);
+    (curator_address, bump_seed)
+}
@@ -0,0 +1,615 @@
+//! Generates `idl/collection.json`, the machine readable interface of the program
+//! the JS SDK schemas are generated from.
+//!
+//! The layouts below are written by hand, so every one of them is checked against
+//! the bytes the program actually reads and writes before the IDL is compared to
+//! the checked in file. Run `UPDATE_IDL=1 cargo test --test idl` to rewrite it.
+
+use collection::error::CollectionError;
+use collection::event::{
+    AccountClosed, CollectionCreated, CollectionEvent, CollectionMigrated, StarsLit, TokenIncluded, TokenRemoved, Withdrawn,
+};
+use collection::instruction::{
+    decode_instruction, CollectionInstruction, ConfigArgs, CreateCollectionAccountArgs, UpdateCollectionAccountArgs,
+    CLEAR_ALL,
+};
+use collection::state::{
+    AccountType, CollectionAccountData, CollectionIndexAccountData, CollectionPositionAccountData, ConfigAccountData,
+    CuratorAccountData, StarReceiptAccountData, SubmissionAccountData, SymbolAccountData, CURATOR_ALL,
+    COLLECTION_ACCOUNT_VERSION,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use num_traits::FromPrimitive;
+use serde_json::{json, Value};
+use solana_sdk::pubkey::Pubkey;
+use std::convert::TryInto;
+#[allow(dead_code)]
+use collection_test_utils::{default_collection_args, every_instruction};
+
+const IDL_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/idl/collection.json");
+
+fn field(name: &str, ty: Value) -> Value {
+    json!({ "name": name, "type": ty })
+}
+
+fn option(ty: Value) -> Value {
+    json!({ "option": ty })
+}
+
+fn vec_of(ty: Value) -> Value {
+    json!({ "vec": ty })
+}
+
+fn defined(name: &str) -> Value {
+    json!({ "defined": name })
+}
+
+fn account_type() -> Value {
+    field("account_type", defined("AccountType"))
+}
+
+fn collection_args_fields(wrap: fn(Value) -> Value) -> Vec<Value> {
+    vec![
+        field("title", wrap(json!("string"))),
+        field("symbol", wrap(json!("string"))),
+        field("description", wrap(json!("string"))),
+        field("icon_image", wrap(json!("string"))),
+        field("header_image", option(json!("string"))),
+        field("short_description", option(json!("string"))),
+        field("banner", option(json!("string"))),
+        field("tags", option(vec_of(json!("string")))),
+        field("max_supply", option(json!("u64"))),
+    ]
+}
+
+fn struct_type(name: &str, fields: Vec<Value>) -> Value {
+    json!({ "name": name, "type": { "kind": "struct", "fields": fields } })
+}
+
+/// Variants of a fieldless enum, read back from its borsh encoding.
+fn enum_type<T: BorshDeserialize + std::fmt::Debug>(name: &str) -> Value {
+    let variants: Vec<Value> = (0..=u8::MAX)
+        .map_while(|index| T::try_from_slice(&[index]).ok())
+        .map(|variant| json!({ "name": format!("{:?}", variant) }))
+        .collect();
+    json!({ "name": name, "type": { "kind": "enum", "variants": variants } })
+}
+
+fn types() -> Vec<Value> {
+    vec![
+        enum_type::<AccountType>("AccountType"),
+        struct_type("CreateCollectionAccountArgs", collection_args_fields(|ty| ty)),
+        struct_type(
+            "UpdateCollectionAccountArgs",
+            collection_args_fields(option).into_iter().chain(vec![field("clear", json!("u8"))]).collect(),
+        ),
+        struct_type(
+            "FieldLimits",
+            [
+                "max_title_length",
+                "max_symbol_length",
+                "max_uri_length",
+                "max_description_length",
+                "max_short_description_length",
+                "max_tag_length",
+                "max_tags_array_length",
+            ]
+            .iter()
+            .map(|name| field(name, json!("u32")))
+            .collect(),
+        ),
+        struct_type(
+            "ConfigArgs",
+            vec![
+                field("admin", json!("publicKey")),
+                field("treasury_manager", json!("publicKey")),
+                field("stars_hundred_price", json!("u64")),
+                field("stars_thousand_price", json!("u64")),
+                field("field_limits", defined("FieldLimits")),
+                field("price_per_star", json!("u64")),
+            ],
+        ),
+    ]
+}
+
+fn account(name: &str, account_type: AccountType, fields: Vec<Value>) -> Value {
+    let mut account = struct_type(name, fields);
+    account["accountType"] = json!(format!("{:?}", account_type));
+    account
+}
+
+fn accounts() -> Vec<Value> {
+    let index_fields = || {
+        vec![
+            account_type(),
+            field("collection", json!("publicKey")),
+            field("mint", json!("publicKey")),
+            field("index", json!("u64")),
+        ]
+    };
+    vec![
+        account(
+            "CollectionAccountData",
+            AccountType::VersionedCollectionAccount,
+            vec![
+                account_type(),
+                field("version", json!("u8")),
+                field("authority", json!("publicKey")),
+                field("title", json!("string")),
+                field("symbol", json!("string")),
+                field("description", json!("string")),
+                field("icon_image", json!("string")),
+                field("supply", json!("u64")),
+                field("stars", json!("u64")),
+                field("header_image", option(json!("string"))),
+                field("short_description", option(json!("string"))),
+                field("banner", option(json!("string"))),
+                field("tags", option(vec_of(json!("string")))),
+                field("pending_authority", option(json!("publicKey"))),
+                field("max_supply", option(json!("u64"))),
+                field("metadata_locked", json!("bool")),
+                field("membership_locked", json!("bool")),
+            ],
+        ),
+        account("CollectionIndexAccountData", AccountType::CollectionIndexAccount, index_fields()),
+        account("CollectionPositionAccountData", AccountType::CollectionPositionAccount, index_fields()),
+        account(
+            "ConfigAccountData",
+            AccountType::ConfigAccount,
+            vec![
+                account_type(),
+                field("admin", json!("publicKey")),
+                field("treasury_manager", json!("publicKey")),
+                field("stars_hundred_price", json!("u64")),
+                field("stars_thousand_price", json!("u64")),
+                field("field_limits", defined("FieldLimits")),
+                field("price_per_star", json!("u64")),
+                field("membership_paused", json!("bool")),
+                field("payments_paused", json!("bool")),
+            ],
+        ),
+        account(
+            "StarReceiptAccountData",
+            AccountType::StarReceiptAccount,
+            vec![
+                account_type(),
+                field("collection", json!("publicKey")),
+                field("supporter", json!("publicKey")),
+                field("stars", json!("u64")),
+                field("lamports", json!("u64")),
+                field("last_slot", json!("u64")),
+            ],
+        ),
+        account(
+            "SymbolAccountData",
+            AccountType::SymbolAccount,
+            vec![account_type(), field("collection", json!("publicKey"))],
+        ),
+        account(
+            "SubmissionAccountData",
+            AccountType::SubmissionAccount,
+            vec![
+                account_type(),
+                field("collection", json!("publicKey")),
+                field("mint", json!("publicKey")),
+                field("submitter", json!("publicKey")),
+            ],
+        ),
+        account(
+            "CuratorAccountData",
+            AccountType::CuratorAccount,
+            vec![
+                account_type(),
+                field("collection", json!("publicKey")),
+                field("curator", json!("publicKey")),
+                field("permissions", json!("u8")),
+            ],
+        ),
+    ]
+}
+
+/// Arguments following the variant index of each instruction. The match is
+/// exhaustive so a new variant can not be left out of the IDL.
+fn instruction_args(instruction: &CollectionInstruction) -> Vec<Value> {
+    match instruction {
+        CollectionInstruction::CreateCollectionAccount(_) => {
+            vec![field("args", defined("CreateCollectionAccountArgs"))]
+        }
+        CollectionInstruction::CloseAccount(_) => vec![field("account_type", defined("AccountType"))],
+        CollectionInstruction::Withdraw { .. } => vec![field("amount", option(json!("u64")))],
+        CollectionInstruction::UpdateCollectionAccount(_) => {
+            vec![field("args", defined("UpdateCollectionAccountArgs"))]
+        }
+        CollectionInstruction::InitializeConfig(_) | CollectionInstruction::UpdateConfig(_) => {
+            vec![field("args", defined("ConfigArgs"))]
+        }
+        CollectionInstruction::LightUpStars { .. } => vec![field("amount", json!("u64"))],
+        CollectionInstruction::SetPaused { .. } => {
+            vec![field("membership", json!("bool")), field("payments", json!("bool"))]
+        }
+        CollectionInstruction::CreateCollectionPda { .. } => vec![
+            field("slug", json!("string")),
+            field("args", defined("CreateCollectionAccountArgs")),
+        ],
+        CollectionInstruction::AddCurator { .. } => vec![field("permissions", json!("u8"))],
+        CollectionInstruction::LockCollection { .. } => {
+            vec![field("metadata", json!("bool")), field("membership", json!("bool"))]
+        }
+        CollectionInstruction::IncludeToken
+        | CollectionInstruction::LightUpStarsOnce
+        | CollectionInstruction::LightUpStarsHundred
+        | CollectionInstruction::LightUpStarsThousand
+        | CollectionInstruction::ProposeAuthority
+        | CollectionInstruction::AcceptAuthority
+        | CollectionInstruction::CancelAuthorityTransfer
+        | CollectionInstruction::RemoveToken
+        | CollectionInstruction::MigrateIndexAccount
+        | CollectionInstruction::IncludeTokenByCreator
+        | CollectionInstruction::SubmitToken
+        | CollectionInstruction::ApproveSubmission
+        | CollectionInstruction::RejectSubmission
+        | CollectionInstruction::RemoveCurator
+        | CollectionInstruction::MigrateCollectionAccount => vec![],
+    }
+}
+
+/// Accounts and writable and signer flags as the builders emit them, names as
+/// `decode_instruction` gives them.
+fn instructions() -> Vec<Value> {
+    every_instruction()
+        .iter()
+        .enumerate()
+        .map(|(discriminant, instruction)| {
+            assert_eq!(instruction.data[0] as usize, discriminant, "every_instruction is out of variant order");
+            let parsed = decode_instruction(instruction).unwrap();
+            let (required, _) = parsed.instruction.account_names();
+            let accounts: Vec<Value> = parsed
+                .accounts
+                .iter()
+                .enumerate()
+                .map(|(index, account)| {
+                    let mut meta = json!({
+                        "name": account.name,
+                        "isMut": account.is_writable,
+                        "isSigner": account.is_signer,
+                    });
+                    if index >= required.len() {
+                        meta["optional"] = json!(true);
+                    }
+                    meta
+                })
+                .collect();
+            let mut idl_instruction = json!({
+                "name": parsed.instruction.name(),
+                "discriminant": discriminant,
+                "accounts": accounts,
+                "args": instruction_args(&parsed.instruction),
+            });
+            match parsed.instruction {
+                CollectionInstruction::CloseAccount(_) => {
+                    idl_instruction["docs"] = json!([
+                        "Closing a CollectionAccount also takes the symbol account of its symbol, unless the symbol is empty or too long to reserve.",
+                        "Closing a CollectionIndexAccount takes the collection, position, last_position and last_index accounts after the authority, followed by the optional holder_token_account.",
+                    ]);
+                }
+                CollectionInstruction::UpdateCollectionAccount(_) => {
+                    idl_instruction["docs"] = json!([
+                        "Changing the symbol takes the symbol and new_symbol accounts after the config, before the optional curator.",
+                        "Symbols that are empty or too long to reserve have no symbol account, it is left out.",
+                        "A curator signing it passes the collection_authority after its curator account, the refunds go to it.",
+                    ]);
+                }
+                _ => {}
+            }
+            idl_instruction
+        })
+        .collect()
+}
+
+/// Events in the order of the `CollectionEvent` variants, the index is the first
+/// byte of the logged data.
+fn events() -> Vec<Value> {
+    let event = |name: &str, discriminant: u8, fields: Vec<Value>| {
+        let mut event = struct_type(name, fields);
+        event["discriminant"] = json!(discriminant);
+        event
+    };
+    vec![
+        event(
+            "CollectionCreated",
+            0,
+            vec![
+                field("collection", json!("publicKey")),
+                field("authority", json!("publicKey")),
+                field("title", json!("string")),
+                field("symbol", json!("string")),
+                field("max_supply", option(json!("u64"))),
+            ],
+        ),
+        event(
+            "TokenIncluded",
+            1,
+            vec![
+                field("collection", json!("publicKey")),
+                field("mint", json!("publicKey")),
+                field("index", json!("u64")),
+                field("supply", json!("u64")),
+            ],
+        ),
+        event(
+            "TokenRemoved",
+            2,
+            vec![
+                field("collection", json!("publicKey")),
+                field("mint", json!("publicKey")),
+                field("index", json!("u64")),
+                field("supply", json!("u64")),
+            ],
+        ),
+        event(
+            "StarsLit",
+            3,
+            vec![
+                field("collection", json!("publicKey")),
+                field("supporter", option(json!("publicKey"))),
+                field("stars", json!("u64")),
+                field("lamports", json!("u64")),
+                field("total_stars", json!("u64")),
+            ],
+        ),
+        event(
+            "Withdrawn",
+            4,
+            vec![
+                field("treasury_manager", json!("publicKey")),
+                field("recipient", json!("publicKey")),
+                field("lamports", json!("u64")),
+            ],
+        ),
+        event(
+            "AccountClosed",
+            5,
+            vec![
+                field("account", json!("publicKey")),
+                field("account_type", defined("AccountType")),
+                field("recipient", json!("publicKey")),
+            ],
+        ),
+        event(
+            "CollectionMigrated",
+            6,
+            vec![
+                field("collection", json!("publicKey")),
+                field("version", json!("u8")),
+                field("legacy_supply", json!("u64")),
+            ],
+        ),
+    ]
+}
+
+fn errors() -> Vec<Value> {
+    (0..)
+        .map_while(|code| CollectionError::from_u32(code).map(|error| (code, error)))
+        .map(|(code, error)| json!({ "code": code, "name": format!("{:?}", error), "msg": error.to_string() }))
+        .collect()
+}
+
+fn idl() -> Value {
+    json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "name": "collection",
+        "address": collection::id().to_string(),
+        "instructions": instructions(),
+        "accounts": accounts(),
+        "types": types(),
+        "events": events(),
+        "errors": errors(),
+    })
+}
+
+fn take<'a>(data: &mut &'a [u8], len: usize) -> &'a [u8] {
+    assert!(data.len() >= len, "layout reads past the end of the data");
+    let (head, tail) = data.split_at(len);
+    *data = tail;
+    head
+}
+
+fn find_type<'a>(idl: &'a Value, name: &str) -> &'a Value {
+    idl["types"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .chain(idl["accounts"].as_array().unwrap())
+        .chain(idl["events"].as_array().unwrap())
+        .find(|ty| ty["name"] == name)
+        .unwrap_or_else(|| panic!("type {} is not described", name))
+}
+
+/// Walks `data` along the layout of `ty` the way borsh would read it.
+fn read(idl: &Value, ty: &Value, data: &mut &[u8]) {
+    if let Some(name) = ty.as_str() {
+        let len = match name {
+            "u8" => 1,
+            "bool" => {
+                assert!(take(data, 1)[0] <= 1, "invalid bool");
+                return;
+            }
+            "u16" => 2,
+            "u32" => 4,
+            "u64" => 8,
+            "publicKey" => 32,
+            "string" => {
+                let len = u32::from_le_bytes(take(data, 4).try_into().unwrap());
+                String::from_utf8(take(data, len as usize).to_vec()).expect("invalid string");
+                return;
+            }
+            _ => panic!("unknown type {}", name),
+        };
+        take(data, len);
+    } else if let Some(inner) = ty.get("option") {
+        match take(data, 1)[0] {
+            0 => {}
+            1 => read(idl, inner, data),
+            tag => panic!("invalid option tag {}", tag),
+        }
+    } else if let Some(inner) = ty.get("vec") {
+        let len = u32::from_le_bytes(take(data, 4).try_into().unwrap());
+        for _ in 0..len {
+            read(idl, inner, data);
+        }
+    } else if let Some(name) = ty["defined"].as_str() {
+        read_fields(idl, &find_type(idl, name)["type"], data);
+    } else {
+        panic!("unknown type {}", ty);
+    }
+}
+
+fn read_fields(idl: &Value, ty: &Value, data: &mut &[u8]) {
+    match ty["kind"].as_str().unwrap() {
+        "struct" => {
+            for field in ty["fields"].as_array().unwrap() {
+                read(idl, &field["type"], data);
+            }
+        }
+        "enum" => {
+            let variant = take(data, 1)[0] as usize;
+            assert!(variant < ty["variants"].as_array().unwrap().len(), "invalid variant {}", variant);
+        }
+        kind => panic!("unknown kind {}", kind),
+    }
+}
+
+/// Fails unless the layout of `name` reads exactly the bytes of `value`.
+fn assert_layout<T: BorshSerialize>(idl: &Value, name: &str, value: &T) {
+    let bytes = value.try_to_vec().unwrap();
+    let mut data = &bytes[..];
+    read_fields(idl, &find_type(idl, name)["type"], &mut data);
+    assert!(data.is_empty(), "layout of {} leaves {} bytes unread", name, data.len());
+}
+
+#[test]
+fn test_idl() {
+    let idl = idl();
+
+    // instruction data is the variant index followed by the arguments
+    for (instruction, idl_instruction) in every_instruction().iter().zip(idl["instructions"].as_array().unwrap()) {
+        let mut data = &instruction.data[1..];
+        for arg in idl_instruction["args"].as_array().unwrap() {
+            read(&idl, &arg["type"], &mut data);
+        }
+        assert!(data.is_empty(), "arguments of {} leave bytes unread", idl_instruction["name"]);
+    }
+
+    // every optional field set, so no layout can hide behind a `None`
+    let address = Pubkey::new_unique();
+    let args = CreateCollectionAccountArgs { max_supply: Some(10), ..default_collection_args() };
+    assert_layout(&idl, "CreateCollectionAccountArgs", &args);
+    assert_layout(&idl, "UpdateCollectionAccountArgs", &UpdateCollectionAccountArgs {
+        title: Some(args.title.clone()),
+        symbol: Some(args.symbol.clone()),
+        description: Some(args.description.clone()),
+        icon_image: Some(args.icon_image.clone()),
+        header_image: args.header_image.clone(),
+        short_description: args.short_description.clone(),
+        banner: args.banner.clone(),
+        tags: args.tags.clone(),
+        max_supply: args.max_supply,
+        clear: CLEAR_ALL,
+    });
+    assert_layout(&idl, "CollectionAccountData", &CollectionAccountData {
+        account_type: AccountType::VersionedCollectionAccount,
+        version: COLLECTION_ACCOUNT_VERSION,
+        authority: address,
+        title: args.title,
+        symbol: args.symbol,
+        description: args.description,
+        icon_image: args.icon_image,
+        supply: 1,
+        stars: 2,
+        header_image: args.header_image,
+        short_description: args.short_description,
+        banner: args.banner,
+        tags: args.tags,
+        pending_authority: Some(address),
+        max_supply: args.max_supply,
+        metadata_locked: true,
+        membership_locked: false,
+    });
+    assert_layout(&idl, "CollectionIndexAccountData", &CollectionIndexAccountData::new(address, address, 3));
+    assert_layout(&idl, "CollectionPositionAccountData", &CollectionPositionAccountData::new(address, address, 3));
+    assert_layout(&idl, "ConfigAccountData", &ConfigAccountData::default());
+    assert_layout(&idl, "ConfigArgs", &ConfigArgs {
+        admin: address,
+        treasury_manager: address,
+        stars_hundred_price: 1,
+        stars_thousand_price: 2,
+        field_limits: Default::default(),
+        price_per_star: 3,
+    });
+    assert_layout(&idl, "StarReceiptAccountData", &StarReceiptAccountData {
+        account_type: AccountType::StarReceiptAccount,
+        collection: address,
+        supporter: address,
+        stars: 1,
+        lamports: 2,
+        last_slot: 3,
+    });
+    assert_layout(&idl, "SymbolAccountData", &SymbolAccountData {
+        account_type: AccountType::SymbolAccount,
+        collection: address,
+    });
+    assert_layout(&idl, "SubmissionAccountData", &SubmissionAccountData::new(address, address, address));
+    assert_layout(&idl, "CuratorAccountData", &CuratorAccountData {
+        account_type: AccountType::CuratorAccount,
+        collection: address,
+        curator: address,
+        permissions: CURATOR_ALL,
+    });
+
+    let events = [
+        CollectionEvent::CollectionCreated(CollectionCreated {
+            collection: address,
+            authority: address,
+            title: "title".to_string(),
+            symbol: "symbol".to_string(),
+            max_supply: Some(1),
+        }),
+        CollectionEvent::TokenIncluded(TokenIncluded { collection: address, mint: address, index: 1, supply: 2 }),
+        CollectionEvent::TokenRemoved(TokenRemoved { collection: address, mint: address, index: 1, supply: 1 }),
+        CollectionEvent::StarsLit(StarsLit {
+            collection: address,
+            supporter: Some(address),
+            stars: 1,
+            lamports: 2,
+            total_stars: 3,
+        }),
+        CollectionEvent::Withdrawn(Withdrawn { treasury_manager: address, recipient: address, lamports: 1 }),
+        CollectionEvent::AccountClosed(AccountClosed {
+            account: address,
+            account_type: AccountType::CollectionIndexAccount,
+            recipient: address,
+        }),
+        CollectionEvent::CollectionMigrated(CollectionMigrated {
+            collection: address,
+            version: COLLECTION_ACCOUNT_VERSION,
+            legacy_supply: 2,
+        }),
+    ];
+    assert_eq!(events.len(), idl["events"].as_array().unwrap().len());
+    for (event, idl_event) in events.iter().zip(idl["events"].as_array().unwrap()) {
+        let bytes = event.try_to_vec().unwrap();
+        assert_eq!(bytes[0] as u64, idl_event["discriminant"], "{} is out of variant order", idl_event["name"]);
+        let mut data = &bytes[1..];
+        read_fields(&idl, &idl_event["type"], &mut data);
+        assert!(data.is_empty(), "layout of {} leaves {} bytes unread", idl_event["name"], data.len());
+    }
+
+    let generated = serde_json::to_string_pretty(&idl).unwrap() + "\n";
+    if std::env::var_os("UPDATE_IDL").is_some() {
+        std::fs::create_dir_all(std::path::Path::new(IDL_PATH).parent().unwrap()).unwrap();
+        std::fs::write(IDL_PATH, generated).unwrap();
+        return;
+    }
+    let checked_in = std::fs::read_to_string(IDL_PATH).unwrap_or_default();
+    assert!(checked_in == generated, "{} is out of date, run `UPDATE_IDL=1 cargo test --test idl`", IDL_PATH);
+}
@@ -3,22 +3,66 @@ use collection::id;
 use collection::processor::process_instruction;
 use collection::instruction::{
     create_collection_account, 
+    create_collection_pda,
+    close_collection_account,
     CreateCollectionAccountArgs, 
     include_token,
+    include_token_by_creator,
+    submit_token,
+    replace_submission,
+    approve_submission,
+    reject_submission,
+    add_curator,
+    remove_curator,
+    with_curator,
+    with_curator_and_authority,
+    lock_collection,
+    migrate_collection_account,
+    light_up_stars_once,
     light_up_stars_hundred,
-    close_account,
+    light_up_stars_thousand,
+    light_up_stars,
+    close_index_account,
+    update_collection_account,
+    update_collection_account_with_symbol,
+    UpdateCollectionAccountArgs,
+    propose_authority,
+    accept_authority,
+    cancel_authority_transfer,
+    remove_token,
+    migrate_index_account,
+    initialize_config,
+    update_config,
+    set_paused,
+    withdraw,
+    ConfigArgs,
+    CollectionInstruction,
+    decode_instruction,
+    ParsedAccount,
+    CLEAR_ALL,
+    CLEAR_BANNER,
+    CLEAR_HEADER_IMAGE,
+    CLEAR_TAGS,
 };
-use collection::state::{CollectionAccountData, AccountType};
-use collection::utils::{get_index_account, get_treasury_account};
+use collection::error::CollectionError;
+use collection::event::{decode_events, AccountClosed, CollectionCreated, CollectionEvent, CollectionMigrated, StarsLit, TokenIncluded, TokenRemoved, Withdrawn};
+use collection::state::{try_from_slice_unchecked, DEFAULT_PRICE_PER_STAR, DEFAULT_STARS_HUNDRED_PRICE, StarReceiptAccountData, CollectionAccountData, CollectionIndexAccountData, CollectionPositionAccountData, ConfigAccountData, FieldLimits, AccountType, SymbolAccountData, SubmissionAccountData, CuratorAccountData, CURATOR_EDIT, CURATOR_INCLUDE, CURATOR_REMOVE, COLLECTION_ACCOUNT_VERSION};
+use collection::utils::{get_collection_account, get_config_account, get_star_receipt_account, get_index_account, get_legacy_index_account, get_position_account, get_treasury_account, get_symbol_account, get_submission_account, get_curator_account};
 use solana_sdk::{
     signature::{Keypair, Signer},
+    pubkey::Pubkey,
     transaction::Transaction,
-    borsh::try_from_slice_unchecked,
     native_token::sol_to_lamports,
+    system_instruction,
+    account::Account,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    transaction::TransactionError,
+    program_error::ProgramError,
 };
-mod helpers;
-use helpers::{create_mint, create_associated_account, mint_tokens};
+use collection_test_utils::{collection_processor, create_mint, create_associated_account, mint_tokens, create_collection, default_collection_args, create_nft, include_nft, add_program_data_account, add_token_metadata_program, create_metadata_accounts, sign_metadata, create_config, every_instruction};
+use collection::metadata::Creator;
 use spl_associated_token_account::get_associated_token_address;
+use borsh::{BorshDeserialize, BorshSerialize};
 
 #[tokio::test]
 async fn test_create_collection_account() {
@@ -37,6 +81,7 @@ async fn test_create_collection_account() {
         short_description: Some("www.solana.com".to_string()),
         banner: Some("www.solana.com".to_string()),
         tags: Some(vec!["art".to_string(), "music".to_string()]),
+        max_supply: None,
     };
     let ix = create_collection_account(program_id, collection_account, payer.pubkey(), args);
     let mut transaction = Transaction::new_with_payer(
@@ -52,11 +97,11 @@ async fn test_create_collection_account() {
         .expect("get_account")
         .expect("processor state account not found");
     let account_data: CollectionAccountData = try_from_slice_unchecked(&collection_account.data).unwrap();
-    assert_eq!(account_data.is_initialized(), true);
-    assert_eq!(account_data.title, "test collection".clone());
-    assert_eq!(account_data.symbol, "tc".clone());
-    assert_eq!(account_data.description, "test collection description".clone());
-    assert_eq!(account_data.icon_image, "https://www.google.com".clone());
+    assert!(account_data.is_initialized());
+    assert_eq!(account_data.title, "test collection");
+    assert_eq!(account_data.symbol, "tc");
+    assert_eq!(account_data.description, "test collection description");
+    assert_eq!(account_data.icon_image, "https://www.google.com");
     assert_eq!(account_data.short_description, Some("www.solana.com".to_string()));
     assert_eq!(account_data.short_description, Some("www.solana.com".to_string()));
     assert_eq!(account_data.banner, Some("www.solana.com".to_string()));
@@ -80,14 +125,10 @@ async fn test_include_token() {
         short_description: Some("www.solana.com".to_string()),
         banner: Some("www.solana.com".to_string()),
         tags: Some(vec!["art".to_string(), "music".to_string()]),
+        max_supply: None,
     };
     let ix = create_collection_account(program_id, collection_account, context.payer.pubkey(), args);
-    let mut transaction = Transaction::new_with_payer(
-        &[ix],
-        Some(&context.payer.pubkey()),
-    );
-    transaction.sign(&[&context.payer, &collection_keypair], context.last_blockhash);
-    context.banks_client.process_transaction(transaction).await.unwrap();
+    process_ok(&mut context, ix, &[&collection_keypair]).await;
 
     let mint_keypair = Keypair::new();
     let payer_pubkey = context.payer.pubkey();
@@ -104,8 +145,10 @@ async fn test_include_token() {
     mint_tokens(&mut context, &mint_keypair.pubkey(), &nft_ata, 1, &payer_pubkey, None).await.unwrap();
 
     let (index_account,_) = get_index_account(
+        &collection_keypair.pubkey(),
         &mint_keypair.pubkey(), 
     );
+    let (position_account, _) = get_position_account(&collection_keypair.pubkey(), 0);
     let ix = include_token(
         program_id,
         collection_keypair.pubkey(),
@@ -114,13 +157,9 @@ async fn test_include_token() {
         nft_ata,
         index_account,
         payer_pubkey,
+        position_account,
     );
-    let mut transaction = Transaction::new_with_payer(
-        &[ix],
-        Some(&context.payer.pubkey()),
-    );
-    transaction.sign(&[&context.payer], context.last_blockhash);
-    context.banks_client.process_transaction(transaction).await.unwrap();
+    process_ok(&mut context, ix, &[]).await;
 }
 
 #[tokio::test]
@@ -140,14 +179,10 @@ async fn test_light_up_stars_hundred() {
         short_description: Some("www.solana.com".to_string()),
         banner: Some("www.solana.com".to_string()),
         tags: Some(vec!["art".to_string(), "music".to_string()]),
+        max_supply: None,
     };
     let ix = create_collection_account(program_id, collection_account, context.payer.pubkey(), args);
-    let mut transaction = Transaction::new_with_payer(
-        &[ix],
-        Some(&context.payer.pubkey()),
-    );
-    transaction.sign(&[&context.payer, &collection_keypair], context.last_blockhash);
-    context.banks_client.process_transaction(transaction).await.unwrap();
+    process_ok(&mut context, ix, &[&collection_keypair]).await;
 
     // let treasury_account = get_treasury_account();
     let (treasury_account, _) = get_treasury_account();
@@ -161,12 +196,7 @@ async fn test_light_up_stars_hundred() {
         payer_pubkey,
         treasury_account,
     );
-    let mut transaction = Transaction::new_with_payer(
-        &[ix],
-        Some(&context.payer.pubkey()),
-    );
-    transaction.sign(&[&context.payer], context.last_blockhash);
-    context.banks_client.process_transaction(transaction).await.unwrap();
+    process_ok(&mut context, ix, &[]).await;
 
     let collection_account = context.banks_client
         .get_account(collection_account)
@@ -184,12 +214,7 @@ async fn test_light_up_stars_hundred() {
         payer_pubkey,
         treasury_account,
     );
-    let mut transaction = Transaction::new_with_payer(
-        &[ix],
-        Some(&context.payer.pubkey()),
-    );
-    transaction.sign(&[&context.payer], context.last_blockhash);
-    context.banks_client.process_transaction(transaction).await.unwrap();
+    process_ok(&mut context, ix, &[]).await;
 
 }
 
@@ -210,6 +235,7 @@ async fn test_close_account() {
         short_description: Some("www.solana.com".to_string()),
         banner: Some("www.solana.com".to_string()),
         tags: Some(vec!["art".to_string(), "music".to_string()]),
+        max_supply: None,
     };
     let ix = create_collection_account(program_id, collection_account, payer.pubkey(), args);
     let mut transaction = Transaction::new_with_payer(
@@ -225,14 +251,14 @@ async fn test_close_account() {
         .expect("get_account")
         .expect("processor state account not found");
     let account_data: CollectionAccountData = try_from_slice_unchecked(&collection_account.data).unwrap();
-    assert_eq!(account_data.is_initialized(), true);
+    assert!(account_data.is_initialized());
 
-    let ix = close_account(
+    let ix = close_collection_account(
         program_id, 
         collection_keypair.pubkey(), 
         payer.pubkey(), 
         payer.pubkey(),
-        AccountType::CollectionAccount,
+        "tc",
     );
     let mut transaction = Transaction::new_with_payer(
         &[ix],
@@ -240,4 +266,2666 @@ async fn test_close_account() {
     );
     transaction.sign(&[&payer], recent_blockhash);
     banks_client.process_transaction(transaction).await.unwrap();
-}
\ No newline at end of file
+}
+
+#[tokio::test]
+async fn test_update_collection_account() {
+    let program_id = id();
+    let program_test = ProgramTest::new("collection", program_id, processor!(process_instruction));
+    let mut context = program_test.start_with_context().await;
+
+    let collection_keypair = Keypair::new();
+    create_collection(&mut context, &collection_keypair, default_collection_args()).await.unwrap();
+    let rent = context.banks_client.get_rent().await.unwrap();
+
+    // grow the account
+    let args = UpdateCollectionAccountArgs {
+        title: Some("renamed collection".to_string()),
+        tags: Some(vec!["art".to_string(), "music".to_string(), "pixel".to_string()]),
+        max_supply: None,
+        ..UpdateCollectionAccountArgs::default()
+    };
+    let ix = update_collection_account(program_id, collection_keypair.pubkey(), context.payer.pubkey(), args);
+    process_ok(&mut context, ix, &[]).await;
+
+    let collection_account = context.banks_client
+        .get_account(collection_keypair.pubkey())
+        .await
+        .expect("get_account")
+        .expect("processor state account not found");
+    let account_data: CollectionAccountData = try_from_slice_unchecked(&collection_account.data).unwrap();
+    assert_eq!(account_data.title, "renamed collection".to_string());
+    assert_eq!(account_data.symbol, "tc".to_string());
+    assert_eq!(account_data.tags, Some(vec!["art".to_string(), "music".to_string(), "pixel".to_string()]));
+    assert_eq!(collection_account.data.len(), account_data.try_to_vec().unwrap().len());
+    assert_eq!(collection_account.lamports, rent.minimum_balance(collection_account.data.len()));
+
+    // shrink the account
+    let args = UpdateCollectionAccountArgs {
+        description: Some("short".to_string()),
+        ..UpdateCollectionAccountArgs::default()
+    };
+    let ix = update_collection_account(program_id, collection_keypair.pubkey(), context.payer.pubkey(), args);
+    process_ok(&mut context, ix, &[]).await;
+
+    let collection_account = context.banks_client
+        .get_account(collection_keypair.pubkey())
+        .await
+        .expect("get_account")
+        .expect("processor state account not found");
+    let account_data: CollectionAccountData = try_from_slice_unchecked(&collection_account.data).unwrap();
+    assert_eq!(account_data.description, "short".to_string());
+    assert_eq!(account_data.title, "renamed collection".to_string());
+    assert_eq!(collection_account.data.len(), account_data.try_to_vec().unwrap().len());
+    assert_eq!(collection_account.lamports, rent.minimum_balance(collection_account.data.len()));
+
+    // remove optional fields, the rest stays as it is
+    let args = UpdateCollectionAccountArgs {
+        clear: CLEAR_HEADER_IMAGE | CLEAR_BANNER | CLEAR_TAGS,
+        ..UpdateCollectionAccountArgs::default()
+    };
+    let ix = update_collection_account(program_id, collection_keypair.pubkey(), context.payer.pubkey(), args);
+    process_ok(&mut context, ix, &[]).await;
+
+    let collection_account = context.banks_client
+        .get_account(collection_keypair.pubkey())
+        .await
+        .expect("get_account")
+        .expect("processor state account not found");
+    let account_data: CollectionAccountData = try_from_slice_unchecked(&collection_account.data).unwrap();
+    assert_eq!(account_data.header_image, None);
+    assert_eq!(account_data.banner, None);
+    assert_eq!(account_data.tags, None);
+    assert_eq!(account_data.short_description, Some("www.solana.com".to_string()));
+    assert_eq!(account_data.description, "short".to_string());
+    assert_eq!(collection_account.data.len(), account_data.try_to_vec().unwrap().len());
+    assert_eq!(collection_account.lamports, rent.minimum_balance(collection_account.data.len()));
+}
+
+#[tokio::test]
+async fn test_update_collection_account_rejects_invalid_args() {
+    let program_id = id();
+    let program_test = ProgramTest::new("collection", program_id, processor!(process_instruction));
+    let mut context = program_test.start_with_context().await;
+
+    let collection_keypair = Keypair::new();
+    create_collection(&mut context, &collection_keypair, default_collection_args()).await.unwrap();
+
+    let args = UpdateCollectionAccountArgs {
+        symbol: Some("a symbol that is too long".to_string()),
+        ..UpdateCollectionAccountArgs::default()
+    };
+    let ix = update_collection_account_with_symbol(program_id, collection_keypair.pubkey(), context.payer.pubkey(), "tc", args);
+    assert_instruction_error(&mut context, ix, &[], CollectionError::InvalidInstructionArguments).await;
+
+    // a field is either set or cleared, and only optional fields can be cleared
+    for args in [
+        UpdateCollectionAccountArgs {
+            banner: Some("www.solana.com".to_string()),
+            clear: CLEAR_BANNER,
+            ..UpdateCollectionAccountArgs::default()
+        },
+        UpdateCollectionAccountArgs {
+            clear: CLEAR_ALL + 1,
+            ..UpdateCollectionAccountArgs::default()
+        },
+    ] {
+        let ix = update_collection_account(program_id, collection_keypair.pubkey(), context.payer.pubkey(), args);
+        assert_instruction_error(&mut context, ix, &[], CollectionError::InvalidInstructionArguments).await;
+    }
+
+    // only the collection authority may update
+    let stranger = Keypair::new();
+    let ix = update_collection_account(
+        program_id,
+        collection_keypair.pubkey(),
+        stranger.pubkey(),
+        UpdateCollectionAccountArgs {
+            title: Some("hijacked".to_string()),
+            ..UpdateCollectionAccountArgs::default()
+        },
+    );
+    assert_instruction_error(&mut context, ix, &[&stranger], CollectionError::NotCollectionAuthority).await;
+}
+
+#[tokio::test]
+async fn test_authority_transfer() {
+    let program_id = id();
+    let program_test = ProgramTest::new("collection", program_id, processor!(process_instruction));
+    let mut context = program_test.start_with_context().await;
+
+    let collection_keypair = Keypair::new();
+    create_collection(&mut context, &collection_keypair, default_collection_args()).await.unwrap();
+    let new_authority = Keypair::new();
+
+    let ix = propose_authority(program_id, collection_keypair.pubkey(), context.payer.pubkey(), new_authority.pubkey());
+    let fund_ix = system_instruction::transfer(&context.payer.pubkey(), &new_authority.pubkey(), sol_to_lamports(1.0));
+    let mut transaction = Transaction::new_with_payer(
+        &[ix, fund_ix],
+        Some(&context.payer.pubkey()),
+    );
+    transaction.sign(&[&context.payer], context.last_blockhash);
+    context.banks_client.process_transaction(transaction).await.unwrap();
+
+    let collection_account = context.banks_client
+        .get_account(collection_keypair.pubkey())
+        .await
+        .expect("get_account")
+        .expect("processor state account not found");
+    let account_data: CollectionAccountData = try_from_slice_unchecked(&collection_account.data).unwrap();
+    assert_eq!(account_data.authority, context.payer.pubkey());
+    assert_eq!(account_data.pending_authority, Some(new_authority.pubkey()));
+
+    // a key other than the pending authority can not accept
+    let stranger = Keypair::new();
+    let ix = accept_authority(program_id, collection_keypair.pubkey(), stranger.pubkey());
+    assert_instruction_error(&mut context, ix, &[&stranger], CollectionError::NotPendingAuthority).await;
+
+    let ix = accept_authority(program_id, collection_keypair.pubkey(), new_authority.pubkey());
+    process_ok(&mut context, ix, &[&new_authority]).await;
+
+    let collection_account = context.banks_client
+        .get_account(collection_keypair.pubkey())
+        .await
+        .expect("get_account")
+        .expect("processor state account not found");
+    let account_data: CollectionAccountData = try_from_slice_unchecked(&collection_account.data).unwrap();
+    assert_eq!(account_data.authority, new_authority.pubkey());
+    assert_eq!(account_data.pending_authority, None);
+
+    // the previous authority lost its rights
+    let ix = update_collection_account(
+        program_id,
+        collection_keypair.pubkey(),
+        context.payer.pubkey(),
+        UpdateCollectionAccountArgs {
+            title: Some("renamed collection".to_string()),
+            ..UpdateCollectionAccountArgs::default()
+        },
+    );
+    assert_instruction_error(&mut context, ix, &[], CollectionError::NotCollectionAuthority).await;
+}
+
+#[tokio::test]
+async fn test_cancel_authority_transfer() {
+    let program_id = id();
+    let program_test = ProgramTest::new("collection", program_id, processor!(process_instruction));
+    let mut context = program_test.start_with_context().await;
+
+    let collection_keypair = Keypair::new();
+    create_collection(&mut context, &collection_keypair, default_collection_args()).await.unwrap();
+    let new_authority = Keypair::new();
+
+    let ix = propose_authority(program_id, collection_keypair.pubkey(), context.payer.pubkey(), new_authority.pubkey());
+    let cancel_ix = cancel_authority_transfer(program_id, collection_keypair.pubkey(), context.payer.pubkey());
+    let mut transaction = Transaction::new_with_payer(
+        &[ix, cancel_ix],
+        Some(&context.payer.pubkey()),
+    );
+    transaction.sign(&[&context.payer], context.last_blockhash);
+    context.banks_client.process_transaction(transaction).await.unwrap();
+
+    let ix = accept_authority(program_id, collection_keypair.pubkey(), new_authority.pubkey());
+    assert_instruction_error(&mut context, ix, &[&new_authority], CollectionError::NoPendingAuthority).await;
+
+    let collection_account = context.banks_client
+        .get_account(collection_keypair.pubkey())
+        .await
+        .expect("get_account")
+        .expect("processor state account not found");
+    let account_data = CollectionAccountData::try_from_slice_unchecked(&collection_account.data).unwrap();
+    assert_eq!(account_data.authority, context.payer.pubkey());
+    assert_eq!(account_data.pending_authority, None);
+}
+
+#[tokio::test]
+async fn test_remove_token() {
+    let program_id = id();
+    let program_test = ProgramTest::new("collection", program_id, processor!(process_instruction));
+    let mut context = program_test.start_with_context().await;
+
+    let collection_keypair = Keypair::new();
+    let collection = collection_keypair.pubkey();
+    create_collection(&mut context, &collection_keypair, default_collection_args()).await.unwrap();
+    let payer_pubkey = context.payer.pubkey();
+
+    let mut mints = vec![];
+    for position in 0..3 {
+        let (mint_keypair, nft_ata) = create_nft(&mut context).await.unwrap();
+        include_nft(&mut context, &collection, &mint_keypair.pubkey(), &nft_ata, position).await.unwrap();
+        mints.push(mint_keypair.pubkey());
+    }
+
+    // only the collection authority may remove
+    let stranger = Keypair::new();
+    let (index_account, _) = get_index_account(&collection, &mints[0]);
+    let (position_account, _) = get_position_account(&collection, 0);
+    let (last_position_account, _) = get_position_account(&collection, 2);
+    let (last_index_account, _) = get_index_account(&collection, &mints[2]);
+    let ix = remove_token(
+        program_id,
+        collection,
+        stranger.pubkey(),
+        index_account,
+        stranger.pubkey(),
+        position_account,
+        last_position_account,
+        last_index_account,
+    );
+    assert_instruction_error(&mut context, ix, &[&stranger], CollectionError::NotCollectionAuthority).await;
+
+    // removing the first token moves the last one into position 0
+    let ix = remove_token(
+        program_id,
+        collection,
+        payer_pubkey,
+        index_account,
+        payer_pubkey,
+        position_account,
+        last_position_account,
+        last_index_account,
+    );
+    process_ok(&mut context, ix, &[]).await;
+
+    let collection_account = context.banks_client
+        .get_account(collection)
+        .await
+        .expect("get_account")
+        .expect("processor state account not found");
+    let account_data: CollectionAccountData = try_from_slice_unchecked(&collection_account.data).unwrap();
+    assert_eq!(account_data.supply, 2);
+    let index = context.banks_client.get_account(index_account).await.expect("get_account");
+    assert!(index.is_none());
+    let last_position = context.banks_client.get_account(last_position_account).await.expect("get_account");
+    assert!(last_position.is_none());
+
+    let position = context.banks_client
+        .get_account(position_account)
+        .await
+        .expect("get_account")
+        .expect("position account not found");
+    let position_data: CollectionPositionAccountData = try_from_slice_unchecked(&position.data).unwrap();
+    assert_eq!(position_data.mint, mints[2]);
+    assert_eq!(position_data.index, 0);
+    let moved_index = context.banks_client
+        .get_account(last_index_account)
+        .await
+        .expect("get_account")
+        .expect("index account not found");
+    let moved_index_data: CollectionIndexAccountData = try_from_slice_unchecked(&moved_index.data).unwrap();
+    assert_eq!(moved_index_data.index, 0);
+
+    // removing the last token only closes its accounts
+    let (index_account, _) = get_index_account(&collection, &mints[1]);
+    let (position_account, _) = get_position_account(&collection, 1);
+    let ix = remove_token(
+        program_id,
+        collection,
+        payer_pubkey,
+        index_account,
+        payer_pubkey,
+        position_account,
+        position_account,
+        index_account,
+    );
+    process_ok(&mut context, ix, &[]).await;
+
+    let collection_account = context.banks_client
+        .get_account(collection)
+        .await
+        .expect("get_account")
+        .expect("processor state account not found");
+    let account_data: CollectionAccountData = try_from_slice_unchecked(&collection_account.data).unwrap();
+    assert_eq!(account_data.supply, 1);
+    let position = context.banks_client.get_account(position_account).await.expect("get_account");
+    assert!(position.is_none());
+
+    // the next include reuses the freed position
+    let (mint_keypair, nft_ata) = create_nft(&mut context).await.unwrap();
+    include_nft(&mut context, &collection, &mint_keypair.pubkey(), &nft_ata, 1).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_include_token_in_many_collections() {
+    let program_id = id();
+    let program_test = ProgramTest::new("collection", program_id, processor!(process_instruction));
+    let mut context = program_test.start_with_context().await;
+
+    let (mint_keypair, nft_ata) = create_nft(&mut context).await.unwrap();
+
+    for symbol in ["tc", "tc2"] {
+        let collection_keypair = Keypair::new();
+        let args = CreateCollectionAccountArgs {
+            symbol: symbol.to_string(),
+            ..default_collection_args()
+        };
+        create_collection(&mut context, &collection_keypair, args).await.unwrap();
+
+        include_nft(&mut context, &collection_keypair.pubkey(), &mint_keypair.pubkey(), &nft_ata, 0).await.unwrap();
+
+        let (index_account, _) = get_index_account(&collection_keypair.pubkey(), &mint_keypair.pubkey());
+
+        let index = context.banks_client
+            .get_account(index_account)
+            .await
+            .expect("get_account")
+            .expect("index account not found");
+        let index_data: CollectionIndexAccountData = try_from_slice_unchecked(&index.data).unwrap();
+        assert_eq!(index_data.collection, collection_keypair.pubkey());
+        assert_eq!(index_data.mint, mint_keypair.pubkey());
+    }
+}
+
+#[tokio::test]
+async fn test_migrate_index_account() {
+    let program_id = id();
+    let program_test = ProgramTest::new("collection", program_id, processor!(process_instruction));
+    let mut context = program_test.start_with_context().await;
+    let payer_pubkey = context.payer.pubkey();
+
+    // a collection written before versioning, closing never decremented the
+    // supply so the indexes of its legacy members have gaps
+    let collection = Keypair::new().pubkey();
+    let legacy_data = (
+        AccountType::CollectionAccount,
+        payer_pubkey,
+        "legacy collection".to_string(),
+        "lc".to_string(),
+        "legacy collection description".to_string(),
+        "https://www.google.com".to_string(),
+        5u64,
+        0u64,
+        None::<String>,
+        None::<String>,
+        None::<String>,
+        None::<Vec<String>>,
+    ).try_to_vec().unwrap();
+    let legacy_account = Account {
+        lamports: solana_sdk::rent::Rent::default().minimum_balance(legacy_data.len()),
+        data: legacy_data,
+        owner: program_id,
+        executable: false,
+        rent_epoch: 0,
+    };
+    context.set_account(&collection, &legacy_account.into());
+
+    let (reincluded_mint, reincluded_ata) = create_nft(&mut context).await.unwrap();
+    let mints = [Pubkey::new_unique(), Pubkey::new_unique(), reincluded_mint.pubkey()];
+    for (mint, legacy_index) in mints.iter().zip(&[1u64, 4, 2]) {
+        let (legacy_index_account, _) = get_legacy_index_account(mint);
+        let legacy_index_data = CollectionIndexAccountData::new(collection, *mint, *legacy_index);
+        let account = Account {
+            lamports: sol_to_lamports(0.01),
+            data: legacy_index_data.try_to_vec().unwrap(),
+            owner: program_id,
+            ..Account::default()
+        };
+        context.set_account(&legacy_index_account, &account.into());
+    }
+
+    // migrated tokens are appended, whatever their legacy index
+    for (position, mint) in mints[..2].iter().enumerate() {
+        let (legacy_index_account, _) = get_legacy_index_account(mint);
+        let (index_account, _) = get_index_account(&collection, mint);
+        let (position_account, _) = get_position_account(&collection, position as u64);
+        let ix = migrate_index_account(
+            program_id,
+            collection,
+            payer_pubkey,
+            legacy_index_account,
+            index_account,
+            payer_pubkey,
+            position_account,
+        );
+        process_ok(&mut context, ix, &[]).await;
+
+        let legacy = context.banks_client.get_account(legacy_index_account).await.expect("get_account");
+        assert!(legacy.is_none());
+        let index = context.banks_client
+            .get_account(index_account)
+            .await
+            .expect("get_account")
+            .expect("index account not found");
+        let index_data: CollectionIndexAccountData = try_from_slice_unchecked(&index.data).unwrap();
+        assert_eq!(index_data.collection, collection);
+        assert_eq!(index_data.mint, *mint);
+        assert_eq!(index_data.index, position as u64);
+        let position = context.banks_client
+            .get_account(position_account)
+            .await
+            .expect("get_account")
+            .expect("position account not found");
+        let position_data: CollectionPositionAccountData = try_from_slice_unchecked(&position.data).unwrap();
+        assert_eq!(position_data.mint, *mint);
+    }
+    let collection_account = context.banks_client.get_account(collection).await.unwrap().unwrap();
+    let collection_data: CollectionAccountData = try_from_slice_unchecked(&collection_account.data).unwrap();
+    assert_eq!(collection_data.version, COLLECTION_ACCOUNT_VERSION);
+    assert_eq!(collection_data.supply, 2);
+
+    // the positions are compact, so the migrated tokens can be removed again
+    let (index_account, _) = get_index_account(&collection, &mints[0]);
+    let (position_account, _) = get_position_account(&collection, 0);
+    let (last_position_account, _) = get_position_account(&collection, 1);
+    let (last_index_account, _) = get_index_account(&collection, &mints[1]);
+    let ix = remove_token(
+        program_id,
+        collection,
+        payer_pubkey,
+        index_account,
+        payer_pubkey,
+        position_account,
+        last_position_account,
+        last_index_account,
+    );
+    process_ok(&mut context, ix, &[]).await;
+    let index = context.banks_client.get_account(last_index_account).await.unwrap().unwrap();
+    let index_data: CollectionIndexAccountData = try_from_slice_unchecked(&index.data).unwrap();
+    assert_eq!(index_data.index, 0);
+
+    // a mint included again before its legacy account was migrated only has the
+    // stale legacy account closed
+    include_nft(&mut context, &collection, &reincluded_mint.pubkey(), &reincluded_ata, 1).await.unwrap();
+    let (legacy_index_account, _) = get_legacy_index_account(&reincluded_mint.pubkey());
+    let (index_account, _) = get_index_account(&collection, &reincluded_mint.pubkey());
+    let (position_account, _) = get_position_account(&collection, 2);
+    let index_before = context.banks_client.get_account(index_account).await.unwrap().unwrap();
+    // someone else pays, the rent of the legacy account still goes to the authority
+    let funder = Keypair::new();
+    let funder_account = Account {
+        lamports: sol_to_lamports(1.0),
+        ..Account::default()
+    };
+    context.set_account(&funder.pubkey(), &funder_account.into());
+    let authority_balance = context.banks_client.get_balance(payer_pubkey).await.unwrap();
+    let ix = migrate_index_account(
+        program_id,
+        collection,
+        payer_pubkey,
+        legacy_index_account,
+        index_account,
+        funder.pubkey(),
+        position_account,
+    );
+    let mut transaction = Transaction::new_with_payer(
+        &[ix],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder, &context.payer], context.last_blockhash);
+    context.banks_client.process_transaction(transaction).await.unwrap();
+
+    let legacy = context.banks_client.get_account(legacy_index_account).await.expect("get_account");
+    assert!(legacy.is_none());
+    let authority_balance_after = context.banks_client.get_balance(payer_pubkey).await.unwrap();
+    assert_eq!(authority_balance_after, authority_balance + sol_to_lamports(0.01));
+    let index_after = context.banks_client.get_account(index_account).await.unwrap().unwrap();
+    assert_eq!(index_after.data, index_before.data);
+    let position = context.banks_client.get_account(position_account).await.expect("get_account");
+    assert!(position.is_none());
+    let collection_account = context.banks_client.get_account(collection).await.unwrap().unwrap();
+    let collection_data: CollectionAccountData = try_from_slice_unchecked(&collection_account.data).unwrap();
+    assert_eq!(collection_data.supply, 2);
+}
+
+#[tokio::test]
+async fn test_migrate_index_account_checks() {
+    let program_id = id();
+    let program_test = ProgramTest::new("collection", program_id, processor!(process_instruction));
+    let mut context = program_test.start_with_context().await;
+    let payer_pubkey = context.payer.pubkey();
+
+    let collection = Keypair::new().pubkey();
+    let mint = Pubkey::new_unique();
+    let (legacy_index_account, _) = get_legacy_index_account(&mint);
+    let legacy_index_data = CollectionIndexAccountData::new(collection, mint, 3);
+    let account = Account {
+        lamports: sol_to_lamports(0.01),
+        data: legacy_index_data.try_to_vec().unwrap(),
+        owner: program_id,
+        ..Account::default()
+    };
+    context.set_account(&legacy_index_account, &account.into());
+    let (index_account, _) = get_index_account(&collection, &mint);
+    let (position_account, _) = get_position_account(&collection, 0);
+    let ix = migrate_index_account(
+        program_id,
+        collection,
+        payer_pubkey,
+        legacy_index_account,
+        index_account,
+        payer_pubkey,
+        position_account,
+    );
+
+    // migrating a member is checked like including one
+    let collection_data = CollectionAccountData {
+        account_type: AccountType::VersionedCollectionAccount,
+        version: COLLECTION_ACCOUNT_VERSION,
+        authority: payer_pubkey,
+        title: "checked collection".to_string(),
+        symbol: "cc".to_string(),
+        description: "checked collection description".to_string(),
+        icon_image: "https://www.google.com".to_string(),
+        supply: 0,
+        stars: 0,
+        header_image: None,
+        short_description: None,
+        banner: None,
+        tags: None,
+        pending_authority: None,
+        max_supply: Some(0),
+        metadata_locked: false,
+        membership_locked: false,
+    };
+    let locked_data = CollectionAccountData {
+        max_supply: None,
+        membership_locked: true,
+        ..collection_data.clone()
+    };
+    for (data, error) in [
+        (collection_data, CollectionError::CollectionFull),
+        (locked_data, CollectionError::CollectionImmutable),
+    ] {
+        let data = data.try_to_vec().unwrap();
+        let account = Account {
+            lamports: solana_sdk::rent::Rent::default().minimum_balance(data.len()),
+            data,
+            owner: program_id,
+            ..Account::default()
+        };
+        context.set_account(&collection, &account.into());
+        context.get_new_latest_blockhash().await.unwrap();
+        assert_instruction_error(&mut context, ix.clone(), &[], error).await;
+    }
+    let legacy = context.banks_client.get_account(legacy_index_account).await.unwrap();
+    assert!(legacy.is_some());
+}
+
+#[tokio::test]
+async fn test_initialize_config() {
+    let program_id = id();
+    let upgrade_authority = Keypair::new();
+    let mut program_test = ProgramTest::new("collection", program_id, processor!(process_instruction));
+    add_program_data_account(&mut program_test, &upgrade_authority.pubkey());
+    let mut context = program_test.start_with_context().await;
+    let payer_pubkey = context.payer.pubkey();
+
+    let treasury_manager = Keypair::new();
+    let args = ConfigArgs {
+        admin: payer_pubkey,
+        treasury_manager: treasury_manager.pubkey(),
+        stars_hundred_price: 5_000_000,
+        stars_thousand_price: 40_000_000,
+        field_limits: FieldLimits {
+            max_title_length: 8,
+            ..FieldLimits::default()
+        },
+        price_per_star: 50_000,
+    };
+
+    // only the upgrade authority can initialize the config
+    let impostor = Keypair::new();
+    let ix = initialize_config(program_id, impostor.pubkey(), payer_pubkey, args.clone());
+    assert_instruction_error(&mut context, ix, &[&impostor], CollectionError::NotUpgradeAuthority).await;
+
+    let ix = initialize_config(program_id, upgrade_authority.pubkey(), payer_pubkey, args.clone());
+    process_ok(&mut context, ix, &[&upgrade_authority]).await;
+
+    let (config_account, _) = get_config_account();
+    let config = context.banks_client
+        .get_account(config_account)
+        .await
+        .expect("get_account")
+        .expect("config account not found");
+    let config_data: ConfigAccountData = try_from_slice_unchecked(&config.data).unwrap();
+    assert_eq!(config_data.account_type, AccountType::ConfigAccount);
+    assert_eq!(config_data.treasury_manager, treasury_manager.pubkey());
+    assert_eq!(config_data.field_limits.max_title_length, 8);
+
+    // the title limit now comes from the config
+    let collection_keypair = Keypair::new();
+    let err = create_collection(&mut context, &collection_keypair, default_collection_args()).await.unwrap_err();
+    assert_eq!(
+        err.unwrap(),
+        TransactionError::InstructionError(0, InstructionError::Custom(CollectionError::InvalidInstructionArguments as u32)),
+    );
+    let args = CreateCollectionAccountArgs {
+        title: "short".to_string(),
+        ..default_collection_args()
+    };
+    create_collection(&mut context, &collection_keypair, args).await.unwrap();
+
+    let (treasury_account, _) = get_treasury_account();
+    let ix = light_up_stars_hundred(
+        program_id,
+        collection_keypair.pubkey(),
+        payer_pubkey,
+        treasury_account,
+    );
+    process_ok(&mut context, ix, &[]).await;
+    let balance = context.banks_client.get_balance(treasury_account).await.expect("get_balance");
+    assert_eq!(balance, 5_000_000);
+}
+
+#[tokio::test]
+async fn test_update_config() {
+    let program_id = id();
+    let upgrade_authority = Keypair::new();
+    let mut program_test = ProgramTest::new("collection", program_id, processor!(process_instruction));
+    add_program_data_account(&mut program_test, &upgrade_authority.pubkey());
+    let mut context = program_test.start_with_context().await;
+    let payer_pubkey = context.payer.pubkey();
+
+    let args = ConfigArgs {
+        admin: payer_pubkey,
+        treasury_manager: payer_pubkey,
+        stars_hundred_price: 5_000_000,
+        stars_thousand_price: 40_000_000,
+        field_limits: FieldLimits::default(),
+        price_per_star: 50_000,
+    };
+    let ix = initialize_config(program_id, upgrade_authority.pubkey(), payer_pubkey, args.clone());
+    process_ok(&mut context, ix, &[&upgrade_authority]).await;
+
+    // only the admin can update the config
+    let impostor = Keypair::new();
+    let ix = update_config(program_id, impostor.pubkey(), ConfigArgs { admin: impostor.pubkey(), ..args.clone() });
+    assert_instruction_error(&mut context, ix, &[&impostor], CollectionError::NotConfigAdmin).await;
+
+    let new_admin = Keypair::new();
+    let ix = update_config(program_id, payer_pubkey, ConfigArgs {
+        admin: new_admin.pubkey(),
+        stars_hundred_price: 7_000_000,
+        ..args
+    });
+    process_ok(&mut context, ix, &[]).await;
+
+    let (config_account, _) = get_config_account();
+    let config = context.banks_client
+        .get_account(config_account)
+        .await
+        .expect("get_account")
+        .expect("config account not found");
+    let config_data: ConfigAccountData = try_from_slice_unchecked(&config.data).unwrap();
+    assert_eq!(config_data.admin, new_admin.pubkey());
+    assert_eq!(config_data.stars_hundred_price, 7_000_000);
+}
+
+#[tokio::test]
+async fn test_light_up_stars() {
+    let program_id = id();
+    let program_test = ProgramTest::new("collection", program_id, processor!(process_instruction));
+    let mut context = program_test.start_with_context().await;
+    let payer_pubkey = context.payer.pubkey();
+
+    let collection_keypair = Keypair::new();
+    create_collection(&mut context, &collection_keypair, default_collection_args()).await.unwrap();
+    let (treasury_account, _) = get_treasury_account();
+
+    let ix = light_up_stars(
+        program_id,
+        collection_keypair.pubkey(),
+        payer_pubkey,
+        treasury_account,
+        250,
+    );
+    process_ok(&mut context, ix, &[]).await;
+
+    let collection_account = context.banks_client
+        .get_account(collection_keypair.pubkey())
+        .await
+        .expect("get_account")
+        .expect("collection account not found");
+    let account_data: CollectionAccountData = try_from_slice_unchecked(&collection_account.data).unwrap();
+    assert_eq!(account_data.stars, 250);
+    let balance = context.banks_client.get_balance(treasury_account).await.expect("get_balance");
+    assert_eq!(balance, 250 * DEFAULT_PRICE_PER_STAR);
+
+    // the supporter receipt accumulates every purchase
+    let ix = light_up_stars_hundred(
+        program_id,
+        collection_keypair.pubkey(),
+        payer_pubkey,
+        treasury_account,
+    );
+    process_ok(&mut context, ix, &[]).await;
+
+    let (receipt_account, _) = get_star_receipt_account(&collection_keypair.pubkey(), &payer_pubkey);
+    let receipt = context.banks_client
+        .get_account(receipt_account)
+        .await
+        .expect("get_account")
+        .expect("receipt account not found");
+    let receipt_data: StarReceiptAccountData = try_from_slice_unchecked(&receipt.data).unwrap();
+    assert_eq!(receipt_data.account_type, AccountType::StarReceiptAccount);
+    assert_eq!(receipt_data.collection, collection_keypair.pubkey());
+    assert_eq!(receipt_data.supporter, payer_pubkey);
+    assert_eq!(receipt_data.stars, 350);
+    assert_eq!(receipt_data.lamports, 250 * DEFAULT_PRICE_PER_STAR + DEFAULT_STARS_HUNDRED_PRICE);
+    assert!(receipt_data.last_slot > 0);
+
+    // zero stars and overflowing prices are rejected
+    for (amount, error) in [
+        (0, CollectionError::InvalidInstructionArguments),
+        (u64::MAX, CollectionError::NumericalOverflow),
+    ] {
+        let ix = light_up_stars(
+            program_id,
+            collection_keypair.pubkey(),
+            payer_pubkey,
+            treasury_account,
+            amount,
+        );
+        assert_instruction_error(&mut context, ix, &[], error).await;
+    }
+}
+
+#[tokio::test]
+async fn test_pause_program() {
+    let program_id = id();
+    let upgrade_authority = Keypair::new();
+    let mut program_test = ProgramTest::new("collection", program_id, processor!(process_instruction));
+    add_program_data_account(&mut program_test, &upgrade_authority.pubkey());
+    let mut context = program_test.start_with_context().await;
+    let payer_pubkey = context.payer.pubkey();
+
+    let args = ConfigArgs {
+        admin: payer_pubkey,
+        treasury_manager: payer_pubkey,
+        stars_hundred_price: 5_000_000,
+        stars_thousand_price: 40_000_000,
+        field_limits: FieldLimits::default(),
+        price_per_star: 50_000,
+    };
+    create_config(&mut context, &upgrade_authority, args).await.unwrap();
+    let collection_keypair = Keypair::new();
+    create_collection(&mut context, &collection_keypair, default_collection_args()).await.unwrap();
+    let (mint, token_account) = create_nft(&mut context).await.unwrap();
+
+    // only the admin can pause
+    let impostor = Keypair::new();
+    let ix = set_paused(program_id, impostor.pubkey(), true, true);
+    assert_instruction_error(&mut context, ix, &[&impostor], CollectionError::NotConfigAdmin).await;
+
+    let ix = set_paused(program_id, payer_pubkey, true, true);
+    process_ok(&mut context, ix, &[]).await;
+
+    let paused = TransactionError::InstructionError(0, InstructionError::Custom(CollectionError::ProgramPaused as u32));
+    let err = include_nft(&mut context, &collection_keypair.pubkey(), &mint.pubkey(), &token_account, 0).await.unwrap_err();
+    assert_eq!(err.unwrap(), paused);
+
+    // so are the other instructions adding members
+    let collection = collection_keypair.pubkey();
+    let (index_account, _) = get_index_account(&collection, &mint.pubkey());
+    let (position_account, _) = get_position_account(&collection, 0);
+    let by_creator_ix = include_token_by_creator(
+        program_id,
+        collection,
+        payer_pubkey,
+        mint.pubkey(),
+        index_account,
+        payer_pubkey,
+        position_account,
+    );
+    let submit_ix = submit_token(program_id, collection, payer_pubkey, mint.pubkey(), token_account);
+    let approve_ix = approve_submission(
+        program_id,
+        collection,
+        payer_pubkey,
+        mint.pubkey(),
+        payer_pubkey,
+        token_account,
+        index_account,
+        payer_pubkey,
+        position_account,
+    );
+    let (legacy_index_account, _) = get_legacy_index_account(&mint.pubkey());
+    let migrate_ix = migrate_index_account(
+        program_id,
+        collection,
+        payer_pubkey,
+        legacy_index_account,
+        index_account,
+        payer_pubkey,
+        position_account,
+    );
+    for ix in [by_creator_ix, submit_ix, approve_ix, migrate_ix] {
+        let mut transaction = Transaction::new_with_payer(
+            &[ix],
+            Some(&payer_pubkey),
+        );
+        transaction.sign(&[&context.payer], context.last_blockhash);
+        let err = context.banks_client.process_transaction(transaction).await.unwrap_err();
+        assert_eq!(err.unwrap(), paused);
+    }
+
+    let (treasury_account, _) = get_treasury_account();
+    let hundred_ix = light_up_stars_hundred(
+        program_id,
+        collection_keypair.pubkey(),
+        payer_pubkey,
+        treasury_account,
+    );
+    let mut thousand_ix = hundred_ix.clone();
+    thousand_ix.data = CollectionInstruction::LightUpStarsThousand.try_to_vec().unwrap();
+    let amount_ix = light_up_stars(
+        program_id,
+        collection_keypair.pubkey(),
+        payer_pubkey,
+        treasury_account,
+        10,
+    );
+    for ix in [hundred_ix, thousand_ix, amount_ix] {
+        let mut transaction = Transaction::new_with_payer(
+            &[ix],
+            Some(&payer_pubkey),
+        );
+        transaction.sign(&[&context.payer], context.last_blockhash);
+        let err = context.banks_client.process_transaction(transaction).await.unwrap_err();
+        assert_eq!(err.unwrap(), paused);
+    }
+
+    // resuming membership leaves payments paused
+    let ix = set_paused(program_id, payer_pubkey, false, true);
+    process_ok(&mut context, ix, &[]).await;
+    // a fresh blockhash so the retried include is not deduplicated
+    context.get_new_latest_blockhash().await.unwrap();
+    include_nft(&mut context, &collection_keypair.pubkey(), &mint.pubkey(), &token_account, 0).await.unwrap();
+
+    let (config_account, _) = get_config_account();
+    let config = context.banks_client
+        .get_account(config_account)
+        .await
+        .expect("get_account")
+        .expect("config account not found");
+    let config_data: ConfigAccountData = try_from_slice_unchecked(&config.data).unwrap();
+    assert!(!config_data.membership_paused);
+    assert!(config_data.payments_paused);
+}
+
+#[tokio::test]
+async fn test_withdraw() {
+    let program_id = id();
+    let upgrade_authority = Keypair::new();
+    let mut program_test = ProgramTest::new("collection", program_id, processor!(process_instruction));
+    add_program_data_account(&mut program_test, &upgrade_authority.pubkey());
+    let mut context = program_test.start_with_context().await;
+    let payer_pubkey = context.payer.pubkey();
+
+    let treasury_manager = Keypair::new();
+    let args = ConfigArgs {
+        admin: payer_pubkey,
+        treasury_manager: treasury_manager.pubkey(),
+        stars_hundred_price: 5_000_000,
+        stars_thousand_price: 40_000_000,
+        field_limits: FieldLimits::default(),
+        price_per_star: 50_000,
+    };
+    create_config(&mut context, &upgrade_authority, args).await.unwrap();
+    let collection_keypair = Keypair::new();
+    create_collection(&mut context, &collection_keypair, default_collection_args()).await.unwrap();
+
+    let (treasury_account, _) = get_treasury_account();
+    let ix = light_up_stars_hundred(
+        program_id,
+        collection_keypair.pubkey(),
+        payer_pubkey,
+        treasury_account,
+    );
+    process_ok(&mut context, ix, &[]).await;
+
+    let recipient = Keypair::new().pubkey();
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let rent_exempt_minimum = rent.minimum_balance(0);
+    let spendable = 5_000_000 - rent_exempt_minimum;
+
+    // more than the spendable balance is rejected
+    let insufficient = TransactionError::InstructionError(0, InstructionError::Custom(CollectionError::InsufficientFunds as u32));
+    let ix = withdraw(program_id, treasury_manager.pubkey(), treasury_account, recipient, Some(spendable + 1));
+    let mut transaction = Transaction::new_with_payer(
+        &[ix],
+        Some(&payer_pubkey),
+    );
+    transaction.sign(&[&context.payer, &treasury_manager], context.last_blockhash);
+    let err = context.banks_client.process_transaction(transaction).await.unwrap_err();
+    assert_eq!(err.unwrap(), insufficient);
+
+    // a partial withdrawal
+    let ix = withdraw(program_id, treasury_manager.pubkey(), treasury_account, recipient, Some(rent_exempt_minimum));
+    process_ok(&mut context, ix, &[&treasury_manager]).await;
+    let balance = context.banks_client.get_balance(recipient).await.expect("get_balance");
+    assert_eq!(balance, rent_exempt_minimum);
+
+    // `None` withdraws the rest and leaves the rent exempt minimum
+    let ix = withdraw(program_id, treasury_manager.pubkey(), treasury_account, recipient, None);
+    process_ok(&mut context, ix, &[&treasury_manager]).await;
+    let balance = context.banks_client.get_balance(recipient).await.expect("get_balance");
+    assert_eq!(balance, spendable);
+    let balance = context.banks_client.get_balance(treasury_account).await.expect("get_balance");
+    assert_eq!(balance, rent_exempt_minimum);
+
+    // nothing left to withdraw
+    context.get_new_latest_blockhash().await.unwrap();
+    let ix = withdraw(program_id, treasury_manager.pubkey(), treasury_account, recipient, None);
+    let mut transaction = Transaction::new_with_payer(
+        &[ix],
+        Some(&payer_pubkey),
+    );
+    transaction.sign(&[&context.payer, &treasury_manager], context.last_blockhash);
+    let err = context.banks_client.process_transaction(transaction).await.unwrap_err();
+    assert_eq!(err.unwrap(), insufficient);
+}
+
+#[tokio::test]
+async fn test_close_index_account() {
+    let program_id = id();
+    let program_test = ProgramTest::new("collection", program_id, processor!(process_instruction));
+    let mut context = program_test.start_with_context().await;
+    let payer_pubkey = context.payer.pubkey();
+
+    let collection_keypair = Keypair::new();
+    let collection = collection_keypair.pubkey();
+    create_collection(&mut context, &collection_keypair, default_collection_args()).await.unwrap();
+    let other_collection_keypair = Keypair::new();
+    let args = CreateCollectionAccountArgs {
+        symbol: "oc".to_string(),
+        ..default_collection_args()
+    };
+    create_collection(&mut context, &other_collection_keypair, args).await.unwrap();
+
+    let mut mints = vec![];
+    let mut token_accounts = vec![];
+    for position in 0..2 {
+        let (mint_keypair, nft_ata) = create_nft(&mut context).await.unwrap();
+        include_nft(&mut context, &collection, &mint_keypair.pubkey(), &nft_ata, position).await.unwrap();
+        mints.push(mint_keypair.pubkey());
+        token_accounts.push(nft_ata);
+    }
+
+    // hand the first token over to a holder
+    let holder = Keypair::new();
+    create_associated_account(&mut context, &holder.pubkey(), &mints[0]).await.unwrap();
+    let holder_token_account = get_associated_token_address(&holder.pubkey(), &mints[0]);
+    let ix = spl_token::instruction::transfer(
+        &spl_token::id(),
+        &token_accounts[0],
+        &holder_token_account,
+        &payer_pubkey,
+        &[],
+        1,
+    ).unwrap();
+    process_ok(&mut context, ix, &[]).await;
+
+    let (index_account, _) = get_index_account(&collection, &mints[0]);
+    let (position_account, _) = get_position_account(&collection, 0);
+    let (last_position_account, _) = get_position_account(&collection, 1);
+    let (last_index_account, _) = get_index_account(&collection, &mints[1]);
+
+    // the collection can not be closed while it has members
+    let close_collection_ix = close_collection_account(
+        program_id,
+        collection,
+        payer_pubkey,
+        payer_pubkey,
+        "tc",
+    );
+    assert_instruction_error(&mut context, close_collection_ix.clone(), &[], CollectionError::CollectionNotEmpty).await;
+
+    // neither the collection authority nor the holder
+    let stranger = Keypair::new();
+    let ix = close_index_account(
+        program_id,
+        index_account,
+        stranger.pubkey(),
+        stranger.pubkey(),
+        collection,
+        position_account,
+        last_position_account,
+        last_index_account,
+        Some(holder_token_account),
+    );
+    assert_instruction_error(&mut context, ix, &[&stranger], CollectionError::NotCollectionAuthority).await;
+
+    // the index must belong to the passed collection
+    let ix = close_index_account(
+        program_id,
+        index_account,
+        payer_pubkey,
+        payer_pubkey,
+        other_collection_keypair.pubkey(),
+        position_account,
+        last_position_account,
+        last_index_account,
+        None,
+    );
+    assert_instruction_error(&mut context, ix, &[], CollectionError::CollectionIndexAccountMismatch).await;
+
+    // the holder closes the index, the last token moves into position 0
+    let ix = close_index_account(
+        program_id,
+        index_account,
+        holder.pubkey(),
+        holder.pubkey(),
+        collection,
+        position_account,
+        last_position_account,
+        last_index_account,
+        Some(holder_token_account),
+    );
+    process_ok(&mut context, ix, &[&holder]).await;
+
+    let index = context.banks_client.get_account(index_account).await.expect("get_account");
+    assert!(index.is_none());
+    let position = context.banks_client
+        .get_account(position_account)
+        .await
+        .expect("get_account")
+        .expect("position account not found");
+    let position_data: CollectionPositionAccountData = try_from_slice_unchecked(&position.data).unwrap();
+    assert_eq!(position_data.mint, mints[1]);
+
+    // the collection authority closes the remaining index
+    let ix = close_index_account(
+        program_id,
+        last_index_account,
+        payer_pubkey,
+        payer_pubkey,
+        collection,
+        position_account,
+        position_account,
+        last_index_account,
+        None,
+    );
+    process_ok(&mut context, ix, &[]).await;
+
+    let collection_account = context.banks_client
+        .get_account(collection)
+        .await
+        .expect("get_account")
+        .expect("collection account not found");
+    let account_data: CollectionAccountData = try_from_slice_unchecked(&collection_account.data).unwrap();
+    assert_eq!(account_data.supply, 0);
+
+    // once empty the collection can be closed, a fresh blockhash so the retried
+    // close is not deduplicated
+    context.get_new_latest_blockhash().await.unwrap();
+    process_ok(&mut context, close_collection_ix, &[]).await;
+    let collection_account = context.banks_client.get_account(collection).await.expect("get_account");
+    assert!(collection_account.is_none());
+}
+
+/// Sends `ix` signed by the payer and `signers`, the transaction must succeed.
+async fn process_ok(context: &mut ProgramTestContext, ix: Instruction, signers: &[&Keypair]) {
+    let mut transaction = Transaction::new_with_payer(
+        &[ix],
+        Some(&context.payer.pubkey()),
+    );
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+    transaction.sign(&all_signers, context.last_blockhash);
+    context.banks_client.process_transaction(transaction).await.unwrap();
+}
+
+async fn assert_instruction_error(
+    context: &mut ProgramTestContext,
+    ix: Instruction,
+    signers: &[&Keypair],
+    error: CollectionError,
+) {
+    let mut transaction = Transaction::new_with_payer(
+        &[ix],
+        Some(&context.payer.pubkey()),
+    );
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+    transaction.sign(&all_signers, context.last_blockhash);
+    let err = context.banks_client.process_transaction(transaction).await.unwrap_err();
+    assert_eq!(err.unwrap(), TransactionError::InstructionError(0, InstructionError::Custom(error as u32)));
+}
+
+#[tokio::test]
+async fn test_rejects_foreign_and_uninitialized_accounts() {
+    let program_id = id();
+    let mut program_test = ProgramTest::new("collection", program_id, processor!(process_instruction));
+
+    // a well formed collection that is owned by another program
+    let authority = Keypair::new();
+    let foreign_collection_keypair = Keypair::new();
+    let foreign_collection = foreign_collection_keypair.pubkey();
+    let foreign_collection_data = CollectionAccountData {
+        account_type: AccountType::VersionedCollectionAccount,
+        version: COLLECTION_ACCOUNT_VERSION,
+        authority: authority.pubkey(),
+        title: "foreign".to_string(),
+        symbol: "fc".to_string(),
+        description: "foreign collection".to_string(),
+        icon_image: "https://www.google.com".to_string(),
+        supply: 0,
+        stars: 0,
+        header_image: None,
+        short_description: None,
+        banner: None,
+        tags: None,
+        pending_authority: Some(authority.pubkey()),
+        max_supply: None,
+        metadata_locked: false,
+        membership_locked: false,
+    };
+    program_test.add_account(
+        foreign_collection,
+        Account {
+            lamports: sol_to_lamports(1.0),
+            data: foreign_collection_data.try_to_vec().unwrap(),
+            owner: spl_token::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+    // a program owned account that was never initialized
+    let uninitialized_collection = Keypair::new().pubkey();
+    program_test.add_account(
+        uninitialized_collection,
+        Account {
+            lamports: sol_to_lamports(1.0),
+            data: vec![0; 300],
+            owner: program_id,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+    let mut context = program_test.start_with_context().await;
+    let payer_pubkey = context.payer.pubkey();
+
+    let collection_keypair = Keypair::new();
+    let collection = collection_keypair.pubkey();
+    create_collection(&mut context, &collection_keypair, default_collection_args()).await.unwrap();
+    let (mint_keypair, nft_ata) = create_nft(&mut context).await.unwrap();
+    let mint = mint_keypair.pubkey();
+    include_nft(&mut context, &collection, &mint, &nft_ata, 0).await.unwrap();
+    let (index_account, _) = get_index_account(&collection, &mint);
+    let (position_account, _) = get_position_account(&collection, 0);
+    let (treasury_account, _) = get_treasury_account();
+
+    // re-initialization
+    let args = CreateCollectionAccountArgs {
+        title: "again".to_string(),
+        ..default_collection_args()
+    };
+    let ix = create_collection_account(program_id, collection, payer_pubkey, args);
+    assert_instruction_error(&mut context, ix, &[&collection_keypair], CollectionError::AlreadyInitialized).await;
+    let err = include_nft(&mut context, &collection, &mint, &nft_ata, 1).await.unwrap_err();
+    assert_eq!(err.unwrap(), TransactionError::InstructionError(0, InstructionError::Custom(CollectionError::AlreadyInitialized as u32)));
+
+    // foreign owned collection
+    let (foreign_index_account, _) = get_index_account(&foreign_collection, &mint);
+    let (foreign_position_account, _) = get_position_account(&foreign_collection, 0);
+    let ix = include_token(
+        program_id,
+        foreign_collection,
+        payer_pubkey,
+        mint,
+        nft_ata,
+        foreign_index_account,
+        payer_pubkey,
+        foreign_position_account,
+    );
+    assert_instruction_error(&mut context, ix, &[], CollectionError::InvalidAccountOwner).await;
+    let ix = light_up_stars_once(program_id, foreign_collection);
+    assert_instruction_error(&mut context, ix, &[&foreign_collection_keypair], CollectionError::InvalidAccountOwner).await;
+    let ix = light_up_stars_hundred(program_id, foreign_collection, payer_pubkey, treasury_account);
+    let mut thousand_ix = ix.clone();
+    thousand_ix.data = CollectionInstruction::LightUpStarsThousand.try_to_vec().unwrap();
+    assert_instruction_error(&mut context, ix, &[], CollectionError::InvalidAccountOwner).await;
+    assert_instruction_error(&mut context, thousand_ix, &[], CollectionError::InvalidAccountOwner).await;
+    let ix = light_up_stars(program_id, foreign_collection, payer_pubkey, treasury_account, 10);
+    assert_instruction_error(&mut context, ix, &[], CollectionError::InvalidAccountOwner).await;
+    let ix = close_collection_account(program_id, foreign_collection, payer_pubkey, authority.pubkey(), "tc");
+    assert_instruction_error(&mut context, ix, &[&authority], CollectionError::InvalidAccountOwner).await;
+    let ix = propose_authority(program_id, foreign_collection, authority.pubkey(), payer_pubkey);
+    assert_instruction_error(&mut context, ix, &[&authority], CollectionError::InvalidAccountOwner).await;
+    let ix = accept_authority(program_id, foreign_collection, authority.pubkey());
+    assert_instruction_error(&mut context, ix, &[&authority], CollectionError::InvalidAccountOwner).await;
+    let ix = migrate_index_account(
+        program_id,
+        collection,
+        payer_pubkey,
+        foreign_collection,
+        index_account,
+        payer_pubkey,
+        position_account,
+    );
+    assert_instruction_error(&mut context, ix, &[], CollectionError::InvalidAccountOwner).await;
+
+    // uninitialized collection
+    let ix = update_collection_account(program_id, uninitialized_collection, authority.pubkey(), UpdateCollectionAccountArgs::default());
+    assert_instruction_error(&mut context, ix, &[&authority], CollectionError::Uninitialized).await;
+    let ix = cancel_authority_transfer(program_id, uninitialized_collection, authority.pubkey());
+    assert_instruction_error(&mut context, ix, &[&authority], CollectionError::Uninitialized).await;
+
+    // a position account where an index account is expected
+    let ix = remove_token(
+        program_id,
+        collection,
+        payer_pubkey,
+        position_account,
+        payer_pubkey,
+        position_account,
+        position_account,
+        index_account,
+    );
+    assert_instruction_error(&mut context, ix, &[], CollectionError::InvalidAccountType).await;
+    let ix = close_index_account(
+        program_id,
+        position_account,
+        payer_pubkey,
+        payer_pubkey,
+        collection,
+        position_account,
+        position_account,
+        index_account,
+        None,
+    );
+    assert_instruction_error(&mut context, ix, &[], CollectionError::InvalidAccountType).await;
+
+    // the config was never initialized
+    let args = ConfigArgs {
+        admin: payer_pubkey,
+        treasury_manager: payer_pubkey,
+        stars_hundred_price: 5_000_000,
+        stars_thousand_price: 40_000_000,
+        field_limits: FieldLimits::default(),
+        price_per_star: 50_000,
+    };
+    let ix = update_config(program_id, payer_pubkey, args);
+    assert_instruction_error(&mut context, ix, &[], CollectionError::Uninitialized).await;
+    let ix = set_paused(program_id, payer_pubkey, true, true);
+    assert_instruction_error(&mut context, ix, &[], CollectionError::Uninitialized).await;
+}
+
+#[tokio::test]
+async fn test_rejects_foreign_config_account() {
+    let program_id = id();
+    let upgrade_authority = Keypair::new();
+    let mut program_test = ProgramTest::new("collection", program_id, processor!(process_instruction));
+    add_program_data_account(&mut program_test, &upgrade_authority.pubkey());
+    let (config_account, _) = get_config_account();
+    program_test.add_account(
+        config_account,
+        Account {
+            lamports: sol_to_lamports(1.0),
+            data: vec![4; ConfigAccountData::LEN],
+            owner: spl_token::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+    let mut context = program_test.start_with_context().await;
+    let payer_pubkey = context.payer.pubkey();
+
+    let args = ConfigArgs {
+        admin: payer_pubkey,
+        treasury_manager: payer_pubkey,
+        stars_hundred_price: 5_000_000,
+        stars_thousand_price: 40_000_000,
+        field_limits: FieldLimits::default(),
+        price_per_star: 50_000,
+    };
+    let ix = initialize_config(program_id, upgrade_authority.pubkey(), payer_pubkey, args.clone());
+    assert_instruction_error(&mut context, ix, &[&upgrade_authority], CollectionError::AlreadyInitialized).await;
+    let ix = update_config(program_id, payer_pubkey, args);
+    assert_instruction_error(&mut context, ix, &[], CollectionError::InvalidAccountOwner).await;
+    let ix = set_paused(program_id, payer_pubkey, true, true);
+    assert_instruction_error(&mut context, ix, &[], CollectionError::InvalidAccountOwner).await;
+    let (treasury_account, _) = get_treasury_account();
+    let ix = withdraw(program_id, payer_pubkey, treasury_account, payer_pubkey, None);
+    assert_instruction_error(&mut context, ix, &[], CollectionError::InvalidAccountOwner).await;
+    let collection_keypair = Keypair::new();
+    let ix = create_collection_account(program_id, collection_keypair.pubkey(), payer_pubkey, default_collection_args());
+    assert_instruction_error(&mut context, ix, &[&collection_keypair], CollectionError::InvalidAccountOwner).await;
+}
+
+#[tokio::test]
+async fn test_create_collection_pda() {
+    let program_id = id();
+    let program_test = ProgramTest::new("collection", program_id, processor!(process_instruction));
+    let mut context = program_test.start_with_context().await;
+    let payer_pubkey = context.payer.pubkey();
+
+    let ix = create_collection_pda(program_id, payer_pubkey, "pixel-cats".to_string(), default_collection_args());
+    process_ok(&mut context, ix, &[]).await;
+
+    let (collection, _) = get_collection_account(&payer_pubkey, "pixel-cats");
+    let collection_account = context.banks_client
+        .get_account(collection)
+        .await
+        .expect("get_account")
+        .expect("collection account not found");
+    assert_eq!(collection_account.owner, program_id);
+    let account_data: CollectionAccountData = try_from_slice_unchecked(&collection_account.data).unwrap();
+    assert_eq!(account_data.authority, payer_pubkey);
+    assert_eq!(account_data.title, default_collection_args().title);
+
+    // the derived collection works like any other
+    let (mint_keypair, nft_ata) = create_nft(&mut context).await.unwrap();
+    include_nft(&mut context, &collection, &mint_keypair.pubkey(), &nft_ata, 0).await.unwrap();
+
+    // a slug can only be used once per authority
+    let args = CreateCollectionAccountArgs {
+        title: "again".to_string(),
+        ..default_collection_args()
+    };
+    let ix = create_collection_pda(program_id, payer_pubkey, "pixel-cats".to_string(), args);
+    assert_instruction_error(&mut context, ix, &[], CollectionError::AlreadyInitialized).await;
+
+    let ix = create_collection_pda(program_id, payer_pubkey, "Pixel Cats".to_string(), default_collection_args());
+    assert_instruction_error(&mut context, ix, &[], CollectionError::InvalidInstructionArguments).await;
+}
+
+#[tokio::test]
+async fn test_symbol_registry() {
+    let program_id = id();
+    let program_test = ProgramTest::new("collection", program_id, processor!(process_instruction));
+    let mut context = program_test.start_with_context().await;
+    let payer_pubkey = context.payer.pubkey();
+
+    let collection_keypair = Keypair::new();
+    let ix = create_collection_account(
+        program_id,
+        collection_keypair.pubkey(),
+        payer_pubkey,
+        default_collection_args(),
+    );
+    process_ok(&mut context, ix, &[&collection_keypair]).await;
+
+    let (symbol_account, _) = get_symbol_account("TC").unwrap();
+    let account = context.banks_client
+        .get_account(symbol_account)
+        .await
+        .expect("get_account")
+        .expect("symbol account not found");
+    assert_eq!(account.owner, program_id);
+    let symbol_data: SymbolAccountData = try_from_slice_unchecked(&account.data).unwrap();
+    assert_eq!(symbol_data.account_type, AccountType::SymbolAccount);
+    assert_eq!(symbol_data.collection, collection_keypair.pubkey());
+
+    // symbols are compared without case and surrounding whitespace
+    let args = CreateCollectionAccountArgs {
+        symbol: " TC ".to_string(),
+        ..default_collection_args()
+    };
+    let ix = create_collection_pda(program_id, payer_pubkey, "taken".to_string(), args.clone());
+    assert_instruction_error(&mut context, ix, &[], CollectionError::SymbolAlreadyTaken).await;
+
+    // without the symbol account the symbol is not reserved
+    let other_keypair = Keypair::new();
+    let mut ix = create_collection_account(
+        program_id,
+        other_keypair.pubkey(),
+        payer_pubkey,
+        CreateCollectionAccountArgs {
+            symbol: "other".to_string(),
+            ..default_collection_args()
+        },
+    );
+    ix.accounts.pop();
+    process_ok(&mut context, ix, &[&other_keypair]).await;
+    let (other_symbol_account, _) = get_symbol_account("other").unwrap();
+    assert!(context.banks_client.get_account(other_symbol_account).await.unwrap().is_none());
+
+    // an empty symbol has no symbol account and reserves nothing
+    assert!(get_symbol_account(" ").is_none());
+    let empty_keypair = Keypair::new();
+    let empty_args = CreateCollectionAccountArgs {
+        symbol: String::new(),
+        ..default_collection_args()
+    };
+    let ix = create_collection_account(program_id, empty_keypair.pubkey(), payer_pubkey, empty_args.clone());
+    assert_eq!(ix.accounts.len(), 5);
+    let pda_ix = create_collection_pda(program_id, payer_pubkey, "empty".to_string(), empty_args);
+    assert_eq!(pda_ix.accounts.len(), 5);
+    let mut transaction = Transaction::new_with_payer(
+        &[ix, pda_ix],
+        Some(&payer_pubkey),
+    );
+    transaction.sign(&[&context.payer, &empty_keypair], context.last_blockhash);
+    context.banks_client.process_transaction(transaction).await.unwrap();
+    let ix = close_collection_account(program_id, empty_keypair.pubkey(), payer_pubkey, payer_pubkey, "");
+    assert_eq!(ix.accounts.len(), 3);
+    process_ok(&mut context, ix, &[]).await;
+    assert!(context.banks_client.get_account(empty_keypair.pubkey()).await.unwrap().is_none());
+
+    // symbols too long for a seed can not be reserved, builders leave their account out
+    let long_symbol = "x".repeat(33);
+    assert!(get_symbol_account(&long_symbol).is_none());
+    let ix = close_collection_account(program_id, other_keypair.pubkey(), payer_pubkey, payer_pubkey, &long_symbol);
+    assert_eq!(ix.accounts.len(), 3);
+    let ix = update_collection_account_with_symbol(
+        program_id,
+        other_keypair.pubkey(),
+        payer_pubkey,
+        &long_symbol,
+        UpdateCollectionAccountArgs {
+            symbol: Some("other".to_string()),
+            ..UpdateCollectionAccountArgs::default()
+        },
+    );
+    assert_eq!(ix.accounts.len(), 6);
+
+    // changing the symbol moves the reservation
+    let ix = update_collection_account_with_symbol(
+        program_id,
+        collection_keypair.pubkey(),
+        payer_pubkey,
+        "tc",
+        UpdateCollectionAccountArgs {
+            symbol: Some("new".to_string()),
+            ..UpdateCollectionAccountArgs::default()
+        },
+    );
+    process_ok(&mut context, ix, &[]).await;
+    assert!(context.banks_client.get_account(symbol_account).await.unwrap().is_none());
+    let (new_symbol_account, _) = get_symbol_account("new").unwrap();
+    let account = context.banks_client.get_account(new_symbol_account).await.unwrap().unwrap();
+    let symbol_data: SymbolAccountData = try_from_slice_unchecked(&account.data).unwrap();
+    assert_eq!(symbol_data.collection, collection_keypair.pubkey());
+
+    // once released the symbol can be taken again, a fresh blockhash so the retried
+    // create is not deduplicated
+    context.get_new_latest_blockhash().await.unwrap();
+    let ix = create_collection_pda(program_id, payer_pubkey, "taken".to_string(), args);
+    process_ok(&mut context, ix, &[]).await;
+
+    let (collection, _) = get_collection_account(&payer_pubkey, "taken");
+    let account = context.banks_client.get_account(symbol_account).await.unwrap().unwrap();
+    let symbol_data: SymbolAccountData = try_from_slice_unchecked(&account.data).unwrap();
+    assert_eq!(symbol_data.collection, collection);
+
+    // a symbol reserved by another collection can not be switched to
+    let ix = update_collection_account_with_symbol(
+        program_id,
+        collection_keypair.pubkey(),
+        payer_pubkey,
+        "new",
+        UpdateCollectionAccountArgs {
+            symbol: Some("tc".to_string()),
+            ..UpdateCollectionAccountArgs::default()
+        },
+    );
+    assert_instruction_error(&mut context, ix, &[], CollectionError::SymbolAlreadyTaken).await;
+
+    // the reservation can only be released by its own collection
+    let ix = close_collection_account(
+        program_id,
+        collection_keypair.pubkey(),
+        payer_pubkey,
+        payer_pubkey,
+        "tc",
+    );
+    assert_instruction_error(&mut context, ix, &[], CollectionError::SymbolAccountMismatch).await;
+
+    // collections created before the registry hold no reservation, closing one
+    // leaves the reservation of its symbol in place
+    let legacy_collection = Keypair::new().pubkey();
+    let legacy_data = (
+        AccountType::CollectionAccount,
+        payer_pubkey,
+        "legacy collection".to_string(),
+        "tc".to_string(),
+        "legacy collection description".to_string(),
+        "https://www.google.com".to_string(),
+        0u64,
+        0u64,
+        None::<String>,
+        None::<String>,
+        None::<String>,
+        None::<Vec<String>>,
+    ).try_to_vec().unwrap();
+    let legacy_account = Account {
+        lamports: solana_sdk::rent::Rent::default().minimum_balance(legacy_data.len()),
+        data: legacy_data,
+        owner: program_id,
+        executable: false,
+        rent_epoch: 0,
+    };
+    context.set_account(&legacy_collection, &legacy_account.into());
+    let ix = close_collection_account(program_id, legacy_collection, payer_pubkey, payer_pubkey, "tc");
+    process_ok(&mut context, ix, &[]).await;
+    assert!(context.banks_client.get_account(legacy_collection).await.unwrap().is_none());
+    let account = context.banks_client.get_account(symbol_account).await.unwrap().unwrap();
+    let symbol_data: SymbolAccountData = try_from_slice_unchecked(&account.data).unwrap();
+    assert_eq!(symbol_data.collection, collection);
+
+    // clearing the symbol releases the reservation without taking a new one
+    let ix = update_collection_account_with_symbol(
+        program_id,
+        collection_keypair.pubkey(),
+        payer_pubkey,
+        "new",
+        UpdateCollectionAccountArgs {
+            symbol: Some(String::new()),
+            ..UpdateCollectionAccountArgs::default()
+        },
+    );
+    assert_eq!(ix.accounts.len(), 6);
+    process_ok(&mut context, ix, &[]).await;
+    assert!(context.banks_client.get_account(new_symbol_account).await.unwrap().is_none());
+    let collection_account = context.banks_client.get_account(collection_keypair.pubkey()).await.unwrap().unwrap();
+    let collection_data: CollectionAccountData = try_from_slice_unchecked(&collection_account.data).unwrap();
+    assert_eq!(collection_data.symbol, "");
+
+    let ix = close_collection_account(
+        program_id,
+        collection_keypair.pubkey(),
+        payer_pubkey,
+        payer_pubkey,
+        "",
+    );
+    process_ok(&mut context, ix, &[]).await;
+    assert!(context.banks_client.get_account(collection_keypair.pubkey()).await.unwrap().is_none());
+}
+
+#[tokio::test]
+#[ignore = "needs the token metadata program in tests/fixtures, see add_token_metadata_program"]
+async fn test_include_token_by_creator() {
+    let program_id = id();
+    let mut program_test = ProgramTest::new("collection", program_id, processor!(process_instruction));
+    add_token_metadata_program(&mut program_test);
+    let creator = Keypair::new();
+    let co_creator = Keypair::new();
+    let sold_mint = Keypair::new();
+    let unverified_mint = Keypair::new();
+    let mut context = program_test.start_with_context().await;
+    let payer_pubkey = context.payer.pubkey();
+
+    let collection_keypair = Keypair::new();
+    let collection = collection_keypair.pubkey();
+    let ix = create_collection_account(program_id, collection, creator.pubkey(), default_collection_args());
+    let mut transaction = Transaction::new_with_payer(
+        &[
+            system_instruction::transfer(&payer_pubkey, &creator.pubkey(), sol_to_lamports(1.0)),
+            ix,
+        ],
+        Some(&payer_pubkey),
+    );
+    transaction.sign(&[&context.payer, &creator, &collection_keypair], context.last_blockhash);
+    context.banks_client.process_transaction(transaction).await.unwrap();
+
+    // both pieces were sold, the buyer holds them
+    let buyer = Pubkey::new_unique();
+    for mint in [&sold_mint, &unverified_mint] {
+        create_mint(&mut context, mint, &payer_pubkey, 0, Some(&payer_pubkey)).await.unwrap();
+        create_associated_account(&mut context, &buyer, &mint.pubkey()).await.unwrap();
+        let token_account = get_associated_token_address(&buyer, &mint.pubkey());
+        mint_tokens(&mut context, &mint.pubkey(), &token_account, 1, &payer_pubkey, None).await.unwrap();
+    }
+
+    // both creators verified the sold piece, nobody signed the other one
+    let mut transaction = Transaction::new_with_payer(
+        &[
+            create_metadata_accounts(&sold_mint.pubkey(), &payer_pubkey, &payer_pubkey, vec![
+                Creator { address: co_creator.pubkey(), verified: false, share: 50 },
+                Creator { address: creator.pubkey(), verified: false, share: 50 },
+            ]),
+            sign_metadata(&sold_mint.pubkey(), &co_creator.pubkey()),
+            sign_metadata(&sold_mint.pubkey(), &creator.pubkey()),
+            create_metadata_accounts(&unverified_mint.pubkey(), &payer_pubkey, &payer_pubkey, vec![
+                Creator { address: creator.pubkey(), verified: false, share: 100 },
+            ]),
+        ],
+        Some(&payer_pubkey),
+    );
+    transaction.sign(&[&context.payer, &co_creator, &creator], context.last_blockhash);
+    context.banks_client.process_transaction(transaction).await.unwrap();
+
+    // holder based inclusion is not possible for the creator
+    let (index_account, _) = get_index_account(&collection, &sold_mint.pubkey());
+    let (position_account, _) = get_position_account(&collection, 0);
+    let ix = include_token(
+        program_id,
+        collection,
+        creator.pubkey(),
+        sold_mint.pubkey(),
+        get_associated_token_address(&buyer, &sold_mint.pubkey()),
+        index_account,
+        payer_pubkey,
+        position_account,
+    );
+    assert_instruction_error(&mut context, ix, &[&creator], CollectionError::NotCollectionAuthority).await;
+
+    let ix = include_token_by_creator(
+        program_id,
+        collection,
+        creator.pubkey(),
+        sold_mint.pubkey(),
+        index_account,
+        payer_pubkey,
+        position_account,
+    );
+    process_ok(&mut context, ix, &[&creator]).await;
+
+    let collection_account = context.banks_client.get_account(collection).await.unwrap().unwrap();
+    let collection_data: CollectionAccountData = try_from_slice_unchecked(&collection_account.data).unwrap();
+    assert_eq!(collection_data.supply, 1);
+    let index = context.banks_client.get_account(index_account).await.unwrap().unwrap();
+    let index_data: CollectionIndexAccountData = try_from_slice_unchecked(&index.data).unwrap();
+    assert_eq!(index_data.mint, sold_mint.pubkey());
+
+    // an unverified creator entry is not enough
+    let (index_account, _) = get_index_account(&collection, &unverified_mint.pubkey());
+    let (position_account, _) = get_position_account(&collection, 1);
+    let ix = include_token_by_creator(
+        program_id,
+        collection,
+        creator.pubkey(),
+        unverified_mint.pubkey(),
+        index_account,
+        payer_pubkey,
+        position_account,
+    );
+    assert_instruction_error(&mut context, ix, &[&creator], CollectionError::CreatorNotVerified).await;
+
+    // a mint without metadata is rejected
+    let (mint_keypair, _) = create_nft(&mut context).await.unwrap();
+    let (index_account, _) = get_index_account(&collection, &mint_keypair.pubkey());
+    let ix = include_token_by_creator(
+        program_id,
+        collection,
+        creator.pubkey(),
+        mint_keypair.pubkey(),
+        index_account,
+        payer_pubkey,
+        position_account,
+    );
+    assert_instruction_error(&mut context, ix, &[&creator], CollectionError::InvalidMetadataAccount).await;
+}
+
+#[tokio::test]
+async fn test_submission_queue() {
+    let program_id = id();
+    let program_test = ProgramTest::new("collection", program_id, processor!(process_instruction));
+    let mut context = program_test.start_with_context().await;
+    let payer_pubkey = context.payer.pubkey();
+
+    let collection_keypair = Keypair::new();
+    let collection = collection_keypair.pubkey();
+    create_collection(&mut context, &collection_keypair, default_collection_args()).await.unwrap();
+
+    // a community member holding three nfts
+    let member = Keypair::new();
+    process_ok(&mut context, system_instruction::transfer(&payer_pubkey, &member.pubkey(), sol_to_lamports(1.0)), &[]).await;
+    let mut mints = vec![];
+    for _ in 0..3 {
+        let mint = Keypair::new();
+        create_mint(&mut context, &mint, &payer_pubkey, 0, Some(&payer_pubkey)).await.unwrap();
+        create_associated_account(&mut context, &member.pubkey(), &mint.pubkey()).await.unwrap();
+        let token_account = get_associated_token_address(&member.pubkey(), &mint.pubkey());
+        mint_tokens(&mut context, &mint.pubkey(), &token_account, 1, &payer_pubkey, None).await.unwrap();
+        mints.push(mint.pubkey());
+    }
+
+    // only the holder can submit
+    let outsider = Keypair::new();
+    let ix = submit_token(
+        program_id,
+        collection,
+        outsider.pubkey(),
+        mints[0],
+        get_associated_token_address(&member.pubkey(), &mints[0]),
+    );
+    let mut transaction = Transaction::new_with_payer(
+        &[
+            system_instruction::transfer(&payer_pubkey, &outsider.pubkey(), sol_to_lamports(1.0)),
+            ix,
+        ],
+        Some(&payer_pubkey),
+    );
+    transaction.sign(&[&context.payer, &outsider], context.last_blockhash);
+    let err = context.banks_client.process_transaction(transaction).await.unwrap_err();
+    assert_eq!(err.unwrap(), TransactionError::InstructionError(1, InstructionError::Custom(CollectionError::NotCollectionAuthority as u32)));
+
+    for mint in &mints {
+        let ix = submit_token(
+            program_id,
+            collection,
+            member.pubkey(),
+            *mint,
+            get_associated_token_address(&member.pubkey(), mint),
+        );
+        process_ok(&mut context, ix, &[&member]).await;
+    }
+    let (submission_account, _) = get_submission_account(&collection, &mints[0]);
+    let submission = context.banks_client.get_account(submission_account).await.unwrap().unwrap();
+    let submission_data: SubmissionAccountData = try_from_slice_unchecked(&submission.data).unwrap();
+    assert_eq!(submission_data.account_type, AccountType::SubmissionAccount);
+    assert_eq!(submission_data.collection, collection);
+    assert_eq!(submission_data.mint, mints[0]);
+    assert_eq!(submission_data.submitter, member.pubkey());
+
+    // submitting twice is not possible
+    let ix = submit_token(
+        program_id,
+        collection,
+        member.pubkey(),
+        mints[0],
+        get_associated_token_address(&member.pubkey(), &mints[0]),
+    );
+    context.get_new_latest_blockhash().await.unwrap();
+    assert_instruction_error(&mut context, ix, &[&member], CollectionError::AlreadyInitialized).await;
+
+    // only the collection authority decides
+    let ix = reject_submission(program_id, collection, member.pubkey(), mints[0], member.pubkey());
+    assert_instruction_error(&mut context, ix, &[&member], CollectionError::NotCollectionAuthority).await;
+
+    let member_lamports = context.banks_client.get_balance(member.pubkey()).await.unwrap();
+    let (index_account, _) = get_index_account(&collection, &mints[0]);
+    let (position_account, _) = get_position_account(&collection, 0);
+    let ix = approve_submission(
+        program_id,
+        collection,
+        payer_pubkey,
+        mints[0],
+        member.pubkey(),
+        get_associated_token_address(&member.pubkey(), &mints[0]),
+        index_account,
+        payer_pubkey,
+        position_account,
+    );
+    process_ok(&mut context, ix, &[]).await;
+
+    assert!(context.banks_client.get_account(submission_account).await.unwrap().is_none());
+    assert_eq!(
+        context.banks_client.get_balance(member.pubkey()).await.unwrap(),
+        member_lamports + submission.lamports,
+    );
+    let index = context.banks_client.get_account(index_account).await.unwrap().unwrap();
+    let index_data: CollectionIndexAccountData = try_from_slice_unchecked(&index.data).unwrap();
+    assert_eq!(index_data.mint, mints[0]);
+    assert_eq!(index_data.index, 0);
+    let collection_account = context.banks_client.get_account(collection).await.unwrap().unwrap();
+    let collection_data: CollectionAccountData = try_from_slice_unchecked(&collection_account.data).unwrap();
+    assert_eq!(collection_data.supply, 1);
+
+    // the refund can not be redirected
+    let ix = reject_submission(program_id, collection, payer_pubkey, mints[1], payer_pubkey);
+    assert_instruction_error(&mut context, ix, &[], CollectionError::SubmissionAccountMismatch).await;
+
+    let ix = reject_submission(program_id, collection, payer_pubkey, mints[1], member.pubkey());
+    process_ok(&mut context, ix, &[]).await;
+    let (submission_account, _) = get_submission_account(&collection, &mints[1]);
+    assert!(context.banks_client.get_account(submission_account).await.unwrap().is_none());
+    let (index_account, _) = get_index_account(&collection, &mints[1]);
+    assert!(context.banks_client.get_account(index_account).await.unwrap().is_none());
+
+    // the member sells the last submitted token
+    let buyer = Keypair::new();
+    create_associated_account(&mut context, &buyer.pubkey(), &mints[2]).await.unwrap();
+    let member_token_account = get_associated_token_address(&member.pubkey(), &mints[2]);
+    let buyer_token_account = get_associated_token_address(&buyer.pubkey(), &mints[2]);
+    let ix = spl_token::instruction::transfer(
+        &spl_token::id(),
+        &member_token_account,
+        &buyer_token_account,
+        &member.pubkey(),
+        &[],
+        1,
+    ).unwrap();
+    let mut transaction = Transaction::new_with_payer(
+        &[
+            ix,
+            system_instruction::transfer(&payer_pubkey, &buyer.pubkey(), sol_to_lamports(1.0)),
+        ],
+        Some(&payer_pubkey),
+    );
+    transaction.sign(&[&context.payer, &member], context.last_blockhash);
+    context.banks_client.process_transaction(transaction).await.unwrap();
+
+    // so the submission can no longer be approved on its behalf
+    let (index_account, _) = get_index_account(&collection, &mints[2]);
+    let (position_account, _) = get_position_account(&collection, 1);
+    let ix = approve_submission(
+        program_id,
+        collection,
+        payer_pubkey,
+        mints[2],
+        member.pubkey(),
+        member_token_account,
+        index_account,
+        payer_pubkey,
+        position_account,
+    );
+    assert_instruction_error(&mut context, ix, &[], CollectionError::SubmitterNotHolder).await;
+
+    // the buyer takes the submission over and refunds its rent to the member
+    let (submission_account, _) = get_submission_account(&collection, &mints[2]);
+    let submission = context.banks_client.get_account(submission_account).await.unwrap().unwrap();
+    let member_lamports = context.banks_client.get_balance(member.pubkey()).await.unwrap();
+    let ix = replace_submission(program_id, collection, buyer.pubkey(), mints[2], buyer_token_account, member.pubkey());
+    process_ok(&mut context, ix, &[&buyer]).await;
+    assert_eq!(
+        context.banks_client.get_balance(member.pubkey()).await.unwrap(),
+        member_lamports + submission.lamports,
+    );
+    let submission = context.banks_client.get_account(submission_account).await.unwrap().unwrap();
+    let submission_data: SubmissionAccountData = try_from_slice_unchecked(&submission.data).unwrap();
+    assert_eq!(submission_data.submitter, buyer.pubkey());
+
+    // the previous submitter can not take it back without the token
+    let ix = replace_submission(program_id, collection, member.pubkey(), mints[2], member_token_account, buyer.pubkey());
+    assert_instruction_error(&mut context, ix, &[&member], CollectionError::InvalidNFT).await;
+
+    let ix = approve_submission(
+        program_id,
+        collection,
+        payer_pubkey,
+        mints[2],
+        buyer.pubkey(),
+        buyer_token_account,
+        index_account,
+        payer_pubkey,
+        position_account,
+    );
+    process_ok(&mut context, ix, &[]).await;
+    let index = context.banks_client.get_account(index_account).await.unwrap().unwrap();
+    let index_data: CollectionIndexAccountData = try_from_slice_unchecked(&index.data).unwrap();
+    assert_eq!(index_data.index, 1);
+}
+
+#[tokio::test]
+async fn test_curators() {
+    let program_id = id();
+    let program_test = ProgramTest::new("collection", program_id, processor!(process_instruction));
+    let mut context = program_test.start_with_context().await;
+    let payer_pubkey = context.payer.pubkey();
+
+    let collection_keypair = Keypair::new();
+    let collection = collection_keypair.pubkey();
+    create_collection(&mut context, &collection_keypair, default_collection_args()).await.unwrap();
+
+    // the curator holds the nft it curates
+    let curator = Keypair::new();
+    let mint = Keypair::new();
+    create_mint(&mut context, &mint, &payer_pubkey, 0, Some(&payer_pubkey)).await.unwrap();
+    create_associated_account(&mut context, &curator.pubkey(), &mint.pubkey()).await.unwrap();
+    let token_account = get_associated_token_address(&curator.pubkey(), &mint.pubkey());
+    mint_tokens(&mut context, &mint.pubkey(), &token_account, 1, &payer_pubkey, None).await.unwrap();
+    process_ok(&mut context, system_instruction::transfer(&payer_pubkey, &curator.pubkey(), sol_to_lamports(1.0)), &[]).await;
+
+    // only the authority manages curators
+    let ix = add_curator(program_id, collection, curator.pubkey(), curator.pubkey(), curator.pubkey(), CURATOR_INCLUDE);
+    assert_instruction_error(&mut context, ix, &[&curator], CollectionError::NotCollectionAuthority).await;
+    let ix = add_curator(program_id, collection, payer_pubkey, curator.pubkey(), payer_pubkey, 1 << 7);
+    assert_instruction_error(&mut context, ix, &[], CollectionError::InvalidInstructionArguments).await;
+
+    let ix = add_curator(program_id, collection, payer_pubkey, curator.pubkey(), payer_pubkey, CURATOR_INCLUDE);
+    process_ok(&mut context, ix, &[]).await;
+
+    let (curator_account, _) = get_curator_account(&collection, &curator.pubkey());
+    let account = context.banks_client.get_account(curator_account).await.unwrap().unwrap();
+    let curator_data: CuratorAccountData = try_from_slice_unchecked(&account.data).unwrap();
+    assert_eq!(curator_data.account_type, AccountType::CuratorAccount);
+    assert_eq!(curator_data.collection, collection);
+    assert_eq!(curator_data.curator, curator.pubkey());
+    assert_eq!(curator_data.permissions, CURATOR_INCLUDE);
+
+    // a curator can not act without its curator account
+    let (index_account, _) = get_index_account(&collection, &mint.pubkey());
+    let (position_account, _) = get_position_account(&collection, 0);
+    let include_ix = include_token(
+        program_id,
+        collection,
+        curator.pubkey(),
+        mint.pubkey(),
+        token_account,
+        index_account,
+        curator.pubkey(),
+        position_account,
+    );
+    assert_instruction_error(&mut context, include_ix.clone(), &[&curator], CollectionError::NotCollectionAuthority).await;
+
+    let ix = with_curator(include_ix, collection, curator.pubkey());
+    process_ok(&mut context, ix, &[&curator]).await;
+    let collection_account = context.banks_client.get_account(collection).await.unwrap().unwrap();
+    let collection_data: CollectionAccountData = try_from_slice_unchecked(&collection_account.data).unwrap();
+    assert_eq!(collection_data.supply, 1);
+
+    // editing needs its own permission
+    let args = UpdateCollectionAccountArgs {
+        title: Some("curated".to_string()),
+        symbol: None,
+        description: None,
+        icon_image: None,
+        header_image: None,
+        short_description: None,
+        banner: None,
+        tags: None,
+        max_supply: None,
+        clear: 0,
+    };
+    let update_ix = with_curator_and_authority(
+        update_collection_account(program_id, collection, curator.pubkey(), args),
+        collection,
+        curator.pubkey(),
+        payer_pubkey,
+    );
+    assert_instruction_error(&mut context, update_ix.clone(), &[&curator], CollectionError::NotCollectionAuthority).await;
+
+    // adding again replaces the permissions
+    let ix = add_curator(program_id, collection, payer_pubkey, curator.pubkey(), payer_pubkey, CURATOR_EDIT | CURATOR_REMOVE);
+    process_ok(&mut context, ix, &[]).await;
+
+    context.get_new_latest_blockhash().await.unwrap();
+    process_ok(&mut context, update_ix, &[&curator]).await;
+    let collection_account = context.banks_client.get_account(collection).await.unwrap().unwrap();
+    let collection_data: CollectionAccountData = try_from_slice_unchecked(&collection_account.data).unwrap();
+    assert_eq!(collection_data.title, "curated");
+    assert_eq!(collection_data.authority, payer_pubkey);
+
+    // the curator pays for the new symbol, the rent the authority funded goes back to it
+    let ix = with_curator_and_authority(
+        update_collection_account_with_symbol(program_id, collection, curator.pubkey(), "tc", UpdateCollectionAccountArgs {
+            title: Some("c".to_string()),
+            symbol: Some("cur".to_string()),
+            ..UpdateCollectionAccountArgs::default()
+        }),
+        collection,
+        curator.pubkey(),
+        payer_pubkey,
+    );
+    let authority_balance = context.banks_client.get_balance(payer_pubkey).await.unwrap();
+    let collection_balance = context.banks_client.get_balance(collection).await.unwrap();
+    let mut transaction = Transaction::new_with_payer(
+        &[ix],
+        Some(&curator.pubkey()),
+    );
+    transaction.sign(&[&curator], context.last_blockhash);
+    context.banks_client.process_transaction(transaction).await.unwrap();
+    let symbol_rent = solana_sdk::rent::Rent::default().minimum_balance(SymbolAccountData::LEN);
+    let resize_refund = collection_balance - context.banks_client.get_balance(collection).await.unwrap();
+    assert!(resize_refund > 0);
+    assert_eq!(
+        context.banks_client.get_balance(payer_pubkey).await.unwrap(),
+        authority_balance + symbol_rent + resize_refund,
+    );
+    // the refunds only go to the collection authority
+    let wrong_authority_ix = with_curator_and_authority(
+        update_collection_account(program_id, collection, curator.pubkey(), UpdateCollectionAccountArgs {
+            title: Some("cu".to_string()),
+            ..UpdateCollectionAccountArgs::default()
+        }),
+        collection,
+        curator.pubkey(),
+        curator.pubkey(),
+    );
+    assert_instruction_error(&mut context, wrong_authority_ix, &[&curator], CollectionError::NotCollectionAuthority).await;
+    let (symbol_account, _) = get_symbol_account("cur").unwrap();
+    let account = context.banks_client.get_account(symbol_account).await.unwrap().unwrap();
+    let symbol_data: SymbolAccountData = try_from_slice_unchecked(&account.data).unwrap();
+    assert_eq!(symbol_data.collection, collection);
+
+    // the cap limits the membership, editing is not enough to change it
+    let cap_ix = |max_supply| with_curator_and_authority(
+        update_collection_account(program_id, collection, curator.pubkey(), UpdateCollectionAccountArgs {
+            max_supply: Some(max_supply),
+            ..UpdateCollectionAccountArgs::default()
+        }),
+        collection,
+        curator.pubkey(),
+        payer_pubkey,
+    );
+    assert_instruction_error(&mut context, cap_ix(5), &[&curator], CollectionError::NotCollectionAuthority).await;
+    let ix = add_curator(program_id, collection, payer_pubkey, curator.pubkey(), payer_pubkey, CURATOR_INCLUDE | CURATOR_REMOVE);
+    let mut transaction = Transaction::new_with_payer(
+        &[ix, cap_ix(5)],
+        Some(&payer_pubkey),
+    );
+    transaction.sign(&[&context.payer, &curator], context.last_blockhash);
+    context.banks_client.process_transaction(transaction).await.unwrap();
+    let collection_account = context.banks_client.get_account(collection).await.unwrap().unwrap();
+    let collection_data: CollectionAccountData = try_from_slice_unchecked(&collection_account.data).unwrap();
+    assert_eq!(collection_data.max_supply, Some(5));
+
+    // a curator account only speaks for its own member
+    let remove_ix = remove_token(
+        program_id,
+        collection,
+        payer_pubkey,
+        index_account,
+        payer_pubkey,
+        position_account,
+        position_account,
+        index_account,
+    );
+    let outsider = Keypair::new();
+    let mut ix = remove_ix.clone();
+    ix.accounts[1] = AccountMeta::new_readonly(outsider.pubkey(), true);
+    let ix = with_curator(ix, collection, curator.pubkey());
+    assert_instruction_error(&mut context, ix, &[&outsider], CollectionError::CuratorAccountMismatch).await;
+
+    let ix = remove_curator(program_id, collection, payer_pubkey, curator.pubkey(), payer_pubkey);
+    process_ok(&mut context, ix, &[]).await;
+    assert!(context.banks_client.get_account(curator_account).await.unwrap().is_none());
+
+    let mut ix = remove_ix;
+    ix.accounts[1] = AccountMeta::new_readonly(curator.pubkey(), true);
+    let ix = with_curator(ix, collection, curator.pubkey());
+    assert_instruction_error(&mut context, ix, &[&curator], CollectionError::InvalidAccountOwner).await;
+}
+
+#[tokio::test]
+async fn test_max_supply() {
+    let program_id = id();
+    let program_test = ProgramTest::new("collection", program_id, processor!(process_instruction));
+    let mut context = program_test.start_with_context().await;
+    let payer_pubkey = context.payer.pubkey();
+
+    // instructions from clients without the cap still decode
+    let data = default_collection_args().try_to_vec().unwrap();
+    assert_eq!(
+        CreateCollectionAccountArgs::try_from_slice(&data[..data.len() - 1]).unwrap(),
+        default_collection_args(),
+    );
+
+    // a cap of zero can never hold anything
+    let args = CreateCollectionAccountArgs {
+        max_supply: Some(0),
+        ..default_collection_args()
+    };
+    let collection_keypair = Keypair::new();
+    let ix = create_collection_account(program_id, collection_keypair.pubkey(), payer_pubkey, args);
+    assert_instruction_error(&mut context, ix, &[&collection_keypair], CollectionError::InvalidInstructionArguments).await;
+
+    let args = CreateCollectionAccountArgs {
+        max_supply: Some(3),
+        ..default_collection_args()
+    };
+    let collection = collection_keypair.pubkey();
+    create_collection(&mut context, &collection_keypair, args).await.unwrap();
+    for position in 0..2 {
+        let (mint_keypair, nft_ata) = create_nft(&mut context).await.unwrap();
+        include_nft(&mut context, &collection, &mint_keypair.pubkey(), &nft_ata, position).await.unwrap();
+    }
+
+    let update = |max_supply| update_collection_account(
+        program_id,
+        collection,
+        payer_pubkey,
+        UpdateCollectionAccountArgs {
+            max_supply: Some(max_supply),
+            ..Default::default()
+        },
+    );
+    // the cap can not be raised nor go below the supply
+    assert_instruction_error(&mut context, update(4), &[], CollectionError::InvalidInstructionArguments).await;
+    assert_instruction_error(&mut context, update(1), &[], CollectionError::InvalidInstructionArguments).await;
+
+    process_ok(&mut context, update(2), &[]).await;
+    let collection_account = context.banks_client.get_account(collection).await.unwrap().unwrap();
+    let collection_data: CollectionAccountData = try_from_slice_unchecked(&collection_account.data).unwrap();
+    assert_eq!(collection_data.max_supply, Some(2));
+    assert_eq!(collection_data.supply, 2);
+
+    let (mint_keypair, nft_ata) = create_nft(&mut context).await.unwrap();
+    let (index_account, _) = get_index_account(&collection, &mint_keypair.pubkey());
+    let (position_account, _) = get_position_account(&collection, 2);
+    let ix = include_token(
+        program_id,
+        collection,
+        payer_pubkey,
+        mint_keypair.pubkey(),
+        nft_ata,
+        index_account,
+        payer_pubkey,
+        position_account,
+    );
+    assert_instruction_error(&mut context, ix, &[], CollectionError::CollectionFull).await;
+}
+
+#[tokio::test]
+async fn test_lock_collection() {
+    let program_id = id();
+    let program_test = ProgramTest::new("collection", program_id, processor!(process_instruction));
+    let mut context = program_test.start_with_context().await;
+    let payer_pubkey = context.payer.pubkey();
+
+    let collection_keypair = Keypair::new();
+    let collection = collection_keypair.pubkey();
+    create_collection(&mut context, &collection_keypair, default_collection_args()).await.unwrap();
+    let (mint_keypair, nft_ata) = create_nft(&mut context).await.unwrap();
+    include_nft(&mut context, &collection, &mint_keypair.pubkey(), &nft_ata, 0).await.unwrap();
+
+    let ix = lock_collection(program_id, collection, payer_pubkey, false, false);
+    assert_instruction_error(&mut context, ix, &[], CollectionError::InvalidInstructionArguments).await;
+
+    let ix = lock_collection(program_id, collection, payer_pubkey, true, false);
+    process_ok(&mut context, ix, &[]).await;
+
+    let update = |args| update_collection_account(program_id, collection, payer_pubkey, args);
+    let ix = update(UpdateCollectionAccountArgs {
+        title: Some("swapped".to_string()),
+        ..Default::default()
+    });
+    assert_instruction_error(&mut context, ix, &[], CollectionError::CollectionImmutable).await;
+
+    // membership is still open
+    let ix = update(UpdateCollectionAccountArgs {
+        max_supply: Some(5),
+        ..Default::default()
+    });
+    process_ok(&mut context, ix, &[]).await;
+    let (second_mint, second_ata) = create_nft(&mut context).await.unwrap();
+    include_nft(&mut context, &collection, &second_mint.pubkey(), &second_ata, 1).await.unwrap();
+
+    // locking again keeps the metadata lock
+    let ix = lock_collection(program_id, collection, payer_pubkey, false, true);
+    process_ok(&mut context, ix, &[]).await;
+    let collection_account = context.banks_client.get_account(collection).await.unwrap().unwrap();
+    let collection_data: CollectionAccountData = try_from_slice_unchecked(&collection_account.data).unwrap();
+    assert!(collection_data.metadata_locked);
+    assert!(collection_data.membership_locked);
+    assert_eq!(collection_data.supply, 2);
+    assert_eq!(collection_data.title, default_collection_args().title);
+
+    let (mint_keypair, nft_ata) = create_nft(&mut context).await.unwrap();
+    let (index_account, _) = get_index_account(&collection, &mint_keypair.pubkey());
+    let (position_account, _) = get_position_account(&collection, 2);
+    let ix = include_token(
+        program_id,
+        collection,
+        payer_pubkey,
+        mint_keypair.pubkey(),
+        nft_ata,
+        index_account,
+        payer_pubkey,
+        position_account,
+    );
+    assert_instruction_error(&mut context, ix, &[], CollectionError::CollectionImmutable).await;
+
+    let (index_account, _) = get_index_account(&collection, &second_mint.pubkey());
+    let (position_account, _) = get_position_account(&collection, 1);
+    let ix = remove_token(
+        program_id,
+        collection,
+        payer_pubkey,
+        index_account,
+        payer_pubkey,
+        position_account,
+        position_account,
+        index_account,
+    );
+    assert_instruction_error(&mut context, ix, &[], CollectionError::CollectionImmutable).await;
+
+    let ix = update(UpdateCollectionAccountArgs {
+        max_supply: Some(2),
+        ..Default::default()
+    });
+    assert_instruction_error(&mut context, ix, &[], CollectionError::CollectionImmutable).await;
+
+    let ix = close_collection_account(program_id, collection, payer_pubkey, payer_pubkey, "tc");
+    assert_instruction_error(&mut context, ix, &[], CollectionError::CollectionImmutable).await;
+}
+
+#[tokio::test]
+async fn test_migrate_collection_account() {
+    let program_id = id();
+    let mut program_test = ProgramTest::new("collection", program_id, processor!(process_instruction));
+
+    // an authority whose first byte matches the version byte, versioned accounts are
+    // told apart by their account type so this can not be mistaken for one
+    let authority = loop {
+        let keypair = Keypair::new();
+        if keypair.pubkey().to_bytes()[0] == COLLECTION_ACCOUNT_VERSION {
+            break keypair;
+        }
+    };
+    // layout written before accounts carried a version
+    let legacy_data = (
+        AccountType::CollectionAccount,
+        authority.pubkey(),
+        "legacy collection".to_string(),
+        "lc".to_string(),
+        "legacy collection description".to_string(),
+        "https://www.google.com".to_string(),
+        7u64,
+        42u64,
+        Some("www.solana.com".to_string()),
+        None::<String>,
+        None::<String>,
+        Some(vec!["art".to_string()]),
+    ).try_to_vec().unwrap();
+    let legacy_keypair = Keypair::new();
+    let legacy_collection = legacy_keypair.pubkey();
+    program_test.add_account(
+        legacy_collection,
+        Account {
+            lamports: solana_sdk::rent::Rent::default().minimum_balance(legacy_data.len()),
+            data: legacy_data,
+            owner: program_id,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+    let mut context = program_test.start_with_context().await;
+    let payer_pubkey = context.payer.pubkey();
+
+    // accounts created before the version byte, `pending_authority` and `max_supply`
+    // existed still deserialize
+    let collection_account = context.banks_client.get_account(legacy_collection).await.unwrap().unwrap();
+    let collection_data: CollectionAccountData = try_from_slice_unchecked(&collection_account.data).unwrap();
+    assert_eq!(collection_data.version, 0);
+    assert_eq!(collection_data.authority, authority.pubkey());
+    assert_eq!(collection_data.title, "legacy collection");
+    assert_eq!(collection_data.supply, 7);
+    assert_eq!(collection_data.stars, 42);
+    assert_eq!(collection_data.tags, Some(vec!["art".to_string()]));
+    assert_eq!(collection_data.pending_authority, None);
+    assert_eq!(collection_data.max_supply, None);
+    assert!(!collection_data.metadata_locked && !collection_data.membership_locked);
+
+    // paid stars are still written in place, keeping the legacy layout
+    let (treasury_account, _) = get_treasury_account();
+    let ix = light_up_stars_hundred(program_id, legacy_collection, payer_pubkey, treasury_account);
+    process_ok(&mut context, ix, &[]).await;
+    let lit_account = context.banks_client.get_account(legacy_collection).await.unwrap().unwrap();
+    assert_eq!(lit_account.data[0], AccountType::CollectionAccount as u8);
+    assert_eq!(lit_account.data.len(), collection_account.data.len());
+    let collection_data: CollectionAccountData = try_from_slice_unchecked(&lit_account.data).unwrap();
+    assert_eq!(collection_data.version, 0);
+    assert_eq!(collection_data.authority, authority.pubkey());
+    assert_eq!(collection_data.supply, 7);
+    assert_eq!(collection_data.stars, 142);
+
+    let ix = migrate_collection_account(program_id, legacy_collection, payer_pubkey);
+    process_ok(&mut context, ix, &[]).await;
+
+    let collection_account = context.banks_client.get_account(legacy_collection).await.unwrap().unwrap();
+    assert_eq!(collection_account.data[0], AccountType::VersionedCollectionAccount as u8);
+    assert_eq!(collection_account.data[1], COLLECTION_ACCOUNT_VERSION);
+    let migrated_data: CollectionAccountData = try_from_slice_unchecked(&collection_account.data).unwrap();
+    assert_eq!(collection_account.data.len(), migrated_data.try_to_vec().unwrap().len());
+    assert_eq!(migrated_data.version, COLLECTION_ACCOUNT_VERSION);
+    assert_eq!(migrated_data.authority, collection_data.authority);
+    assert_eq!(migrated_data.title, collection_data.title);
+    assert_eq!(migrated_data.description, collection_data.description);
+    // legacy members are counted again as their index accounts are migrated
+    assert_eq!(migrated_data.supply, 0);
+    assert_eq!(migrated_data.stars, 142);
+    assert_eq!(migrated_data.header_image, collection_data.header_image);
+    assert_eq!(migrated_data.tags, collection_data.tags);
+    assert_eq!(migrated_data.pending_authority, None);
+    assert_eq!(migrated_data.max_supply, None);
+
+    context.get_new_latest_blockhash().await.unwrap();
+    let ix = light_up_stars_once(program_id, legacy_collection);
+    process_ok(&mut context, ix, &[&legacy_keypair]).await;
+    let collection_account = context.banks_client.get_account(legacy_collection).await.unwrap().unwrap();
+    let migrated_data: CollectionAccountData = try_from_slice_unchecked(&collection_account.data).unwrap();
+    assert_eq!(migrated_data.stars, 143);
+
+    // migrating a current account changes nothing
+    context.get_new_latest_blockhash().await.unwrap();
+    let ix = migrate_collection_account(program_id, legacy_collection, payer_pubkey);
+    process_ok(&mut context, ix, &[]).await;
+    let account = context.banks_client.get_account(legacy_collection).await.unwrap().unwrap();
+    assert_eq!(account.data, collection_account.data);
+}
+
+#[tokio::test]
+async fn test_update_legacy_collection_max_supply() {
+    let program_id = id();
+    let program_test = ProgramTest::new("collection", program_id, processor!(process_instruction));
+    let mut context = program_test.start_with_context().await;
+    let payer_pubkey = context.payer.pubkey();
+
+    // the stale supply of an unversioned collection is dropped when it is saved
+    let collection = Keypair::new().pubkey();
+    let legacy_data = (
+        AccountType::CollectionAccount,
+        payer_pubkey,
+        "legacy collection".to_string(),
+        "lc".to_string(),
+        "legacy collection description".to_string(),
+        "https://www.google.com".to_string(),
+        5u64,
+        0u64,
+        None::<String>,
+        None::<String>,
+        None::<String>,
+        None::<Vec<String>>,
+    ).try_to_vec().unwrap();
+    let legacy_account = Account {
+        lamports: solana_sdk::rent::Rent::default().minimum_balance(legacy_data.len()),
+        data: legacy_data,
+        owner: program_id,
+        executable: false,
+        rent_epoch: 0,
+    };
+    context.set_account(&collection, &legacy_account.into());
+    let collection_account = context.banks_client.get_account(collection).await.unwrap().unwrap();
+    let collection_data: CollectionAccountData = try_from_slice_unchecked(&collection_account.data).unwrap();
+    assert_eq!(collection_data.supply, 5);
+    assert_eq!(collection_data.effective_supply(), 0);
+
+    let ix = update_collection_account(
+        program_id,
+        collection,
+        payer_pubkey,
+        UpdateCollectionAccountArgs {
+            max_supply: Some(1),
+            ..Default::default()
+        },
+    );
+    process_ok(&mut context, ix, &[]).await;
+
+    let collection_account = context.banks_client.get_account(collection).await.unwrap().unwrap();
+    assert_eq!(collection_account.data[0], AccountType::VersionedCollectionAccount as u8);
+    let collection_data: CollectionAccountData = try_from_slice_unchecked(&collection_account.data).unwrap();
+    assert_eq!(collection_data.supply, 0);
+    assert_eq!(collection_data.effective_supply(), 0);
+    assert_eq!(collection_data.max_supply, Some(1));
+}
+
+#[tokio::test]
+async fn test_light_up_stars_thousand() {
+    let program_id = id();
+    let program_test = ProgramTest::new("collection", program_id, processor!(process_instruction));
+    let mut context = program_test.start_with_context().await;
+
+    let collection_keypair = Keypair::new();
+    create_collection(&mut context, &collection_keypair, default_collection_args()).await.unwrap();
+
+    let (treasury_account, _) = get_treasury_account();
+    let payer_pubkey = context.payer.pubkey();
+    process_ok(&mut context, light_up_stars_thousand(program_id, collection_keypair.pubkey(), payer_pubkey, treasury_account), &[]).await;
+
+    let collection_account = context.banks_client.get_account(collection_keypair.pubkey()).await.unwrap().unwrap();
+    let account_data = CollectionAccountData::try_from_slice_unchecked(&collection_account.data).unwrap();
+    assert_eq!(account_data.stars, 1000);
+    let balance = context.banks_client.get_balance(treasury_account).await.unwrap();
+    assert_eq!(balance, sol_to_lamports(1.0));
+}
+
+#[test]
+fn test_decode_instruction() {
+    let program_id = id();
+    let collection = Pubkey::new_unique();
+    let authority = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    let (treasury_account, _) = get_treasury_account();
+    let (index_account, _) = get_index_account(&collection, &mint);
+    let (position_account, _) = get_position_account(&collection, 0);
+
+    let parsed = decode_instruction(&light_up_stars_thousand(program_id, collection, authority, treasury_account)).unwrap();
+    assert_eq!(parsed.instruction, CollectionInstruction::LightUpStarsThousand);
+    assert_eq!(parsed.instruction.name(), "LightUpStarsThousand");
+    assert_eq!(
+        parsed.accounts.iter().map(|account| account.name).collect::<Vec<_>>(),
+        vec!["collection", "payer", "treasury", "system_program", "config", "star_receipt", "rent"]
+    );
+    assert_eq!(
+        parsed.accounts[1],
+        ParsedAccount { name: "payer", pubkey: authority, is_signer: true, is_writable: true }
+    );
+
+    // instruction arguments are decoded along with the accounts
+    let parsed = decode_instruction(&create_collection_account(program_id, collection, authority, default_collection_args())).unwrap();
+    assert_eq!(parsed.instruction, CollectionInstruction::CreateCollectionAccount(default_collection_args()));
+    let parsed = decode_instruction(&withdraw(program_id, authority, treasury_account, authority, Some(7))).unwrap();
+    assert_eq!(parsed.instruction, CollectionInstruction::Withdraw { amount: Some(7) });
+
+    // optional accounts are named as well
+    let instruction = with_curator(
+        include_token(program_id, collection, authority, mint, Pubkey::new_unique(), index_account, authority, position_account),
+        collection,
+        authority,
+    );
+    assert_eq!(decode_instruction(&instruction).unwrap().accounts.last().unwrap().name, "curator");
+
+    // every builder produces the documented accounts
+    let mut builders = every_instruction();
+    builders.extend(vec![
+        include_token(program_id, collection, authority, mint, Pubkey::new_unique(), index_account, authority, position_account),
+        close_collection_account(program_id, collection, authority, authority, "tc"),
+        replace_submission(program_id, collection, authority, mint, Pubkey::new_unique(), Pubkey::new_unique()),
+        with_curator_and_authority(
+            update_collection_account_with_symbol(program_id, collection, authority, "tc", UpdateCollectionAccountArgs {
+                symbol: Some("new".to_string()),
+                ..UpdateCollectionAccountArgs::default()
+            }),
+            collection,
+            authority,
+            authority,
+        ),
+        close_index_account(program_id, index_account, authority, authority, collection, position_account, position_account, index_account, Some(mint)),
+    ]);
+    for instruction in builders {
+        let parsed = decode_instruction(&instruction).unwrap();
+        assert!(
+            parsed.accounts.iter().all(|account| account.name != "unknown"),
+            "{} has undocumented accounts",
+            parsed.instruction.name()
+        );
+    }
+
+    // accounts past the documented ones
+    let mut instruction = light_up_stars_once(program_id, collection);
+    instruction.accounts.push(AccountMeta::new_readonly(mint, false));
+    assert_eq!(decode_instruction(&instruction).unwrap().accounts[1].name, "unknown");
+
+    let mut instruction = light_up_stars_hundred(program_id, collection, authority, treasury_account);
+    instruction.accounts.truncate(3);
+    assert_eq!(decode_instruction(&instruction), Err(ProgramError::NotEnoughAccountKeys));
+    instruction.data = vec![255];
+    assert_eq!(decode_instruction(&instruction), Err(ProgramError::InvalidInstructionData));
+    instruction.program_id = Pubkey::new_unique();
+    assert_eq!(decode_instruction(&instruction), Err(ProgramError::IncorrectProgramId));
+}
+
+#[test]
+fn test_decode_events() {
+    let program_id = id().to_string();
+    let collection = Pubkey::new_unique();
+    let authority = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    let events = vec![
+        CollectionEvent::CollectionCreated(CollectionCreated {
+            collection,
+            authority,
+            title: "test collection".to_string(),
+            symbol: "tc".to_string(),
+            max_supply: Some(10),
+        }),
+        CollectionEvent::TokenIncluded(TokenIncluded { collection, mint, index: 0, supply: 1 }),
+        CollectionEvent::StarsLit(StarsLit {
+            collection,
+            supporter: Some(authority),
+            stars: 100,
+            lamports: DEFAULT_STARS_HUNDRED_PRICE,
+            total_stars: 100,
+        }),
+        CollectionEvent::TokenRemoved(TokenRemoved { collection, mint, index: 0, supply: 0 }),
+        CollectionEvent::AccountClosed(AccountClosed {
+            account: collection,
+            account_type: AccountType::CollectionAccount,
+            recipient: authority,
+        }),
+        CollectionEvent::Withdrawn(Withdrawn { treasury_manager: authority, recipient: authority, lamports: 1 }),
+    ];
+    let data = |event: &CollectionEvent| format!("Program data: {}", base64::encode(event.try_to_vec().unwrap()));
+
+    // the logs of a transaction as the runtime writes them, with a CPI into
+    // another program that logs data of its own
+    let system_program = solana_sdk::system_program::id().to_string();
+    let other_program = Pubkey::new_unique().to_string();
+    let mut logs = vec![format!("Program {} invoke [1]", program_id), "Program log: Instruction: Create Collection Account".to_string()];
+    logs.push(format!("Program {} invoke [2]", system_program));
+    logs.push(format!("Program {} success", system_program));
+    logs.push(data(&events[0]));
+    logs.push(format!("Program {} consumed 5000 of 200000 compute units", program_id));
+    logs.push(format!("Program {} success", program_id));
+    logs.push(format!("Program {} invoke [1]", other_program));
+    logs.push("Program data: AQID".to_string());
+    logs.push(format!("Program {} success", other_program));
+    logs.push(format!("Program {} invoke [1]", program_id));
+    logs.push(data(&events[1]));
+    logs.push(data(&events[2]));
+    logs.push(format!("Program {} invoke [2]", other_program));
+    logs.push("Program data: AQID".to_string());
+    logs.push(format!("Program {} success", other_program));
+    logs.extend(events[3..].iter().map(data));
+    logs.push(format!("Program {} success", program_id));
+    logs.push("Program data: AQID".to_string());
+    assert_eq!(decode_events(&logs).unwrap(), events);
+
+    // events of a failed invocation were rolled back, also when the program was
+    // invoked by another one
+    let mut failed_logs = logs.clone();
+    failed_logs.push(format!("Program {} invoke [1]", program_id));
+    failed_logs.push(data(&events[0]));
+    failed_logs.push(format!("Program {} failed: custom program error: 0x1", program_id));
+    failed_logs.push(format!("Program {} invoke [1]", other_program));
+    failed_logs.push(format!("Program {} invoke [2]", program_id));
+    failed_logs.push(data(&events[1]));
+    failed_logs.push(format!("Program {} success", program_id));
+    failed_logs.push(format!("Program {} failed: custom program error: 0x1", other_program));
+    assert_eq!(decode_events(&failed_logs).unwrap(), events);
+
+    // events logged through a successful invocation by another program are kept
+    let mut nested_logs = logs;
+    nested_logs.push(format!("Program {} invoke [1]", other_program));
+    nested_logs.push(format!("Program {} invoke [2]", program_id));
+    nested_logs.push(data(&events[1]));
+    nested_logs.push(format!("Program {} success", program_id));
+    nested_logs.push(format!("Program {} success", other_program));
+    assert_eq!(decode_events(&nested_logs).unwrap().last(), Some(&events[1]));
+
+    // data logged outside of any invocation is not an event of the program
+    assert_eq!(decode_events(&[data(&events[0])]).unwrap(), vec![]);
+
+    // data of the program that is not an event
+    let logs = vec![format!("Program {} invoke [1]", program_id), "Program data: /w==".to_string()];
+    assert!(decode_events(&logs).is_err());
+    let logs = vec![format!("Program {} invoke [1]", program_id), "Program data: !".to_string()];
+    assert!(decode_events(&logs).is_err());
+}
+
+#[tokio::test]
+async fn test_decode_transaction_events() {
+    let program_id = id();
+    let program_test = ProgramTest::new("collection", program_id, collection_processor());
+    let mut context = program_test.start_with_context().await;
+    let payer_pubkey = context.payer.pubkey();
+
+    // the logs of a processed transaction
+    let collection_keypair = Keypair::new();
+    let collection = collection_keypair.pubkey();
+    let ix = create_collection_account(program_id, collection, payer_pubkey, default_collection_args());
+    let mut transaction = Transaction::new_with_payer(
+        &[ix],
+        Some(&payer_pubkey),
+    );
+    transaction.sign(&[&context.payer, &collection_keypair], context.last_blockhash);
+    let result = context.banks_client.process_transaction_with_metadata(transaction).await.unwrap();
+    assert_eq!(result.result, Ok(()));
+    let logs = result.metadata.unwrap().log_messages;
+    let args = default_collection_args();
+    assert_eq!(decode_events(&logs).unwrap(), vec![
+        CollectionEvent::CollectionCreated(CollectionCreated {
+            collection,
+            authority: payer_pubkey,
+            title: args.title,
+            symbol: args.symbol,
+            max_supply: args.max_supply,
+        }),
+    ]);
+
+    // and of a simulated one
+    let (mint, token_account) = create_nft(&mut context).await.unwrap();
+    let (index_account, _) = get_index_account(&collection, &mint.pubkey());
+    let (position_account, _) = get_position_account(&collection, 0);
+    let ix = include_token(
+        program_id,
+        collection,
+        payer_pubkey,
+        mint.pubkey(),
+        token_account,
+        index_account,
+        payer_pubkey,
+        position_account,
+    );
+    let mut transaction = Transaction::new_with_payer(
+        &[ix],
+        Some(&payer_pubkey),
+    );
+    transaction.sign(&[&context.payer], context.last_blockhash);
+    let result = context.banks_client.simulate_transaction(transaction).await.unwrap();
+    assert_eq!(result.result, Some(Ok(())));
+    let logs = result.simulation_details.unwrap().logs;
+    assert_eq!(decode_events(&logs).unwrap(), vec![
+        CollectionEvent::TokenIncluded(TokenIncluded { collection, mint: mint.pubkey(), index: 0, supply: 1 }),
+    ]);
+
+    // migrating a legacy member first logs the supply the collection restarts from
+    let legacy_collection = Pubkey::new_unique();
+    let legacy_data = (
+        AccountType::CollectionAccount,
+        payer_pubkey,
+        "legacy collection".to_string(),
+        "lc".to_string(),
+        "legacy collection description".to_string(),
+        "https://www.google.com".to_string(),
+        5u64,
+        0u64,
+        None::<String>,
+        None::<String>,
+        None::<String>,
+        None::<Vec<String>>,
+    ).try_to_vec().unwrap();
+    let legacy_account = Account {
+        lamports: solana_sdk::rent::Rent::default().minimum_balance(legacy_data.len()),
+        data: legacy_data,
+        owner: program_id,
+        ..Account::default()
+    };
+    context.set_account(&legacy_collection, &legacy_account.into());
+    let legacy_mint = Pubkey::new_unique();
+    let (legacy_index_account, _) = get_legacy_index_account(&legacy_mint);
+    let legacy_index_account_data = Account {
+        lamports: sol_to_lamports(0.01),
+        data: CollectionIndexAccountData::new(legacy_collection, legacy_mint, 3).try_to_vec().unwrap(),
+        owner: program_id,
+        ..Account::default()
+    };
+    context.set_account(&legacy_index_account, &legacy_index_account_data.into());
+    let (index_account, _) = get_index_account(&legacy_collection, &legacy_mint);
+    let (position_account, _) = get_position_account(&legacy_collection, 0);
+    let ix = migrate_index_account(
+        program_id,
+        legacy_collection,
+        payer_pubkey,
+        legacy_index_account,
+        index_account,
+        payer_pubkey,
+        position_account,
+    );
+    let mut transaction = Transaction::new_with_payer(
+        &[ix],
+        Some(&payer_pubkey),
+    );
+    transaction.sign(&[&context.payer], context.last_blockhash);
+    let result = context.banks_client.process_transaction_with_metadata(transaction).await.unwrap();
+    assert_eq!(result.result, Ok(()));
+    let logs = result.metadata.unwrap().log_messages;
+    assert_eq!(decode_events(&logs).unwrap(), vec![
+        CollectionEvent::CollectionMigrated(CollectionMigrated {
+            collection: legacy_collection,
+            version: COLLECTION_ACCOUNT_VERSION,
+            legacy_supply: 5,
+        }),
+        CollectionEvent::TokenIncluded(TokenIncluded {
+            collection: legacy_collection,
+            mint: legacy_mint,
+            index: 0,
+            supply: 1,
+        }),
+    ]);
+}
@@ -0,0 +1,473 @@
+//! Program test helpers shared by the tests of the program and the client.
+
+use solana_program_test::*;
+use solana_program_runtime::{invoke_context::BuiltinFunctionWithContext, log_collector::LogCollector, stable_log};
+use solana_sdk::{
+    account::Account,
+    account_info::AccountInfo,
+    bpf_loader_upgradeable,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    program_stubs::{self, SyscallStubs},
+    program_pack::Pack, 
+    pubkey::Pubkey, 
+    signature::Signer,
+    signer::keypair::Keypair, 
+    system_instruction, 
+    system_program,
+    transaction::Transaction, 
+};
+use spl_associated_token_account::{get_associated_token_address, instruction::create_associated_token_account};
+use collection::instruction::{
+    accept_authority, add_curator, approve_submission, cancel_authority_transfer, close_account,
+    create_collection_account, create_collection_pda,
+    include_token, include_token_by_creator, initialize_config, light_up_stars, light_up_stars_hundred,
+    light_up_stars_once, light_up_stars_thousand, lock_collection, migrate_collection_account,
+    migrate_index_account, propose_authority, reject_submission, remove_curator, remove_token, set_paused,
+    submit_token, update_collection_account, update_config, with_curator, with_curator_and_authority, withdraw, ConfigArgs,
+    CreateCollectionAccountArgs, UpdateCollectionAccountArgs,
+};
+use collection::state::{AccountType, FieldLimits, CURATOR_ALL, DEFAULT_PRICE_PER_STAR, DEFAULT_STARS_HUNDRED_PRICE, DEFAULT_STARS_THOUSAND_PRICE};
+use collection::utils::{get_index_account, get_metadata_account, get_position_account, get_treasury_account};
+use collection::metadata::{self, Creator, Data};
+use collection::processor::process_instruction;
+use borsh::BorshSerialize;
+use std::{cell::RefCell, rc::Rc, sync::{Once, RwLock}};
+
+pub async fn create_mint(
+    context: &mut ProgramTestContext,
+    mint: &Keypair,
+    manager: &Pubkey,
+    decimals: u8,
+    freeze_authority: Option<&Pubkey>,
+) -> Result<(), BanksClientError> {
+    let rent = context.banks_client.get_rent().await.unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            system_instruction::create_account(
+                &context.payer.pubkey(),
+                &mint.pubkey(),
+                rent.minimum_balance(spl_token::state::Mint::LEN),
+                spl_token::state::Mint::LEN as u64,
+                &spl_token::id(),
+            ),
+            spl_token::instruction::initialize_mint(
+                &spl_token::id(),
+                &mint.pubkey(),
+                manager,
+                freeze_authority,
+                decimals,
+            )
+            .unwrap(),
+        ],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, mint],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await
+}
+
+pub async fn mint_tokens(
+    context: &mut ProgramTestContext,
+    mint: &Pubkey,
+    account: &Pubkey,
+    amount: u64,
+    owner: &Pubkey,
+    additional_signer: Option<&Keypair>,
+) -> Result<(), BanksClientError> {
+    let mut signing_keypairs = vec![&context.payer];
+    if let Some(signer) = additional_signer {
+        signing_keypairs.push(signer);
+    }
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            spl_token::instruction::mint_to(&spl_token::id(), mint, account, owner, &[], amount)
+                .unwrap(),
+        ],
+        Some(&context.payer.pubkey()),
+        &signing_keypairs,
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await
+}
+
+pub async fn create_associated_account(
+    context: &mut ProgramTestContext,
+    wallet_address: &Pubkey,
+    mint_address: &Pubkey,
+) -> Result<(), BanksClientError> {
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            create_associated_token_account(
+                &context.payer.pubkey(),
+                wallet_address,
+                mint_address,
+                &spl_token::id(),
+            )
+        ],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await
+}
+
+pub fn default_collection_args() -> CreateCollectionAccountArgs {
+    CreateCollectionAccountArgs{
+        title: "test collection".to_string(),
+        symbol: "tc".to_string(),
+        description: "test collection description".to_string(),
+        icon_image: "https://www.google.com".to_string(),
+        header_image: Some("www.solana.com".to_string()),
+        short_description: Some("www.solana.com".to_string()),
+        banner: Some("www.solana.com".to_string()),
+        tags: Some(vec!["art".to_string(), "music".to_string()]),
+        max_supply: None,
+    }
+}
+
+pub async fn create_collection(
+    context: &mut ProgramTestContext,
+    collection: &Keypair,
+    args: CreateCollectionAccountArgs,
+) -> Result<(), BanksClientError> {
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            create_collection_account(
+                collection::id(),
+                collection.pubkey(),
+                context.payer.pubkey(),
+                args,
+            )
+        ],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, collection],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await
+}
+
+/// Mints a single token with zero decimals to the associated account of the payer.
+pub async fn create_nft(
+    context: &mut ProgramTestContext,
+) -> Result<(Keypair, Pubkey), BanksClientError> {
+    let mint = Keypair::new();
+    let payer_pubkey = context.payer.pubkey();
+    create_mint(context, &mint, &payer_pubkey, 0, Some(&payer_pubkey)).await?;
+    create_associated_account(context, &payer_pubkey, &mint.pubkey()).await?;
+
+    let token_account = get_associated_token_address(&payer_pubkey, &mint.pubkey());
+    mint_tokens(context, &mint.pubkey(), &token_account, 1, &payer_pubkey, None).await?;
+    Ok((mint, token_account))
+}
+
+/// Includes a token owned by the payer into `position` of a collection created by the payer.
+pub async fn include_nft(
+    context: &mut ProgramTestContext,
+    collection: &Pubkey,
+    mint: &Pubkey,
+    token_account: &Pubkey,
+    position: u64,
+) -> Result<(), BanksClientError> {
+    let (index_account, _) = get_index_account(collection, mint);
+    let (position_account, _) = get_position_account(collection, position);
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            include_token(
+                collection::id(),
+                *collection,
+                context.payer.pubkey(),
+                *mint,
+                *token_account,
+                index_account,
+                context.payer.pubkey(),
+                position_account,
+            )
+        ],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await
+}
+
+/// Adds the upgradeable loader program data account of the collection program,
+/// so `InitializeConfig` can check the upgrade authority under `processor!`.
+pub fn add_program_data_account(program_test: &mut ProgramTest, upgrade_authority: &Pubkey) {
+    let program_id = collection::id();
+    let (program_data_account, _) = Pubkey::find_program_address(
+        &[program_id.as_ref()],
+        &bpf_loader_upgradeable::id(),
+    );
+    // bincode layout of `UpgradeableLoaderState::ProgramData`
+    let mut data = vec![3, 0, 0, 0];
+    data.extend_from_slice(&0u64.to_le_bytes());
+    data.push(1);
+    data.extend_from_slice(upgrade_authority.as_ref());
+    program_test.add_account(
+        program_data_account,
+        Account {
+            lamports: 1_000_000_000,
+            data,
+            owner: bpf_loader_upgradeable::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+}
+
+thread_local! {
+    // log collector of the innermost invocation of the collection program
+    static LOG_COLLECTOR: RefCell<Option<Rc<RefCell<LogCollector>>>> = const { RefCell::new(None) };
+}
+
+// syscall stubs of solana-program-test, wrapped by `ProgramDataStubs`
+static PROGRAM_TEST_STUBS: RwLock<Option<Box<dyn SyscallStubs>>> = RwLock::new(None);
+
+/// Syscall stubs of solana-program-test that write what `sol_log_data` logs to
+/// the transaction logs instead of printing it.
+struct ProgramDataStubs;
+
+impl ProgramDataStubs {
+    fn with<R>(f: impl FnOnce(&dyn SyscallStubs) -> R) -> R {
+        f(PROGRAM_TEST_STUBS.read().unwrap().as_deref().expect("program test stubs"))
+    }
+}
+
+impl SyscallStubs for ProgramDataStubs {
+    fn sol_log(&self, message: &str) {
+        Self::with(|stubs| stubs.sol_log(message))
+    }
+    fn sol_log_compute_units(&self) {
+        Self::with(|stubs| stubs.sol_log_compute_units())
+    }
+    fn sol_remaining_compute_units(&self) -> u64 {
+        Self::with(|stubs| stubs.sol_remaining_compute_units())
+    }
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        Self::with(|stubs| stubs.sol_invoke_signed(instruction, account_infos, signers_seeds))
+    }
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        Self::with(|stubs| stubs.sol_get_clock_sysvar(var_addr))
+    }
+    fn sol_get_epoch_schedule_sysvar(&self, var_addr: *mut u8) -> u64 {
+        Self::with(|stubs| stubs.sol_get_epoch_schedule_sysvar(var_addr))
+    }
+    fn sol_get_fees_sysvar(&self, var_addr: *mut u8) -> u64 {
+        Self::with(|stubs| stubs.sol_get_fees_sysvar(var_addr))
+    }
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        Self::with(|stubs| stubs.sol_get_rent_sysvar(var_addr))
+    }
+    fn sol_get_epoch_rewards_sysvar(&self, var_addr: *mut u8) -> u64 {
+        Self::with(|stubs| stubs.sol_get_epoch_rewards_sysvar(var_addr))
+    }
+    fn sol_get_last_restart_slot(&self, var_addr: *mut u8) -> u64 {
+        Self::with(|stubs| stubs.sol_get_last_restart_slot(var_addr))
+    }
+    unsafe fn sol_memcpy(&self, dst: *mut u8, src: *const u8, n: usize) {
+        Self::with(|stubs| stubs.sol_memcpy(dst, src, n))
+    }
+    unsafe fn sol_memmove(&self, dst: *mut u8, src: *const u8, n: usize) {
+        Self::with(|stubs| stubs.sol_memmove(dst, src, n))
+    }
+    unsafe fn sol_memcmp(&self, s1: *const u8, s2: *const u8, n: usize, result: *mut i32) {
+        Self::with(|stubs| stubs.sol_memcmp(s1, s2, n, result))
+    }
+    unsafe fn sol_memset(&self, s: *mut u8, c: u8, n: usize) {
+        Self::with(|stubs| stubs.sol_memset(s, c, n))
+    }
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        Self::with(|stubs| stubs.sol_get_return_data())
+    }
+    fn sol_set_return_data(&self, data: &[u8]) {
+        Self::with(|stubs| stubs.sol_set_return_data(data))
+    }
+    fn sol_log_data(&self, fields: &[&[u8]]) {
+        LOG_COLLECTOR.with(|log_collector| match &*log_collector.borrow() {
+            Some(log_collector) => stable_log::program_data(&Some(log_collector.clone()), fields),
+            None => Self::with(|stubs| stubs.sol_log_data(fields)),
+        })
+    }
+    fn sol_get_processed_sibling_instruction(&self, index: usize) -> Option<Instruction> {
+        Self::with(|stubs| stubs.sol_get_processed_sibling_instruction(index))
+    }
+    fn sol_get_stack_height(&self) -> u64 {
+        Self::with(|stubs| stubs.sol_get_stack_height())
+    }
+}
+
+/// `processor!(process_instruction)` of the collection program that logs its
+/// events to the transaction logs like on chain, the stubs of
+/// solana-program-test only print what `sol_log_data` logs.
+pub fn collection_processor() -> Option<BuiltinFunctionWithContext> {
+    Some(|vm, _arg0, _arg1, _arg2, _arg3, _arg4| {
+        static WRAP_STUBS: Once = Once::new();
+        // solana-program-test installed its stubs while setting up the bank
+        WRAP_STUBS.call_once(|| {
+            let mut program_test_stubs = PROGRAM_TEST_STUBS.write().unwrap();
+            *program_test_stubs = Some(program_stubs::set_syscall_stubs(Box::new(ProgramDataStubs)));
+        });
+        let vm = unsafe {
+            &mut *((vm as *mut u64).offset(-(get_runtime_environment_key() as isize))
+                as *mut EbpfVm<InvokeContext>)
+        };
+        let log_collector = vm.context_object_pointer.get_log_collector();
+        let outer_log_collector = LOG_COLLECTOR.with(|current| current.replace(log_collector));
+        vm.program_result = invoke_builtin_function(process_instruction, vm.context_object_pointer)
+            .map_err(EbpfError::SyscallError)
+            .into();
+        LOG_COLLECTOR.with(|current| current.replace(outer_log_collector));
+    })
+}
+
+/// Loads the token metadata program from `tests/fixtures/mpl_token_metadata.so`,
+/// dumped from mainnet with
+/// `solana program dump -um metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s tests/fixtures/mpl_token_metadata.so`.
+pub fn add_token_metadata_program(program_test: &mut ProgramTest) {
+    program_test.add_program("mpl_token_metadata", metadata::program::id(), None);
+}
+
+/// `CreateMetadataAccountV3` of the token metadata program with the payer as
+/// update authority, creators start out unverified and verify themselves with
+/// `sign_metadata`.
+pub fn create_metadata_accounts(
+    mint: &Pubkey,
+    mint_authority: &Pubkey,
+    payer: &Pubkey,
+    creators: Vec<Creator>,
+) -> Instruction {
+    let data = Data {
+        name: "test nft".to_string(),
+        symbol: "tc".to_string(),
+        uri: "https://www.solana.com".to_string(),
+        seller_fee_basis_points: 500,
+        creators: Some(creators),
+    };
+    // instruction tag, data, no collection, no uses, mutable, no collection details
+    let data = (33u8, data, None::<u8>, None::<u8>, true, None::<u8>).try_to_vec().unwrap();
+    Instruction {
+        program_id: metadata::program::id(),
+        accounts: vec![
+            AccountMeta::new(get_metadata_account(mint).0, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(*mint_authority, true),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(*payer, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data,
+    }
+}
+
+/// `SignMetadata` of the token metadata program, verifies `creator` on the
+/// metadata of `mint`.
+pub fn sign_metadata(mint: &Pubkey, creator: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: metadata::program::id(),
+        accounts: vec![
+            AccountMeta::new(get_metadata_account(mint).0, false),
+            AccountMeta::new_readonly(*creator, true),
+        ],
+        data: vec![7],
+    }
+}
+
+pub async fn create_config(
+    context: &mut ProgramTestContext,
+    upgrade_authority: &Keypair,
+    args: ConfigArgs,
+) -> Result<(), BanksClientError> {
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            initialize_config(
+                collection::id(),
+                upgrade_authority.pubkey(),
+                context.payer.pubkey(),
+                args,
+            )
+        ],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, upgrade_authority],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await
+}
+
+/// One instruction of every `CollectionInstruction` variant, in variant order, made
+/// by its builder with every optional account included.
+pub fn every_instruction() -> Vec<Instruction> {
+    let program_id = collection::id();
+    let collection = Pubkey::new_unique();
+    let authority = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    let token_account = Pubkey::new_unique();
+    let (treasury_account, _) = get_treasury_account();
+    let (index_account, _) = get_index_account(&collection, &mint);
+    let (position_account, _) = get_position_account(&collection, 0);
+    let config_args = ConfigArgs {
+        admin: authority,
+        treasury_manager: authority,
+        stars_hundred_price: DEFAULT_STARS_HUNDRED_PRICE,
+        stars_thousand_price: DEFAULT_STARS_THOUSAND_PRICE,
+        field_limits: FieldLimits::default(),
+        price_per_star: DEFAULT_PRICE_PER_STAR,
+    };
+    let update_args = UpdateCollectionAccountArgs {
+        banner: Some("www.solana.com".to_string()),
+        max_supply: Some(10),
+        ..UpdateCollectionAccountArgs::default()
+    };
+    vec![
+        create_collection_account(program_id, collection, authority, default_collection_args()),
+        with_curator(
+            include_token(program_id, collection, authority, mint, token_account, index_account, authority, position_account),
+            collection,
+            authority,
+        ),
+        light_up_stars_once(program_id, collection),
+        light_up_stars_hundred(program_id, collection, authority, treasury_account),
+        light_up_stars_thousand(program_id, collection, authority, treasury_account),
+        close_account(program_id, collection, authority, authority, AccountType::CuratorAccount),
+        withdraw(program_id, authority, treasury_account, authority, Some(1)),
+        with_curator_and_authority(update_collection_account(program_id, collection, authority, update_args), collection, authority, authority),
+        propose_authority(program_id, collection, authority, mint),
+        accept_authority(program_id, collection, authority),
+        cancel_authority_transfer(program_id, collection, authority),
+        with_curator(
+            remove_token(program_id, collection, authority, index_account, authority, position_account, position_account, index_account),
+            collection,
+            authority,
+        ),
+        migrate_index_account(program_id, collection, authority, Pubkey::new_unique(), index_account, authority, position_account),
+        initialize_config(program_id, authority, authority, config_args.clone()),
+        update_config(program_id, authority, config_args),
+        light_up_stars(program_id, collection, authority, treasury_account, 5),
+        set_paused(program_id, authority, true, false),
+        create_collection_pda(program_id, authority, "slug".to_string(), default_collection_args()),
+        include_token_by_creator(program_id, collection, authority, mint, index_account, authority, position_account),
+        submit_token(program_id, collection, authority, mint, token_account),
+        with_curator(
+            approve_submission(program_id, collection, authority, mint, authority, token_account, index_account, authority, position_account),
+            collection,
+            authority,
+        ),
+        with_curator(reject_submission(program_id, collection, authority, mint, authority), collection, authority),
+        add_curator(program_id, collection, authority, mint, authority, CURATOR_ALL),
+        remove_curator(program_id, collection, authority, mint, authority),
+        lock_collection(program_id, collection, authority, true, false),
+        migrate_collection_account(program_id, collection, authority),
+    ]
+}